@@ -0,0 +1,976 @@
+//! Boolean operations (union, intersection, difference and xor) between two paths.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate lyon_algorithms;
+//! # use lyon_algorithms::path::Path;
+//! # use lyon_algorithms::path::builder::*;
+//! # use lyon_algorithms::math::point;
+//! use lyon_algorithms::boolean::{boolean_op, BooleanOp, BooleanOptions};
+//! # fn main() {
+//! let mut a = Path::builder();
+//! a.polygon(&[point(0.0, 0.0), point(2.0, 0.0), point(2.0, 2.0), point(0.0, 2.0)]);
+//! let a = a.build();
+//!
+//! let mut b = Path::builder();
+//! b.polygon(&[point(1.0, 1.0), point(3.0, 1.0), point(3.0, 3.0), point(1.0, 3.0)]);
+//! let b = b.build();
+//!
+//! // The union of the two squares.
+//! let union = boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Union, &BooleanOptions::default());
+//! # let _ = union;
+//! # }
+//! ```
+//!
+//! Curves are flattened before the operation is applied. The parts of the result that follow
+//! a single curve of the input paths are then converted back into curves, and the rest of the
+//! result is made of line segments.
+//!
+//! The sub-paths of the resulting path don't overlap and are oriented consistently: regions
+//! that are inside of the result have a winding number of +1, so the result can be filled with
+//! any of the fill rules.
+
+use crate::math::*;
+use crate::geom::{BezierSegment, LineSegment};
+use crate::path::{Path, PathSlice, PathEvent, FillRule, Builder};
+use crate::hit_test::winding_numbers;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::cmp::Ordering;
+use std::f32;
+
+/// The operation to apply between two paths.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum BooleanOp {
+    /// Regions that are inside of either path.
+    Union,
+    /// Regions that are inside of both paths.
+    Intersection,
+    /// Regions that are inside of the first path but not the second one.
+    Difference,
+    /// Regions that are inside of exactly one of the paths.
+    Xor,
+}
+
+impl BooleanOp {
+    #[inline]
+    fn is_in(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            BooleanOp::Union => in_a || in_b,
+            BooleanOp::Intersection => in_a && in_b,
+            BooleanOp::Difference => in_a && !in_b,
+            BooleanOp::Xor => in_a != in_b,
+        }
+    }
+}
+
+/// Parameters for boolean operations.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct BooleanOptions {
+    /// Maximum allowed distance to the path when building an approximation.
+    ///
+    /// Default value: `BooleanOptions::DEFAULT_TOLERANCE`.
+    pub tolerance: f32,
+
+    /// The fill rule used to determine what is inside of each of the input paths.
+    ///
+    /// Default value: `EvenOdd`.
+    pub fill_rule: FillRule,
+
+    // To be able to add fields without making it a breaking change, add an empty private field
+    // which makes it impossible to create a BooleanOptions without calling the constructor.
+    _private: (),
+}
+
+impl Default for BooleanOptions {
+    fn default() -> Self { Self::DEFAULT }
+}
+
+impl BooleanOptions {
+    /// Default flattening tolerance.
+    pub const DEFAULT_TOLERANCE: f32 = 0.1;
+    /// Default fill rule.
+    pub const DEFAULT_FILL_RULE: FillRule = FillRule::EvenOdd;
+
+    pub const DEFAULT: Self = BooleanOptions {
+        tolerance: Self::DEFAULT_TOLERANCE,
+        fill_rule: Self::DEFAULT_FILL_RULE,
+        _private: (),
+    };
+
+    #[inline]
+    pub fn tolerance(tolerance: f32) -> Self {
+        Self::DEFAULT.with_tolerance(tolerance)
+    }
+
+    #[inline]
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    #[inline]
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+}
+
+/// Applies a boolean operation between two paths and returns the resulting path.
+///
+/// Curves are flattened, and the parts of the result that follow a single curve of the
+/// input paths are converted back into curves.
+pub fn boolean_op(a: PathSlice, b: PathSlice, op: BooleanOp, options: &BooleanOptions) -> Path {
+    // Distance under which two points are considered equal.
+    let threshold = options.tolerance * 0.001;
+
+    let mut vertices = VertexMap::new(threshold);
+    let mut curves = Vec::new();
+    let mut edges = Vec::new();
+    add_edges(a, 0, options.tolerance, &mut vertices, &mut curves, &mut edges);
+    add_edges(b, 1, options.tolerance, &mut vertices, &mut curves, &mut edges);
+
+    // Merging the intersections with the nearby vertices moves them slightly, which can
+    // create new intersections, so the edges are split again until nothing changes.
+    for _ in 0..MAX_SPLIT_PASSES {
+        if !split_edges(&mut edges, &mut vertices) {
+            break;
+        }
+    }
+
+    let boundary = select_boundary_edges(&edges, &vertices.points, op, options.fill_rule);
+
+    build_path(&boundary, &vertices.points, &curves, threshold)
+}
+
+/// Maximum number of times the edges are split at their intersections.
+const MAX_SPLIT_PASSES: u32 = 8;
+
+/// Maximum number of times a curve is subdivided when it is flattened.
+const MAX_FLATTENING_DEPTH: u32 = 16;
+
+/// An edge of the planar graph formed by the two paths.
+///
+/// Edges are oriented downwards, or towards the negative x direction if they are horizontal.
+/// The winding contains the contribution of the edge to the winding number of each operand.
+#[derive(Copy, Clone, Debug)]
+struct Edge {
+    from: usize,
+    to: usize,
+    winding: [i32; 2],
+    curve: Option<CurveRange>,
+}
+
+impl Edge {
+    /// Creates an edge going from one vertex to another with the provided winding, and
+    /// orients it.
+    fn new(from: usize, to: usize, winding: [i32; 2], curve: Option<CurveRange>, points: &[Point]) -> Self {
+        if is_before(points[from], points[to]) {
+            Edge { from, to, winding, curve }
+        } else {
+            Edge {
+                from: to,
+                to: from,
+                winding: [-winding[0], -winding[1]],
+                curve: curve.map(CurveRange::flip),
+            }
+        }
+    }
+}
+
+/// The part of a curve of the input paths that an edge approximates, from the curve
+/// parameter at the start of the edge to the one at its end.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct CurveRange {
+    curve: usize,
+    from: f32,
+    to: f32,
+}
+
+impl CurveRange {
+    fn flip(self) -> Self {
+        CurveRange { curve: self.curve, from: self.to, to: self.from }
+    }
+
+    /// The curve parameter at a position along the edge.
+    fn at(&self, t: f32) -> f32 {
+        self.from + (self.to - self.from) * t
+    }
+
+    /// Whether the other range continues this one along the same curve.
+    fn is_continued_by(&self, other: &CurveRange) -> bool {
+        self.curve == other.curve
+            && self.to == other.from
+            && (self.to - self.from) * (other.to - other.from) > 0.0
+    }
+}
+
+/// Stores the vertices, merging the ones that are closer than a threshold.
+struct VertexMap {
+    points: Vec<Point>,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    threshold: f32,
+}
+
+impl VertexMap {
+    fn new(threshold: f32) -> Self {
+        VertexMap {
+            points: Vec::new(),
+            cells: HashMap::new(),
+            threshold,
+        }
+    }
+
+    fn cell(&self, p: Point) -> (i64, i64) {
+        ((p.x / self.threshold).floor() as i64, (p.y / self.threshold).floor() as i64)
+    }
+
+    fn add(&mut self, p: Point) -> usize {
+        let (cx, cy) = self.cell(p);
+        for x in cx - 1 ..= cx + 1 {
+            for y in cy - 1 ..= cy + 1 {
+                if let Some(ids) = self.cells.get(&(x, y)) {
+                    for &id in ids {
+                        let other = self.points[id];
+                        if (other.x - p.x).abs() <= self.threshold
+                            && (other.y - p.y).abs() <= self.threshold {
+                            return id;
+                        }
+                    }
+                }
+            }
+        }
+
+        let id = self.points.len();
+        self.points.push(p);
+        self.cells.entry((cx, cy)).or_default().push(id);
+
+        id
+    }
+}
+
+fn add_edges(
+    path: PathSlice,
+    operand: usize,
+    tolerance: f32,
+    vertices: &mut VertexMap,
+    curves: &mut Vec<BezierSegment<f32>>,
+    edges: &mut Vec<Edge>,
+) {
+    let mut winding = [0, 0];
+    winding[operand] = 1;
+    let add_edge = |from: Option<usize>, to: usize, curve, points: &[Point], edges: &mut Vec<Edge>| {
+        if let Some(from) = from {
+            if from != to {
+                edges.push(Edge::new(from, to, winding, curve, points));
+            }
+        }
+    };
+
+    let mut first = None;
+    let mut current = None;
+    for evt in path.iter() {
+        let curve = match evt {
+            PathEvent::MoveTo(to) => {
+                // Sub-paths are implicitly closed.
+                if let Some(first) = first {
+                    add_edge(current, first, None, &vertices.points, edges);
+                }
+                let id = vertices.add(to);
+                first = Some(id);
+                current = Some(id);
+                continue;
+            }
+            PathEvent::Line(segment) => {
+                let id = vertices.add(segment.to);
+                add_edge(current, id, None, &vertices.points, edges);
+                current = Some(id);
+                continue;
+            }
+            PathEvent::Close(..) => {
+                if let Some(first) = first {
+                    add_edge(current, first, None, &vertices.points, edges);
+                }
+                current = first;
+                continue;
+            }
+            PathEvent::Quadratic(segment) => BezierSegment::Quadratic(segment),
+            PathEvent::Cubic(segment) => BezierSegment::Cubic(segment),
+        };
+
+        let idx = curves.len();
+        curves.push(curve);
+        let mut t0 = 0.0;
+        flatten_curve(&curve, 0.0, 1.0, MAX_FLATTENING_DEPTH, tolerance, &mut |t, p| {
+            let id = vertices.add(p);
+            let range = CurveRange { curve: idx, from: t0, to: t };
+            add_edge(current, id, Some(range), &vertices.points, edges);
+            current = Some(id);
+            t0 = t;
+        });
+    }
+
+    if let Some(first) = first {
+        add_edge(current, first, None, &vertices.points, edges);
+    }
+}
+
+/// Flattens the part of a curve between two parameters by subdividing it until the pieces
+/// are flat enough, invoking the callback with the parameter and the position at the end of
+/// each piece.
+fn flatten_curve(
+    curve: &BezierSegment<f32>,
+    t0: f32,
+    t1: f32,
+    depth: u32,
+    tolerance: f32,
+    cb: &mut dyn FnMut(f32, Point),
+) {
+    let piece = sub_curve(curve, t0, t1);
+    if depth == 0 || is_flat(&piece, tolerance) {
+        cb(t1, piece.to());
+        return;
+    }
+
+    let t = (t0 + t1) * 0.5;
+    flatten_curve(curve, t0, t, depth - 1, tolerance, cb);
+    flatten_curve(curve, t, t1, depth - 1, tolerance, cb);
+}
+
+fn sub_curve(curve: &BezierSegment<f32>, t0: f32, t1: f32) -> BezierSegment<f32> {
+    match *curve {
+        BezierSegment::Linear(segment) => BezierSegment::Linear(segment.split_range(t0..t1)),
+        BezierSegment::Quadratic(segment) => BezierSegment::Quadratic(segment.split_range(t0..t1)),
+        BezierSegment::Cubic(segment) => BezierSegment::Cubic(segment.split_range(t0..t1)),
+    }
+}
+
+/// Whether the curve is close enough to the segment between its end points, including
+/// when it is smaller than the tolerance.
+fn is_flat(curve: &BezierSegment<f32>, tolerance: f32) -> bool {
+    let near = |a: Point, b: Point| (a - b).square_length() <= tolerance * tolerance;
+    let small = match *curve {
+        BezierSegment::Linear(..) => true,
+        BezierSegment::Quadratic(s) => near(s.from, s.ctrl) && near(s.from, s.to),
+        BezierSegment::Cubic(s) => near(s.from, s.ctrl1) && near(s.from, s.ctrl2) && near(s.from, s.to),
+    };
+
+    small || curve.is_linear(tolerance)
+}
+
+/// Splits the edges at their intersections and merges the overlapping ones.
+///
+/// Returns whether any edge was split.
+fn split_edges(edges: &mut Vec<Edge>, vertices: &mut VertexMap) -> bool {
+    let threshold = vertices.threshold;
+
+    // Position along the edge and vertex id of the points where each edge must be split.
+    let mut splits: Vec<Vec<(f32, usize)>> = edges.iter().map(|_| Vec::new()).collect();
+
+    // Only test pairs of edges which overlap on the x axis.
+    let mut sorted: Vec<usize> = (0..edges.len()).collect();
+    let range_x = |e: &Edge, points: &[Point]| {
+        let a = points[e.from].x;
+        let b = points[e.to].x;
+        (a.min(b) - threshold, a.max(b) + threshold)
+    };
+    sorted.sort_by(|a, b| {
+        let a = range_x(&edges[*a], &vertices.points).0;
+        let b = range_x(&edges[*b], &vertices.points).0;
+        a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+    });
+
+    for (i, &idx1) in sorted.iter().enumerate() {
+        let e1 = &edges[idx1];
+        let max_x = range_x(e1, &vertices.points).1;
+        for &idx2 in &sorted[i + 1 ..] {
+            let e2 = &edges[idx2];
+            let (min_x2, _) = range_x(e2, &vertices.points);
+            if min_x2 > max_x {
+                break;
+            }
+
+            // T-junctions and overlapping edges.
+            for &(e, other, idx) in &[(e1, e2, idx1), (e2, e1, idx2)] {
+                for &endpoint in &[other.from, other.to] {
+                    if endpoint == e.from || endpoint == e.to {
+                        continue;
+                    }
+                    let from = vertices.points[e.from];
+                    let to = vertices.points[e.to];
+                    if let Some(t) = point_on_segment(vertices.points[endpoint], from, to, threshold) {
+                        splits[idx].push((t, endpoint));
+                    }
+                }
+            }
+
+            if let Some((t1, t2, position)) = edge_intersection(e1, e2, &vertices.points) {
+                let id = vertices.add(position);
+                if id != e1.from && id != e1.to {
+                    splits[idx1].push((t1, id));
+                }
+                if id != e2.from && id != e2.to {
+                    splits[idx2].push((t2, id));
+                }
+            }
+        }
+    }
+
+    let split = splits.iter().any(|s| !s.is_empty());
+
+    // Split the edges and accumulate the winding of identical edges.
+    let mut edge_map: HashMap<(usize, usize), Edge> = HashMap::new();
+    for (edge, edge_splits) in edges.iter().zip(splits.iter_mut()) {
+        edge_splits.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+        let mut from = edge.from;
+        let mut t0 = 0.0;
+        let split_points = edge_splits.iter().cloned().chain(Some((1.0, edge.to)));
+        for (t, to) in split_points {
+            if to == from {
+                continue;
+            }
+
+            let curve = edge.curve.map(|c| CurveRange { curve: c.curve, from: c.at(t0), to: c.at(t) });
+            let piece = Edge::new(from, to, edge.winding, curve, &vertices.points);
+            match edge_map.entry((piece.from, piece.to)) {
+                Entry::Vacant(entry) => {
+                    entry.insert(piece);
+                }
+                Entry::Occupied(mut entry) => {
+                    let existing = entry.get_mut();
+                    existing.winding[0] += piece.winding[0];
+                    existing.winding[1] += piece.winding[1];
+                    if existing.curve != piece.curve {
+                        existing.curve = None;
+                    }
+                }
+            }
+
+            from = to;
+            t0 = t;
+        }
+    }
+
+    edges.clear();
+    edges.extend(edge_map.into_iter().map(|(_, edge)| edge)
+        .filter(|edge| edge.winding != [0, 0])
+    );
+
+    // Keep the output deterministic.
+    edges.sort_by_key(|edge| (edge.from, edge.to));
+
+    split
+}
+
+/// The order in which the edges are oriented: downwards, and towards the negative x
+/// direction for horizontal edges.
+#[inline]
+fn is_before(a: Point, b: Point) -> bool {
+    a.y < b.y || (a.y == b.y && a.x > b.x)
+}
+
+/// If the point is on the interior of the segment, returns its position along the segment.
+fn point_on_segment(p: Point, from: Point, to: Point, threshold: f32) -> Option<f32> {
+    let v = to - from;
+    let sq_length = v.square_length();
+    if sq_length == 0.0 {
+        return None;
+    }
+
+    let t = (p - from).dot(v) / sq_length;
+    if t <= 0.0 || t >= 1.0 {
+        return None;
+    }
+
+    let projection = from + v * t;
+    if (projection - p).square_length() > threshold * threshold {
+        return None;
+    }
+
+    Some(t)
+}
+
+/// Computes the proper intersection of two edges if any.
+///
+/// The intersection is computed with double precision.
+fn edge_intersection(e1: &Edge, e2: &Edge, points: &[Point]) -> Option<(f32, f32, Point)> {
+    if e1.from == e2.from || e1.from == e2.to || e1.to == e2.from || e1.to == e2.to {
+        return None;
+    }
+
+    let a1 = points[e1.from].to_f64();
+    let a2 = points[e1.to].to_f64();
+    let b1 = points[e2.from].to_f64();
+    let b2 = points[e2.to].to_f64();
+
+    let v1 = a2 - a1;
+    let v2 = b2 - b1;
+    let denom = v1.cross(v2);
+    if denom == 0.0 {
+        // Parallel edges are handled as T-junctions.
+        return None;
+    }
+
+    let v3 = b1 - a1;
+    let t = v3.cross(v2) / denom;
+    let u = v3.cross(v1) / denom;
+
+    if t <= 0.0 || t >= 1.0 || u <= 0.0 || u >= 1.0 {
+        return None;
+    }
+
+    let position = a1 + v1 * t;
+
+    Some((t as f32, u as f32, point(position.x as f32, position.y as f32)))
+}
+
+/// An edge of the result, going from one vertex to another.
+type BoundaryEdge = (usize, usize, Option<CurveRange>);
+
+/// Returns the edges that separate regions inside of the result from regions outside
+/// of it, oriented so that the inside is on their left (with the y axis pointing down).
+fn select_boundary_edges(
+    edges: &[Edge],
+    points: &[Point],
+    op: BooleanOp,
+    fill_rule: FillRule,
+) -> Vec<BoundaryEdge> {
+    let is_in = |winding: [i32; 2]| {
+        op.is_in(
+            fill_rule.is_in(winding[0] as i16),
+            fill_rule.is_in(winding[1] as i16),
+        )
+    };
+
+    // The winding numbers on the left of non-horizontal edges, computed with a ray
+    // going towards the negative x direction.
+    let mut segments = Vec::new();
+    let mut positions = Vec::new();
+    let mut queried = Vec::new();
+    for (idx, edge) in edges.iter().enumerate() {
+        let from = points[edge.from];
+        let to = points[edge.to];
+        if from.y != to.y {
+            positions.push((from.lerp(to, 0.5), Some(segments.len())));
+            segments.push((LineSegment { from, to }, edge.winding));
+            queried.push(idx);
+        }
+    }
+    let mut left_windings = vec![[0, 0]; edges.len()];
+    for (idx, winding) in queried.iter().zip(operand_winding_numbers(&segments, &positions)) {
+        left_windings[*idx] = winding;
+    }
+
+    // The winding numbers above horizontal edges, computed with a ray going towards the
+    // negative y direction. The problem is transposed so that it can use the same code.
+    segments.clear();
+    positions.clear();
+    queried.clear();
+    for (idx, edge) in edges.iter().enumerate() {
+        let from = points[edge.from];
+        let to = points[edge.to];
+        if from.x != to.x {
+            // With a vertical ray, edges going towards the negative x direction contribute
+            // positively to the winding number.
+            let sign = if to.x < from.x { 1 } else { -1 };
+            let (from, to) = if from.x < to.x { (from, to) } else { (to, from) };
+            if edge_is_horizontal(edge, points) {
+                let mid = from.lerp(to, 0.5);
+                positions.push((point(mid.y, mid.x), Some(segments.len())));
+                queried.push(idx);
+            }
+            segments.push((
+                LineSegment { from: point(from.y, from.x), to: point(to.y, to.x) },
+                [edge.winding[0] * sign, edge.winding[1] * sign],
+            ));
+        }
+    }
+    let mut above_windings = vec![[0, 0]; edges.len()];
+    for (idx, winding) in queried.iter().zip(operand_winding_numbers(&segments, &positions)) {
+        above_windings[*idx] = winding;
+    }
+
+    let mut output = Vec::new();
+    for (idx, edge) in edges.iter().enumerate() {
+        let (before, after) = if edge_is_horizontal(edge, points) {
+            // Edges going towards the negative x direction have the region below on their left.
+            let above = above_windings[idx];
+            let below = [above[0] + edge.winding[0], above[1] + edge.winding[1]];
+            (is_in(above), is_in(below))
+        } else {
+            // Downward edges have the region towards the positive x direction on their left.
+            let left = left_windings[idx];
+            let right = [left[0] + edge.winding[0], left[1] + edge.winding[1]];
+            (is_in(left), is_in(right))
+        };
+
+        if before == after {
+            continue;
+        }
+
+        if after {
+            output.push((edge.from, edge.to, edge.curve));
+        } else {
+            output.push((edge.to, edge.from, edge.curve.map(CurveRange::flip)));
+        }
+    }
+
+    output
+}
+
+#[inline]
+fn edge_is_horizontal(edge: &Edge, points: &[Point]) -> bool {
+    points[edge.from].y == points[edge.to].y
+}
+
+/// Computes the winding number of each operand at each position (see `winding_numbers`).
+fn operand_winding_numbers(
+    segments: &[(LineSegment<f32>, [i32; 2])],
+    positions: &[(Point, Option<usize>)],
+) -> Vec<[i32; 2]> {
+    let operand = |i: usize| {
+        let weighted: Vec<(LineSegment<f32>, i32)> = segments.iter().map(|&(s, w)| (s, w[i])).collect();
+        winding_numbers(&weighted, positions)
+    };
+
+    operand(0).into_iter().zip(operand(1)).map(|(a, b)| [a, b]).collect()
+}
+
+/// Connects the oriented boundary edges into closed sub-paths.
+fn build_path(edges: &[BoundaryEdge], points: &[Point], curves: &[BezierSegment<f32>], threshold: f32) -> Path {
+    let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (idx, &(from, _, _)) in edges.iter().enumerate() {
+        outgoing.entry(from).or_default().push(idx);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut builder = Path::builder();
+    // The vertices of the sub-path and the curve that the edge starting at each of them
+    // follows, if any.
+    let mut polygon = Vec::new();
+
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+
+        polygon.clear();
+        let start_vertex = edges[start].0;
+        let mut current = start;
+        loop {
+            used[current] = true;
+            let (from, to, curve) = edges[current];
+            polygon.push((points[from], curve));
+
+            if to == start_vertex {
+                break;
+            }
+
+            // When several edges leave the same vertex, pick the first one clockwise from
+            // the incoming edge so that the sub-paths don't cross each other.
+            let incoming = points[from] - points[to];
+            let mut best = None;
+            let mut best_angle = f32::MAX;
+            let candidates = outgoing.get(&to).map(|c| &c[..]).unwrap_or(&[]);
+            for &candidate in candidates {
+                if used[candidate] {
+                    continue;
+                }
+                let outgoing = points[edges[candidate].1] - points[to];
+                let mut angle = incoming.cross(outgoing).atan2(incoming.dot(outgoing));
+                if angle <= 0.0 {
+                    angle += 2.0 * f32::consts::PI;
+                }
+                if angle < best_angle {
+                    best_angle = angle;
+                    best = Some(candidate);
+                }
+            }
+
+            match best {
+                Some(next) => { current = next; }
+                None => {
+                    // The boundary edges are expected to form closed loops, but imprecise
+                    // intersections can leave a dangling edge. It is replaced with a line
+                    // segment closing the sub-path.
+                    if let Some(last) = polygon.last_mut() {
+                        last.1 = None;
+                    }
+                    break;
+                }
+            }
+        }
+
+        remove_collinear_points(&mut polygon, threshold);
+        if polygon.len() < 3 {
+            continue;
+        }
+
+        add_sub_path(&mut builder, &polygon, curves);
+    }
+
+    builder.build()
+}
+
+/// Adds a closed sub-path, replacing the runs of edges that follow the same curve of the
+/// input paths with the corresponding part of the curve.
+fn add_sub_path(builder: &mut Builder, polygon: &[(Point, Option<CurveRange>)], curves: &[BezierSegment<f32>]) {
+    let n = polygon.len();
+    let continues_curve = |i: usize| {
+        match (polygon[(i + n - 1) % n].1, polygon[i % n].1) {
+            (Some(prev), Some(next)) => prev.is_continued_by(&next),
+            _ => false,
+        }
+    };
+
+    // Start at the beginning of a run.
+    let start = (0..n).find(|&i| !continues_curve(i)).unwrap_or(0);
+    builder.move_to(polygon[start].0);
+    let mut i = 0;
+    while i < n {
+        let mut end = i + 1;
+        let range = polygon[(start + i) % n].1;
+        if range.is_some() {
+            while end < n && continues_curve(start + end) {
+                end += 1;
+            }
+        }
+
+        // The curve is split at the vertices of the graph so that the sub-paths share
+        // the vertices of the flattened geometry.
+        let to = polygon[(start + end) % n].0;
+        match range {
+            Some(range) => {
+                let last = polygon[(start + end - 1) % n].1.unwrap();
+                match sub_curve(&curves[range.curve], range.from, last.to) {
+                    BezierSegment::Quadratic(curve) => { builder.quadratic_bezier_to(curve.ctrl, to); }
+                    BezierSegment::Cubic(curve) => { builder.cubic_bezier_to(curve.ctrl1, curve.ctrl2, to); }
+                    BezierSegment::Linear(..) => { builder.line_to(to); }
+                }
+            }
+            None if end < n => { builder.line_to(to); }
+            // The last line segment is added by close.
+            None => {}
+        }
+
+        i = end;
+    }
+
+    builder.close();
+}
+
+/// Removes the points that lie on the line segment between their neighbors.
+fn remove_collinear_points(polygon: &mut Vec<(Point, Option<CurveRange>)>, threshold: f32) {
+    let mut i = 0;
+    while polygon.len() > 2 && i < polygon.len() {
+        let n = polygon.len();
+        let (prev, prev_curve) = polygon[(i + n - 1) % n];
+        let (next, _) = polygon[(i + 1) % n];
+        let (p, curve) = polygon[i];
+        if prev_curve.is_none() && curve.is_none() && point_on_segment(p, prev, next, threshold).is_some() {
+            polygon.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::path::{FlattenedEvent, iterator::PathIterator};
+
+#[cfg(test)]
+fn area(path: &Path) -> f32 {
+    // With the y axis pointing down, the inside of the result is on the left of its edges
+    // which makes the signed area negative with this formula.
+    let mut area = 0.0;
+    for evt in path.iter().flattened(0.001) {
+        if let FlattenedEvent::Line(segment) | FlattenedEvent::Close(segment) = evt {
+            area -= segment.from.to_vector().cross(segment.to.to_vector()) * 0.5;
+        }
+    }
+
+    area
+}
+
+#[cfg(test)]
+fn square(min: Point, max: Point) -> Path {
+    let mut builder = Path::builder();
+    builder.polygon(&[min, point(max.x, min.y), max, point(min.x, max.y)]);
+    builder.build()
+}
+
+#[cfg(test)]
+fn assert_area(path: &Path, expected: f32) {
+    let actual = area(path);
+    assert!((actual - expected).abs() < 0.01, "expected area {}, got {}", expected, actual);
+}
+
+#[test]
+fn overlapping_squares() {
+    let a = square(point(0.0, 0.0), point(2.0, 2.0));
+    let b = square(point(1.0, 1.0), point(3.0, 3.0));
+    let options = BooleanOptions::default();
+
+    assert_area(&boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Union, &options), 7.0);
+    assert_area(&boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Intersection, &options), 1.0);
+    assert_area(&boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Difference, &options), 3.0);
+    assert_area(&boolean_op(b.as_slice(), a.as_slice(), BooleanOp::Difference, &options), 3.0);
+    assert_area(&boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Xor, &options), 6.0);
+
+    let intersection = boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Intersection, &options);
+    assert_eq!(intersection.iter().count(), 5);
+}
+
+#[test]
+fn disjoint_squares() {
+    let a = square(point(0.0, 0.0), point(1.0, 1.0));
+    let b = square(point(2.0, 0.0), point(3.0, 1.0));
+    let options = BooleanOptions::default();
+
+    assert_area(&boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Union, &options), 2.0);
+    assert_area(&boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Difference, &options), 1.0);
+
+    let intersection = boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Intersection, &options);
+    assert_eq!(intersection.iter().count(), 0);
+}
+
+#[test]
+fn shared_edges() {
+    // Squares sharing part of an edge.
+    let a = square(point(0.0, 0.0), point(2.0, 2.0));
+    let b = square(point(2.0, 1.0), point(3.0, 3.0));
+    let options = BooleanOptions::default();
+
+    assert_area(&boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Union, &options), 6.0);
+    assert_area(&boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Intersection, &options), 0.0);
+
+    // Identical squares.
+    assert_area(&boolean_op(a.as_slice(), a.as_slice(), BooleanOp::Union, &options), 4.0);
+    assert_area(&boolean_op(a.as_slice(), a.as_slice(), BooleanOp::Intersection, &options), 4.0);
+    assert_area(&boolean_op(a.as_slice(), a.as_slice(), BooleanOp::Xor, &options), 0.0);
+}
+
+#[test]
+fn hole() {
+    use crate::hit_test::hit_test_path;
+
+    let a = square(point(0.0, 0.0), point(10.0, 10.0));
+    let b = square(point(2.0, 2.0), point(8.0, 8.0));
+    let options = BooleanOptions::default();
+
+    let result = boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Difference, &options);
+    assert_area(&result, 64.0);
+
+    // The hole is oriented in the opposite direction so that the result works with all
+    // fill rules.
+    for &fill_rule in &[FillRule::EvenOdd, FillRule::NonZero, FillRule::Positive] {
+        assert!(hit_test_path(&point(1.0, 1.0), result.iter(), fill_rule, 0.1));
+        assert!(!hit_test_path(&point(5.0, 5.0), result.iter(), fill_rule, 0.1));
+        assert!(!hit_test_path(&point(11.0, 5.0), result.iter(), fill_rule, 0.1));
+    }
+}
+
+#[test]
+fn fill_rule() {
+    // Two overlapping sub-paths in the same path.
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, 0.0), point(2.0, 0.0), point(2.0, 2.0), point(0.0, 2.0)]);
+    builder.polygon(&[point(1.0, 0.0), point(3.0, 0.0), point(3.0, 2.0), point(1.0, 2.0)]);
+    let a = builder.build();
+    let empty = Path::new();
+
+    let even_odd = BooleanOptions::default().with_fill_rule(FillRule::EvenOdd);
+    let non_zero = BooleanOptions::default().with_fill_rule(FillRule::NonZero);
+
+    assert_area(&boolean_op(a.as_slice(), empty.as_slice(), BooleanOp::Union, &even_odd), 4.0);
+    assert_area(&boolean_op(a.as_slice(), empty.as_slice(), BooleanOp::Union, &non_zero), 6.0);
+}
+
+#[test]
+fn circles() {
+    use crate::geom::Arc;
+    use crate::geom::euclid::Angle;
+
+    let circle = |center: Point| {
+        let mut builder = Path::builder();
+        let arc = Arc {
+            center,
+            radii: vector(1.0, 1.0),
+            start_angle: Angle::radians(0.0),
+            sweep_angle: Angle::radians(2.0 * f32::consts::PI),
+            x_rotation: Angle::radians(0.0),
+        };
+        builder.move_to(arc.from());
+        arc.for_each_quadratic_bezier(&mut |curve| {
+            builder.quadratic_bezier_to(curve.ctrl, curve.to);
+        });
+        builder.close();
+        builder.build()
+    };
+
+    let a = circle(point(0.0, 0.0));
+    let b = circle(point(1.0, 0.0));
+    let options = BooleanOptions::tolerance(0.001);
+
+    // Area of the lens formed by two unit circles one radius apart.
+    let lens = 2.0 * f32::consts::PI / 3.0 - 3.0f32.sqrt() / 2.0;
+
+    let union = boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Union, &options);
+    let intersection = boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Intersection, &options);
+    let input_area = area(&a).abs() + area(&b).abs();
+    assert!((area(&intersection) - lens).abs() < 0.01);
+    assert!((area(&union) + area(&intersection) - input_area).abs() < 0.001);
+}
+
+
+#[cfg(test)]
+fn count_curves(path: &Path) -> usize {
+    path.iter().filter(|evt| match evt {
+        PathEvent::Quadratic(..) | PathEvent::Cubic(..) => true,
+        _ => false,
+    }).count()
+}
+
+#[test]
+fn keep_curves() {
+    // A square with a rounded side.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(2.0, 0.0));
+    builder.quadratic_bezier_to(point(4.0, 1.0), point(2.0, 2.0));
+    builder.line_to(point(0.0, 2.0));
+    builder.close();
+    let a = builder.build();
+    // Bulge of the curve: 2/3 of the triangle formed with its control point.
+    let a_area = 4.0 + 4.0 / 3.0;
+
+    let options = BooleanOptions::tolerance(0.001);
+
+    // The curve is not touched by the other path.
+    let b = square(point(-1.0, 0.5), point(1.0, 1.5));
+    let union = boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Union, &options);
+    assert_eq!(count_curves(&union), 1);
+    assert!((area(&union) - (a_area + 1.0)).abs() < 0.01);
+
+    // The curve is cut in two by the other path.
+    let b = square(point(1.0, 0.5), point(4.0, 1.5));
+    let difference = boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Difference, &options);
+    assert_eq!(count_curves(&difference), 2);
+    let intersection = boolean_op(a.as_slice(), b.as_slice(), BooleanOp::Intersection, &options);
+    assert_eq!(count_curves(&intersection), 1);
+    assert!((area(&difference) + area(&intersection) - a_area).abs() < 0.01);
+}
+
+#[test]
+fn dangling_edge() {
+    // A triangle followed by an edge that doesn't lead anywhere.
+    let points = [point(0.0, 0.0), point(1.0, 0.0), point(1.0, 1.0), point(2.0, 2.0)];
+    let edges = [(0, 1, None), (1, 2, None), (2, 3, None), (2, 0, None)];
+
+    let path = build_path(&edges, &points, &[], 0.0001);
+    assert!((area(&path).abs() - 0.5).abs() < 0.0001);
+}
@@ -3,6 +3,7 @@
 use crate::path::{PathEvent, FillRule};
 use crate::math::Point;
 use crate::geom::LineSegment;
+use std::cmp::Ordering;
 use std::f32;
 
 /// Returns whether the point is inside the path.
@@ -12,10 +13,7 @@ where
 {
     let winding = path_winding_number_at_position(point, path, tolerance);
 
-    match fill_rule {
-        FillRule::EvenOdd => winding % 2 != 0,
-        FillRule::NonZero => winding != 0,
-    }
+    fill_rule.is_in(winding as i16)
 }
 
 /// Compute the winding number of a given position with respect to the path.
@@ -74,6 +72,57 @@ fn test_segment(point: Point, segment: &LineSegment<f32>, winding: &mut i32) {
     }
 }
 
+/// Computes the winding numbers of several positions with respect to a set of weighted
+/// line segments.
+///
+/// Each segment adds its weight to the winding number of the positions on the right of it
+/// (the segment crosses the horizontal line passing through the position, on its left).
+/// Each position can ignore one of the segments, typically the one it lies on. The
+/// positions are visited in a single sweep along the y axis, so that each of them is only
+/// tested against the segments that cross its horizontal line.
+pub fn winding_numbers(
+    segments: &[(LineSegment<f32>, i32)],
+    positions: &[(Point, Option<usize>)],
+) -> Vec<i32> {
+    let cmp = |a: f32, b: f32| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+    let min_y = |s: &LineSegment<f32>| s.from.y.min(s.to.y);
+
+    let mut sorted_segments: Vec<usize> = (0..segments.len()).collect();
+    sorted_segments.sort_by(|a, b| cmp(min_y(&segments[*a].0), min_y(&segments[*b].0)));
+    let mut sorted_positions: Vec<usize> = (0..positions.len()).collect();
+    sorted_positions.sort_by(|a, b| cmp(positions[*a].0.y, positions[*b].0.y));
+
+    let mut result = vec![0; positions.len()];
+    // The segments that cross the horizontal line of the current position.
+    let mut active: Vec<usize> = Vec::new();
+    let mut next = 0;
+    for idx in sorted_positions {
+        let (position, ignored) = positions[idx];
+        while next < sorted_segments.len() && min_y(&segments[sorted_segments[next]].0) <= position.y {
+            active.push(sorted_segments[next]);
+            next += 1;
+        }
+        active.retain(|&s| segments[s].0.from.y.max(segments[s].0.to.y) > position.y);
+
+        let mut winding = 0;
+        for &s in &active {
+            if Some(s) == ignored {
+                continue;
+            }
+            let (segment, weight) = segments[s];
+            let t = (position.y - segment.from.y) / (segment.to.y - segment.from.y);
+            let x = segment.from.x + (segment.to.x - segment.from.x) * t;
+            if x < position.x {
+                winding += weight;
+            }
+        }
+
+        result[idx] = winding;
+    }
+
+    result
+}
+
 #[test]
 fn test_hit_test() {
     use crate::path::Path;
@@ -102,4 +151,50 @@ fn test_hit_test() {
     assert!(hit_test_path(&point(0.5, 0.5), path.iter(), FillRule::NonZero, 0.1));
     assert!(hit_test_path(&point(0.2, 0.5), path.iter(), FillRule::EvenOdd, 0.1));
     assert!(hit_test_path(&point(0.8, 0.5), path.iter(), FillRule::EvenOdd, 0.1));
+
+    assert!(hit_test_path(&point(0.5, 0.5), path.iter(), FillRule::Negative, 0.1));
+    assert!(hit_test_path(&point(0.2, 0.5), path.iter(), FillRule::Negative, 0.1));
+    assert!(!hit_test_path(&point(0.5, 0.5), path.iter(), FillRule::Positive, 0.1));
+    assert!(!hit_test_path(&point(0.2, 0.5), path.iter(), FillRule::Positive, 0.1));
+    assert!(!hit_test_path(&point(2.0, 0.5), path.iter(), FillRule::Negative, 0.1));
+}
+
+#[test]
+fn test_winding_numbers() {
+    use crate::math::point;
+
+    // A square and two overlapping triangles inside of it, going in the opposite direction.
+    let mut segments = Vec::new();
+    let mut add_polygon = |points: &[Point]| {
+        for i in 0..points.len() {
+            let segment = LineSegment { from: points[i], to: points[(i + 1) % points.len()] };
+            let weight = if segment.to.y > segment.from.y { 1 } else { -1 };
+            segments.push((segment, weight));
+        }
+    };
+    add_polygon(&[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)]);
+    add_polygon(&[point(2.0, 2.0), point(2.0, 8.0), point(8.0, 8.0)]);
+    add_polygon(&[point(3.0, 2.0), point(3.0, 8.0), point(9.0, 8.0)]);
+
+    let positions = [
+        (point(-1.0, 5.0), None),
+        (point(1.0, 5.0), None),
+        (point(2.5, 7.0), None),
+        (point(4.0, 7.0), None),
+        (point(11.0, 5.0), None),
+        (point(5.0, 15.0), None),
+        // Ignoring the right edge of the square.
+        (point(11.0, 5.0), Some(1)),
+    ];
+    let windings = winding_numbers(&segments, &positions);
+    assert_eq!(windings, vec![0, -1, 0, 1, 0, 0, -1]);
+
+    // Compare with the winding numbers of the individual positions.
+    for (&(position, _), winding) in positions[..6].iter().zip(&windings) {
+        let mut expected = 0;
+        for &(segment, _) in &segments {
+            test_segment(position, &segment, &mut expected);
+        }
+        assert_eq!(*winding, expected);
+    }
 }
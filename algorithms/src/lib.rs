@@ -9,6 +9,10 @@
 
 pub extern crate lyon_path as path;
 
+#[cfg(feature = "serialization")]
+#[macro_use]
+pub extern crate serde;
+
 pub(crate) mod advanced_path;
 pub mod splitter;
 pub mod hatching;
@@ -17,6 +21,8 @@ pub mod hit_test;
 pub mod walk;
 pub mod aabb;
 pub mod fit;
+pub mod boolean;
+pub mod offset;
 
 pub use crate::path::math;
 pub use crate::path::geom;
@@ -0,0 +1,578 @@
+//! Grow or shrink the shape of a path.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate lyon_algorithms;
+//! # use lyon_algorithms::path::{Path, LineJoin};
+//! # use lyon_algorithms::path::builder::*;
+//! # use lyon_algorithms::math::point;
+//! use lyon_algorithms::offset::offset_path;
+//! # fn main() {
+//! let mut builder = Path::builder();
+//! builder.polygon(&[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)]);
+//! let path = builder.build();
+//!
+//! // A focus ring two units around the shape.
+//! let ring = offset_path(path.as_slice(), 2.0, LineJoin::Round, 4.0, 0.1);
+//!
+//! // The same shape, one unit smaller.
+//! let inset = offset_path(path.as_slice(), -1.0, LineJoin::Miter, 4.0, 0.1);
+//! # let _ = (ring, inset);
+//! # }
+//! ```
+
+use crate::math::*;
+use crate::geom::{Arc, LineSegment, QuadraticBezierSegment, CubicBezierSegment, BezierSegment};
+use crate::geom::euclid::Angle;
+use crate::geom::arrayvec::ArrayVec;
+use crate::path::{Path, PathSlice, PathEvent, FlattenedEvent, LineJoin, Builder};
+use crate::path::builder::PathBuilder;
+use crate::path::iterator::PathIterator;
+use std::cmp::Ordering;
+use std::f32;
+
+/// Maximum number of times a curve is subdivided when approximating its offset.
+const MAX_SUBDIVISIONS: u32 = 8;
+
+/// Moves each edge of the path along its normal and returns the resulting path.
+///
+/// Positive distances grow the shape and negative distances shrink it. Which side is
+/// the outside is determined by the orientation of the path: holes are expected to have
+/// the opposite orientation of the sub-paths that contain them. Open sub-paths are offset
+/// on one side only and stay open.
+///
+/// The corners that need to be filled are joined using the provided `LineJoin` and miter
+/// limit, with the same semantics as for strokes. Lines are offset as lines and curves
+/// are approximated with curves of the same degree, with an error smaller than the
+/// tolerance.
+///
+/// When the distance is large compared to the features of the shape, parts of the
+/// offset path can overlap each other. Parts that collapse when shrinking the shape
+/// have an inverted orientation, so the result should be filled with the fill rule
+/// matching the orientation of the input (for example `FillRule::Positive` or
+/// `FillRule::Negative`), or cleaned up using the [boolean](../boolean/index.html)
+/// module.
+pub fn offset_path(
+    path: PathSlice,
+    distance: f32,
+    join: LineJoin,
+    miter_limit: f32,
+    tolerance: f32,
+) -> Path {
+    let distance = if signed_area(path, tolerance) < 0.0 { -distance } else { distance };
+
+    let mut builder = Path::builder();
+    let mut segments = Vec::new();
+    let offset_sub_path = |segments: &[BezierSegment<f32>], closed: bool, builder: &mut Builder| {
+        let curve = OffsetCurve::new(segments, closed, distance, join, miter_limit, tolerance);
+        if let Some(first) = curve.first_point() {
+            builder.move_to(first);
+            curve.append_to(builder);
+            if closed {
+                builder.close();
+            }
+        }
+    };
+
+    for evt in path.iter() {
+        match evt {
+            PathEvent::MoveTo(..) => {
+                offset_sub_path(&segments, false, &mut builder);
+                segments.clear();
+            }
+            PathEvent::Line(segment) => {
+                segments.push(BezierSegment::Linear(segment));
+            }
+            PathEvent::Quadratic(segment) => {
+                segments.push(BezierSegment::Quadratic(segment));
+            }
+            PathEvent::Cubic(segment) => {
+                segments.push(BezierSegment::Cubic(segment));
+            }
+            PathEvent::Close(segment) => {
+                segments.push(BezierSegment::Linear(segment));
+                offset_sub_path(&segments, true, &mut builder);
+                segments.clear();
+            }
+        }
+    }
+    offset_sub_path(&segments, false, &mut builder);
+
+    builder.build()
+}
+
+/// The signed area of the path, positive if the inside of the path is on the left of its
+/// edges with the y axis pointing down.
+fn signed_area(path: PathSlice, tolerance: f32) -> f32 {
+    let mut area = 0.0;
+    let mut first = point(0.0, 0.0);
+    let mut last = point(0.0, 0.0);
+    for evt in path.iter().flattened(tolerance) {
+        match evt {
+            FlattenedEvent::MoveTo(to) => {
+                area -= last.to_vector().cross(first.to_vector());
+                first = to;
+                last = to;
+            }
+            FlattenedEvent::Line(segment) | FlattenedEvent::Close(segment) => {
+                area -= segment.from.to_vector().cross(segment.to.to_vector());
+                last = segment.to;
+            }
+        }
+    }
+    area -= last.to_vector().cross(first.to_vector());
+
+    area * 0.5
+}
+
+/// A piece of the offset path along with the part of the original path it was generated from.
+struct Piece {
+    source: BezierSegment<f32>,
+    offset: BezierSegment<f32>,
+}
+
+/// What to insert between two pieces of the offset path.
+enum Join {
+    Lines(ArrayVec<[Point; 3]>),
+    Arc(Arc<f32>),
+}
+
+impl Join {
+    fn none() -> Self {
+        Join::Lines(ArrayVec::new())
+    }
+
+    fn lines(points: &[Point]) -> Self {
+        Join::Lines(points.iter().cloned().collect())
+    }
+}
+
+struct Offsetter {
+    distance: f32,
+    join: LineJoin,
+    miter_limit: f32,
+    tolerance: f32,
+}
+
+/// The offset of a sequence of connected segments.
+///
+/// This is the building block of `offset_path`. It doesn't make any assumption about
+/// the orientation of the segments, which makes it useful to implement other algorithms
+/// such as stroke outlines.
+pub struct OffsetCurve {
+    pieces: Vec<Piece>,
+    joins: Vec<Join>,
+}
+
+impl OffsetCurve {
+    /// Offsets the segments towards the right of their direction (with the y axis pointing
+    /// down) if the distance is positive, and towards the left otherwise.
+    ///
+    /// The segments are expected to be connected. If `closed` is true, the last segment is
+    /// joined with the first one.
+    pub fn new(
+        segments: &[BezierSegment<f32>],
+        closed: bool,
+        distance: f32,
+        join: LineJoin,
+        miter_limit: f32,
+        tolerance: f32,
+    ) -> Self {
+        let offsetter = Offsetter { distance, join, miter_limit, tolerance };
+
+        let mut pieces = Vec::new();
+        for segment in segments {
+            if segment.is_degenerate() {
+                continue;
+            }
+            match *segment {
+                BezierSegment::Linear(segment) => {
+                    let n = normal(segment.to_vector()) * distance;
+                    pieces.push(Piece {
+                        source: BezierSegment::Linear(segment),
+                        offset: BezierSegment::Linear(LineSegment {
+                            from: segment.from + n,
+                            to: segment.to + n,
+                        }),
+                    });
+                }
+                BezierSegment::Quadratic(segment) => {
+                    offsetter.offset_quadratic(&segment, 0, &mut pieces);
+                }
+                BezierSegment::Cubic(segment) => {
+                    offsetter.offset_cubic(&segment, 0, &mut pieces);
+                }
+            }
+        }
+
+        let num_pieces = pieces.len();
+        let num_joins = if closed { num_pieces } else { num_pieces.saturating_sub(1) };
+        let mut joins = Vec::with_capacity(num_joins);
+        for i in 0..num_joins {
+            joins.push(offsetter.join(&mut pieces, i, (i + 1) % num_pieces));
+        }
+
+        OffsetCurve { pieces, joins }
+    }
+
+    /// Returns true if all of the segments were empty.
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+
+    /// The first point of the offset curve.
+    pub fn first_point(&self) -> Option<Point> {
+        self.pieces.first().map(|piece| piece.offset.from())
+    }
+
+    /// The last point of the offset curve.
+    ///
+    /// For closed curves, this is the same as the first point.
+    pub fn last_point(&self) -> Option<Point> {
+        if self.joins.len() == self.pieces.len() {
+            return self.first_point();
+        }
+
+        self.pieces.last().map(|piece| piece.offset.to())
+    }
+
+    /// Adds the offset curve to a path builder.
+    ///
+    /// The current position of the builder is expected to be the first point of the
+    /// offset curve.
+    pub fn append_to(&self, output: &mut impl PathBuilder) {
+        for (idx, piece) in self.pieces.iter().enumerate() {
+            match piece.offset {
+                BezierSegment::Linear(segment) => {
+                    output.line_to(segment.to);
+                }
+                BezierSegment::Quadratic(segment) => {
+                    output.quadratic_bezier_to(segment.ctrl, segment.to);
+                }
+                BezierSegment::Cubic(segment) => {
+                    output.cubic_bezier_to(segment.ctrl1, segment.ctrl2, segment.to);
+                }
+            }
+
+            match self.joins.get(idx) {
+                Some(Join::Lines(points)) => {
+                    for p in points {
+                        output.line_to(*p);
+                    }
+                }
+                Some(Join::Arc(arc)) => {
+                    arc.for_each_quadratic_bezier(&mut |curve| {
+                        output.quadratic_bezier_to(curve.ctrl, curve.to);
+                    });
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+impl Offsetter {
+    /// Computes the join between two consecutive pieces, trimming them if they overlap.
+    fn join(&self, pieces: &mut [Piece], i: usize, j: usize) -> Join {
+        let vertex = pieces[i].source.to();
+        let a = pieces[i].offset.to();
+        let b = pieces[j].offset.from();
+        if (b - a).square_length() <= self.tolerance * self.tolerance * 1e-6 {
+            return Join::none();
+        }
+
+        let t_in = pieces[i].source.end_tangent().normalize();
+        let t_out = pieces[j].source.start_tangent().normalize();
+        let cross = t_in.cross(t_out);
+        let dot = t_in.dot(t_out);
+
+        // Nearly tangent pieces, for example when a curve was subdivided.
+        if dot > 0.0 && cross.abs() < 1e-4 {
+            return Join::lines(&[b]);
+        }
+
+        if cross * self.distance > 0.0 {
+            // Inner join: the pieces overlap, cut them at their intersection.
+            if i != j {
+                if let Some((t1, t2)) = intersection(&pieces[i].offset, &pieces[j].offset) {
+                    pieces[i].offset = pieces[i].offset.split(t1).0;
+                    pieces[j].offset = pieces[j].offset.split(t2).1;
+                    return Join::none();
+                }
+            }
+
+            // The pieces don't intersect, go through the original vertex.
+            return Join::lines(&[vertex, b]);
+        }
+
+        let n_in = normal(t_in);
+        let n_out = normal(t_out);
+        let d = self.distance;
+        let miter_dir = n_in + n_out;
+        // Ratio between the length of the miter and the offset distance.
+        let miter_ratio = (2.0 / (1.0 + n_in.dot(n_out))).sqrt();
+
+        match self.join {
+            LineJoin::Miter | LineJoin::MiterClip if miter_ratio <= self.miter_limit => {
+                let miter = vertex + miter_dir * (d / (1.0 + n_in.dot(n_out)));
+                Join::lines(&[miter, b])
+            }
+            LineJoin::MiterClip => {
+                // Clip the miter at the miter limit.
+                let dir = if miter_dir.square_length() > 1e-12 {
+                    miter_dir.normalize() * d.signum()
+                } else {
+                    t_in
+                };
+                let clip_distance = self.miter_limit * d.abs();
+                let s1 = (clip_distance - (a - vertex).dot(dir)) / t_in.dot(dir);
+                let s2 = (clip_distance - (b - vertex).dot(dir)) / -t_out.dot(dir);
+                Join::lines(&[a + t_in * s1, b - t_out * s2, b])
+            }
+            LineJoin::Round => {
+                let from = a - vertex;
+                let to = b - vertex;
+                Join::Arc(Arc {
+                    center: vertex,
+                    radii: vector(d.abs(), d.abs()),
+                    start_angle: Angle::radians(from.y.atan2(from.x)),
+                    sweep_angle: Angle::radians(from.cross(to).atan2(from.dot(to))),
+                    x_rotation: Angle::radians(0.0),
+                })
+            }
+            LineJoin::Miter | LineJoin::Bevel => Join::lines(&[b]),
+        }
+    }
+
+    fn offset_quadratic(&self, curve: &QuadraticBezierSegment<f32>, depth: u32, output: &mut Vec<Piece>) {
+        let d = self.distance;
+        let t0 = BezierSegment::Quadratic(*curve).start_tangent();
+        let t1 = BezierSegment::Quadratic(*curve).end_tangent();
+        let from = curve.from + normal(t0) * d;
+        let to = curve.to + normal(t1) * d;
+        let ctrl = line_intersection(from, t0, to, t1)
+            .unwrap_or_else(|| curve.ctrl + normal(t0) * d);
+
+        let offset = QuadraticBezierSegment { from, ctrl, to };
+
+        if depth < MAX_SUBDIVISIONS && !self.is_good_approximation(&|t| curve.sample(t), &|t| offset.sample(t)) {
+            let (a, b) = curve.split(0.5);
+            self.offset_quadratic(&a, depth + 1, output);
+            self.offset_quadratic(&b, depth + 1, output);
+            return;
+        }
+
+        output.push(Piece {
+            source: BezierSegment::Quadratic(*curve),
+            offset: BezierSegment::Quadratic(offset),
+        });
+    }
+
+    fn offset_cubic(&self, curve: &CubicBezierSegment<f32>, depth: u32, output: &mut Vec<Piece>) {
+        let d = self.distance;
+        let t0 = BezierSegment::Cubic(*curve).start_tangent();
+        let t1 = BezierSegment::Cubic(*curve).end_tangent();
+        let from = curve.from + normal(t0) * d;
+        let to = curve.to + normal(t1) * d;
+
+        // Offset the middle leg of the control polygon and intersect it with the other ones.
+        let mid = curve.ctrl2 - curve.ctrl1;
+        let (ctrl1, ctrl2) = if mid.square_length() > 1e-12 {
+            let mid_offset = curve.ctrl1 + normal(mid) * d;
+            (
+                line_intersection(from, t0, mid_offset, mid).unwrap_or(curve.ctrl1 + normal(t0) * d),
+                line_intersection(mid_offset, mid, to, t1).unwrap_or(curve.ctrl2 + normal(t1) * d),
+            )
+        } else {
+            (curve.ctrl1 + normal(t0) * d, curve.ctrl2 + normal(t1) * d)
+        };
+
+        let offset = CubicBezierSegment { from, ctrl1, ctrl2, to };
+
+        if depth < MAX_SUBDIVISIONS && !self.is_good_approximation(&|t| curve.sample(t), &|t| offset.sample(t)) {
+            let (a, b) = curve.split(0.5);
+            self.offset_cubic(&a, depth + 1, output);
+            self.offset_cubic(&b, depth + 1, output);
+            return;
+        }
+
+        output.push(Piece {
+            source: BezierSegment::Cubic(*curve),
+            offset: BezierSegment::Cubic(offset),
+        });
+    }
+
+    fn is_good_approximation(&self, curve: &dyn Fn(f32) -> Point, offset: &dyn Fn(f32) -> Point) -> bool {
+        let d = self.distance.abs();
+        for &t in &[0.25, 0.5, 0.75] {
+            let error = ((offset(t) - curve(t)).length() - d).abs();
+            if error > self.tolerance {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// The normal pointing towards the right of the direction, with the y axis pointing down.
+#[inline]
+fn normal(v: Vector) -> Vector {
+    vector(-v.y, v.x).normalize()
+}
+
+/// Intersects the lines passing through the provided points with the provided directions.
+fn line_intersection(p1: Point, v1: Vector, p2: Point, v2: Vector) -> Option<Point> {
+    let det = v1.cross(v2);
+    if det.abs() <= 1e-6 * v1.length() * v2.length() {
+        return None;
+    }
+
+    let t = (p2 - p1).cross(v2) / det;
+
+    Some(p1 + v1 * t)
+}
+
+fn to_cubic(segment: &BezierSegment<f32>) -> CubicBezierSegment<f32> {
+    match *segment {
+        BezierSegment::Linear(s) => CubicBezierSegment {
+            from: s.from,
+            ctrl1: s.from.lerp(s.to, 1.0 / 3.0),
+            ctrl2: s.from.lerp(s.to, 2.0 / 3.0),
+            to: s.to,
+        },
+        BezierSegment::Quadratic(s) => s.to_cubic(),
+        BezierSegment::Cubic(s) => s,
+    }
+}
+
+/// Returns the intersection of two consecutive pieces that is the closest to the end of the
+/// first one.
+fn intersection(a: &BezierSegment<f32>, b: &BezierSegment<f32>) -> Option<(f32, f32)> {
+    if let (BezierSegment::Linear(a), BezierSegment::Linear(b)) = (a, b) {
+        return a.intersection_t(b);
+    }
+
+    to_cubic(a).cubic_intersections_t(&to_cubic(b))
+        .into_iter()
+        .max_by(|x, y| x.0.partial_cmp(&y.0).unwrap_or(Ordering::Equal))
+}
+
+#[cfg(test)]
+fn area(path: &Path) -> f32 {
+    signed_area(path.as_slice(), 0.001).abs()
+}
+
+#[cfg(test)]
+fn square(min: Point, max: Point, clockwise: bool) -> Path {
+    let mut builder = Path::builder();
+    if clockwise {
+        builder.polygon(&[min, point(max.x, min.y), max, point(min.x, max.y)]);
+    } else {
+        builder.polygon(&[min, point(min.x, max.y), max, point(max.x, min.y)]);
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+fn assert_approx_eq(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 0.01, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn offset_square() {
+    use std::f32::consts::PI;
+
+    for &clockwise in &[true, false] {
+        let path = square(point(0.0, 0.0), point(10.0, 10.0), clockwise);
+        let offset = |d, join| area(&offset_path(path.as_slice(), d, join, 4.0, 0.01));
+
+        assert_approx_eq(offset(1.0, LineJoin::Miter), 144.0);
+        assert_approx_eq(offset(1.0, LineJoin::MiterClip), 144.0);
+        assert_approx_eq(offset(1.0, LineJoin::Bevel), 142.0);
+        assert_approx_eq(offset(1.0, LineJoin::Round), 140.0 + PI);
+        assert_approx_eq(offset(-1.0, LineJoin::Miter), 64.0);
+        assert_approx_eq(offset(-1.0, LineJoin::Round), 64.0);
+        assert_approx_eq(offset(0.0, LineJoin::Miter), 100.0);
+    }
+}
+
+#[test]
+fn miter_limit() {
+    let path = square(point(0.0, 0.0), point(10.0, 10.0), true);
+
+    // The miter of a square corner is sqrt(2) times the offset distance.
+    let area_with_limit = |join, limit| area(&offset_path(path.as_slice(), 1.0, join, limit, 0.01));
+    assert_approx_eq(area_with_limit(LineJoin::Miter, 1.5), 144.0);
+    assert_approx_eq(area_with_limit(LineJoin::Miter, 1.3), 142.0);
+
+    // Clipped at one unit from the corner, which is where the bevel is.
+    assert_approx_eq(area_with_limit(LineJoin::MiterClip, 1.0 / 2.0f32.sqrt()), 142.0);
+    // Clipped at 1.2 units from the corner.
+    let clipped = 144.0 - 4.0 * (2.0f32.sqrt() - 1.2) * (2.0f32.sqrt() - 1.2);
+    assert_approx_eq(area_with_limit(LineJoin::MiterClip, 1.2), clipped);
+}
+
+#[test]
+fn offset_hole() {
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)]);
+    builder.polygon(&[point(2.0, 2.0), point(2.0, 8.0), point(8.0, 8.0), point(8.0, 2.0)]);
+    let path = builder.build();
+
+    let offset = offset_path(path.as_slice(), 1.0, LineJoin::Miter, 4.0, 0.01);
+    let num_sub_paths = offset.iter()
+        .filter(|evt| if let PathEvent::MoveTo(..) = evt { true } else { false })
+        .count();
+    assert_eq!(num_sub_paths, 2);
+
+    // The outer square grows and the hole shrinks.
+    assert_approx_eq(area(&offset), 144.0 - 16.0);
+}
+
+#[test]
+fn offset_circle() {
+    let mut builder = Path::builder();
+    let arc = Arc::circle(point(0.0, 0.0), 10.0);
+    builder.move_to(arc.from());
+    arc.for_each_quadratic_bezier(&mut |curve| {
+        builder.quadratic_bezier_to(curve.ctrl, curve.to);
+    });
+    builder.close();
+    let path = builder.build();
+
+    let original = area(&path);
+    for &d in &[2.0, -2.0] {
+        let offset = offset_path(path.as_slice(), d, LineJoin::Miter, 4.0, 0.01);
+        // Curves are offset as curves.
+        assert!(offset.iter().all(|evt| match evt {
+            PathEvent::Line(..) => false,
+            _ => true,
+        }));
+
+        let radius = (original / f32::consts::PI).sqrt() + d;
+        assert!((area(&offset) - f32::consts::PI * radius * radius).abs() < 1.0);
+    }
+}
+
+#[test]
+fn offset_open_path() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let path = builder.build();
+
+    let offset = offset_path(path.as_slice(), 1.0, LineJoin::Miter, 4.0, 0.01);
+    let events: Vec<PathEvent> = offset.iter().collect();
+    assert_eq!(events.len(), 2);
+    if let PathEvent::Line(segment) = events[1] {
+        assert_eq!(segment.from.x, 0.0);
+        assert_eq!(segment.to.x, 10.0);
+        assert_eq!(segment.from.y, segment.to.y);
+        assert_eq!(segment.from.y.abs(), 1.0);
+    } else {
+        panic!();
+    }
+}
+
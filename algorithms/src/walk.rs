@@ -233,6 +233,157 @@ where Cb: FnMut(Point, Vector, f32) -> Option<f32> {
     }
 }
 
+/// Events produced by a `Dasher`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DashEvent {
+    /// A dash starts at the provided position and distance along the path.
+    Begin { at: Point, advancement: f32 },
+    /// The current dash continues to the provided position.
+    LineTo(Point),
+    /// The current dash stops at the previous position.
+    End,
+}
+
+/// Splits a flattened path into dashes.
+///
+/// The dash pattern is a repeated sequence of alternating dash and gap lengths,
+/// starting with a dash. The pattern restarts at the beginning of each sub-path,
+/// `offset` units into the pattern.
+///
+/// Like the `PathWalker`, the dasher is fed with a builder-like API and keeps
+/// counting the traversed distance along the whole path, including the gaps.
+/// The dashes are reported to the provided callback.
+pub struct Dasher {
+    intervals: Vec<f32>,
+    offset: f32,
+    index: usize,
+    remaining: f32,
+    advancement: f32,
+    first: Point,
+    prev: Point,
+    need_moveto: bool,
+    in_dash: bool,
+}
+
+impl Dasher {
+    /// Creates a dasher for the provided pattern.
+    ///
+    /// Patterns with an odd number of values are repeated twice. The sum of the
+    /// pattern values must be greater than zero.
+    pub fn new(intervals: &[f32], offset: f32) -> Self {
+        let mut pattern = intervals.to_vec();
+        if intervals.len() % 2 == 1 {
+            pattern.extend_from_slice(intervals);
+        }
+
+        let total: f32 = pattern.iter().sum();
+        assert!(total > 0.0);
+
+        Dasher {
+            intervals: pattern,
+            offset: offset % total + if offset < 0.0 { total } else { 0.0 },
+            index: 0,
+            remaining: 0.0,
+            advancement: 0.0,
+            first: point(0.0, 0.0),
+            prev: point(0.0, 0.0),
+            need_moveto: true,
+            in_dash: false,
+        }
+    }
+
+    pub fn move_to(&mut self, to: Point, output: &mut dyn FnMut(DashEvent)) {
+        self.end(output);
+
+        self.need_moveto = false;
+        self.first = to;
+        self.prev = to;
+
+        // Restart the pattern.
+        let mut offset = self.offset;
+        let mut is_dash = true;
+        self.index = 0;
+        self.remaining = self.intervals[0];
+        while offset > 0.0 && offset >= self.remaining {
+            offset -= self.remaining;
+            is_dash = !is_dash;
+            self.index = (self.index + 1) % self.intervals.len();
+            self.remaining = self.intervals[self.index];
+        }
+        self.remaining -= offset;
+
+        if is_dash {
+            self.in_dash = true;
+            output(DashEvent::Begin { at: to, advancement: self.advancement });
+        }
+    }
+
+    pub fn line_to(&mut self, to: Point, output: &mut dyn FnMut(DashEvent)) {
+        if self.need_moveto {
+            let first = self.first;
+            self.move_to(first, output);
+        }
+
+        let v = to - self.prev;
+        let d = v.length();
+
+        if d < 1e-5 {
+            return;
+        }
+
+        let tangent = v / d;
+
+        let mut distance = 0.0;
+        while distance + self.remaining <= d {
+            distance += self.remaining;
+            self.advancement += self.remaining;
+            let position = self.prev + tangent * distance;
+            if self.in_dash {
+                output(DashEvent::LineTo(position));
+                output(DashEvent::End);
+            } else {
+                output(DashEvent::Begin { at: position, advancement: self.advancement });
+            }
+            self.in_dash = !self.in_dash;
+            self.index = (self.index + 1) % self.intervals.len();
+            self.remaining = self.intervals[self.index];
+        }
+
+        self.remaining -= d - distance;
+        self.advancement += d - distance;
+        self.prev = to;
+
+        if self.in_dash {
+            output(DashEvent::LineTo(to));
+        }
+    }
+
+    pub fn close(&mut self, output: &mut dyn FnMut(DashEvent)) {
+        let first = self.first;
+        self.line_to(first, output);
+        self.end(output);
+        self.need_moveto = true;
+    }
+
+    /// Ends the current dash, if any.
+    ///
+    /// Must be called after the last event of the path.
+    pub fn end(&mut self, output: &mut dyn FnMut(DashEvent)) {
+        if self.in_dash {
+            self.in_dash = false;
+            output(DashEvent::End);
+        }
+    }
+
+    pub fn current_position(&self) -> Point { self.prev }
+
+    /// The position at the start of the current sub-path.
+    pub fn first_position(&self) -> Point { self.first }
+
+    /// The distance traversed along the path so far.
+    pub fn advancement(&self) -> f32 { self.advancement }
+}
+
 #[test]
 fn walk_square() {
     let expected = [
@@ -317,3 +468,38 @@ fn walk_starting_after() {
     walker.move_to(point(0.0, 0.0));
     walker.line_to(point(5.0, 0.0));
 }
+
+#[test]
+fn dashes() {
+    use self::DashEvent::*;
+
+    let mut events = Vec::new();
+    {
+        let output = &mut |evt| events.push(evt);
+        let mut dasher = Dasher::new(&[2.0, 1.0, 0.0], 1.0);
+        dasher.move_to(point(0.0, 0.0), output);
+        dasher.line_to(point(3.0, 0.0), output);
+        dasher.line_to(point(3.0, 3.0), output);
+        dasher.end(output);
+    }
+
+    // The pattern [2, 1, 0] is repeated as [2, 1, 0, 2, 1, 0] and starts one unit in,
+    // so the second dash has a length of zero and the last gap is empty.
+    assert_eq!(
+        events,
+        vec![
+            Begin { at: point(0.0, 0.0), advancement: 0.0 },
+            LineTo(point(1.0, 0.0)),
+            End,
+            Begin { at: point(2.0, 0.0), advancement: 2.0 },
+            LineTo(point(2.0, 0.0)),
+            End,
+            Begin { at: point(3.0, 1.0), advancement: 4.0 },
+            LineTo(point(3.0, 2.0)),
+            End,
+            Begin { at: point(3.0, 2.0), advancement: 5.0 },
+            LineTo(point(3.0, 3.0)),
+            End,
+        ]
+    );
+}
@@ -63,6 +63,8 @@ pub struct FuzzCmd {
     pub max_points: Option<u32>,
     pub tessellator: Tessellator,
     pub ignore_errors: bool,
+    pub robust: bool,
+    pub iterations: Option<u64>,
 }
 
 #[derive(Copy, Clone, Debug)]
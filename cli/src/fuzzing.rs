@@ -64,12 +64,19 @@ pub fn run(cmd: FuzzCmd) -> bool {
     if let Some(num) = cmd.max_points {
         println!("maximum number of points per path: {}", num);
     }
+    if cmd.robust {
+        println!("using robust predicates");
+    }
     println!("----");
     loop {
         let path = generate_path(&cmd, i);
         if cmd.fill || !cmd.stroke {
+            let mut options = FillOptions::default();
+            if cmd.robust {
+                options = options.with_robust_predicates();
+            }
             let status = ::std::panic::catch_unwind(|| {
-                let options = FillOptions::default().on_error(
+                let options = options.on_error(
                     if cmd.ignore_errors {
                         OnError::Recover
                     } else {
@@ -108,7 +115,7 @@ pub fn run(cmd: FuzzCmd) -> bool {
                     &|path: Path| {
                         FillTessellator::new().tessellate_path(
                             &path,
-                            &FillOptions::default(),
+                            &options,
                             &mut NoOutput::new()
                         ).is_err()
                     },
@@ -128,5 +135,10 @@ pub fn run(cmd: FuzzCmd) -> bool {
         if i % 500 == 0 {
             println!(" -- tested {} paths (~{} points per path)", i, path.points().len());
         }
+        if Some(i) == cmd.iterations {
+            println!("----");
+            println!("Tested {} paths without errors.", i);
+            return true;
+        }
     }
 }
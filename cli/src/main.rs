@@ -131,6 +131,16 @@ fn main() {
                 .long("ignore-errors")
                 .help("Try to continue when encoutering errors unless it is a panic.")
             )
+            .arg(Arg::with_name("ROBUST")
+                .long("robust")
+                .help("Use robust predicates in the fill tessellator.")
+            )
+            .arg(Arg::with_name("ITERATIONS")
+                .long("iterations")
+                .help("Stops after tessellating this number of paths")
+                .value_name("ITERATIONS")
+                .takes_value(true)
+            )
         )
         .subcommand(
             declare_tess_params(SubCommand::with_name("show"))
@@ -174,8 +184,11 @@ fn main() {
                                          float_precision,
                                          output).unwrap();
             }
-            _ => {
-                println!(" -- Error while tessellating");
+            res => {
+                match res {
+                    Ok(Err(e)) => { println!(" -- Error while tessellating: {}", e); }
+                    _ => { println!(" -- Error while tessellating"); }
+                }
                 if command.is_present("DEBUG") {
                     println!(" -- Looking for a minimal test case...");
                     find_reduced_test_case(
@@ -214,6 +227,8 @@ fn main() {
             max_points: fuzz_matches.value_of("MAX_POINTS").and_then(|str_val| str_val.parse::<u32>().ok()),
             tessellator: get_tessellator(fuzz_matches),
             ignore_errors: fuzz_matches.is_present("IGNORE_ERRORS"),
+            robust: fuzz_matches.is_present("ROBUST"),
+            iterations: fuzz_matches.value_of("ITERATIONS").and_then(|str_val| str_val.parse::<u64>().ok()),
         });
     }
 
@@ -22,7 +22,7 @@ const DEFAULT_WINDOW_WIDTH: f32 = 800.0;
 const DEFAULT_WINDOW_HEIGHT: f32 = 800.0;
 
 pub fn show_path(cmd: TessellateCmd, render_options: RenderCmd) {
-    let mut geometry: VertexBuffers<GpuVertex, u16> = VertexBuffers::new();
+    let mut geometry: VertexBuffers<GpuVertex, u32> = VertexBuffers::new();
     let mut stroke_width = 1.0;
     if let Some(options) = cmd.stroke {
         stroke_width = options.line_width;
@@ -129,7 +129,7 @@ pub fn show_path(cmd: TessellateCmd, render_options: RenderCmd) {
         return;
     }
 
-    let mut bg_geometry: VertexBuffers<BgVertex, u16> = VertexBuffers::new();
+    let mut bg_geometry: VertexBuffers<BgVertex, u32> = VertexBuffers::new();
     fill_rectangle(
         &Rect::new(point(-1.0, -1.0), size(2.0, 2.0)),
         &FillOptions::default(),
@@ -367,7 +367,7 @@ fn get_debug_geometry(
     target_frame: Option<u32>,
     z_index: f32,
     points: &mut Vec<Primitive>,
-    edges: &mut VertexBuffers<GpuVertex, u16>,
+    edges: &mut VertexBuffers<GpuVertex, u32>,
 ) {
     let mut edge_path = Path::builder();
     let mut frame = 0;
@@ -9,7 +9,7 @@ const DEFAULT_FMT: &str = "vertices: [@vertices{sep=, }{fmt=({position.x}, {posi
 pub fn format_output(
     fmt_string: Option<&str>,
     precision: Option<usize>,
-    buffers: &VertexBuffers<Point, u16>,
+    buffers: &VertexBuffers<Point, u32>,
 ) -> String {
     let fmt = fmt_string.unwrap_or(DEFAULT_FMT).split('@');
     let extract = Regex::new(r"^(.*)\{sep=(.+?)\}\{fmt=(.*)\}$").unwrap();
@@ -133,8 +133,8 @@ impl<'a> MatchVariable for &'a Point {
     }
 }
 
-impl<'a> MatchVariable for &'a u16 {
-    type Value = u16;
+impl<'a> MatchVariable for &'a u32 {
+    type Value = u32;
 
     fn match_var(&self, key: &str) -> Option<Self::Value> {
         match key {
@@ -144,8 +144,8 @@ impl<'a> MatchVariable for &'a u16 {
     }
 }
 
-impl<'a> MatchVariable for (&'a u16, &'a u16, &'a u16) {
-    type Value = u16;
+impl<'a> MatchVariable for (&'a u32, &'a u32, &'a u32) {
+    type Value = u32;
 
     fn match_var(&self, key: &str) -> Option<Self::Value> {
         match key {
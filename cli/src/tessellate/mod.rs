@@ -3,10 +3,11 @@ use lyon::math::*;
 use lyon::tessellation::geometry_builder::{VertexBuffers, BuffersBuilder, VertexConstructor, Identity};
 use lyon::tessellation::{
     FillVertex, StrokeVertex,
-    StrokeTessellator, FillTessellator
+    StrokeTessellator, FillTessellator, TessellationError,
 };
 use lyon::tess2;
 use std::io;
+use std::fmt;
 
 mod format;
 use self::format::format_output;
@@ -14,60 +15,63 @@ use self::format::format_output;
 #[derive(Debug)]
 pub enum TessError {
     Io(io::Error),
-    Fill,
-    Stroke,
+    Fill(TessellationError),
+    Tess2,
+    Stroke(TessellationError),
+}
+
+impl fmt::Display for TessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TessError::Io(e) => write!(f, "io error: {}", e),
+            TessError::Fill(e) => write!(f, "fill tessellation failed: {}", e),
+            TessError::Tess2 => write!(f, "libtess2 fill tessellation failed"),
+            TessError::Stroke(e) => write!(f, "stroke tessellation failed: {}", e),
+        }
+    }
 }
 
 impl ::std::convert::From<::std::io::Error> for TessError {
     fn from(err: io::Error) -> Self { TessError::Io(err) }
 }
 
-pub fn tessellate_path(cmd: TessellateCmd) -> Result<VertexBuffers<Point, u16>, TessError> {
+pub fn tessellate_path(cmd: TessellateCmd) -> Result<VertexBuffers<Point, u32>, TessError> {
 
-    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
 
     if let Some(options) = cmd.fill {
 
-        let ok = match cmd.tessellator {
+        match cmd.tessellator {
             Tessellator::Default => {
                 FillTessellator::new().tessellate_path(
                     &cmd.path,
                     &options,
                     &mut BuffersBuilder::new(&mut buffers, VertexCtor)
-                ).is_ok()
+                ).map_err(TessError::Fill)?;
             }
             Tessellator::Tess2 => {
                 tess2::FillTessellator::new().tessellate_path(
                     &cmd.path,
                     &options,
                     &mut BuffersBuilder::new(&mut buffers, Identity)
-                ).is_ok()
+                ).map_err(|()| TessError::Tess2)?;
             }
-        };
-
-
-        if !ok {
-            return Err(TessError::Fill);
         }
     }
 
     if let Some(options) = cmd.stroke {
-        let ok = StrokeTessellator::new().tessellate_path(
+        StrokeTessellator::new().tessellate_path(
             &cmd.path,
             &options,
             &mut BuffersBuilder::new(&mut buffers, VertexCtor)
-        ).is_ok();
-
-        if !ok {
-            return Err(TessError::Stroke);
-        }
+        ).map_err(TessError::Stroke)?;
     }
 
     Ok(buffers)
 }
 
 pub fn write_output(
-    buffers: VertexBuffers<Point, u16>,
+    buffers: VertexBuffers<Point, u32>,
     count: bool,
     fmt_string: Option<&str>,
     float_precision: Option<usize>,
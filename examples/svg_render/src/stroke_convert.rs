@@ -19,10 +19,20 @@ pub fn convert_stroke(s: &Stroke) -> (Color, StrokeOptions) {
         usvg::LineJoin::Round => tessellation::LineJoin::Round,
     };
 
-    let opt = StrokeOptions::tolerance(0.01)
+    let mut opt = StrokeOptions::tolerance(0.01)
         .with_line_width(s.width.value() as f32)
         .with_line_cap(linecap)
         .with_line_join(linejoin);
 
+    if let Some(ref dasharray) = s.dasharray {
+        let dashes: Vec<f32> = dasharray.0.iter().map(|&dash| dash as f32).collect();
+        match opt.with_dashes(&dashes, s.dashoffset as f32) {
+            Ok(dashed) => { opt = dashed; }
+            Err(e) => {
+                println!("Can't apply the dash pattern {:?} ({}), the path is stroked without dashes.", dashes, e);
+            }
+        }
+    }
+
     (color, opt)
 }
@@ -257,6 +257,58 @@ impl<S: Scalar> BezierSegment<S> {
             }
         }
     }
+
+    /// Swap the direction of the segment.
+    pub fn flip(&self) -> Self {
+        match self {
+            BezierSegment::Linear(segment) => BezierSegment::Linear(segment.flip()),
+            BezierSegment::Quadratic(segment) => BezierSegment::Quadratic(segment.flip()),
+            BezierSegment::Cubic(segment) => BezierSegment::Cubic(segment.flip()),
+        }
+    }
+
+    /// Returns true if all of the points of the segment are equal.
+    pub fn is_degenerate(&self) -> bool {
+        match self {
+            BezierSegment::Linear(s) => s.from == s.to,
+            BezierSegment::Quadratic(s) => s.from == s.to && s.from == s.ctrl,
+            BezierSegment::Cubic(s) => s.from == s.to && s.from == s.ctrl1 && s.from == s.ctrl2,
+        }
+    }
+
+    /// Returns a (not normalized) tangent vector at the start of the segment.
+    ///
+    /// Unlike the derivative, it is not zero if the first control point is
+    /// equal to the start of the segment.
+    pub fn start_tangent(&self) -> Vector<S> {
+        match self {
+            BezierSegment::Linear(s) => s.to_vector(),
+            BezierSegment::Quadratic(s) => first_non_zero(&[s.ctrl - s.from, s.to - s.from]),
+            BezierSegment::Cubic(s) => first_non_zero(&[s.ctrl1 - s.from, s.ctrl2 - s.from, s.to - s.from]),
+        }
+    }
+
+    /// Returns a (not normalized) tangent vector at the end of the segment.
+    ///
+    /// Unlike the derivative, it is not zero if the last control point is
+    /// equal to the end of the segment.
+    pub fn end_tangent(&self) -> Vector<S> {
+        match self {
+            BezierSegment::Linear(s) => s.to_vector(),
+            BezierSegment::Quadratic(s) => first_non_zero(&[s.to - s.ctrl, s.to - s.from]),
+            BezierSegment::Cubic(s) => first_non_zero(&[s.to - s.ctrl2, s.to - s.ctrl1, s.to - s.from]),
+        }
+    }
+}
+
+fn first_non_zero<S: Scalar>(vectors: &[Vector<S>]) -> Vector<S> {
+    for v in vectors {
+        if v.square_length() > S::value(1e-12) {
+            return *v;
+        }
+    }
+
+    vectors[vectors.len() - 1]
 }
 
 impl<S> From<LineSegment<S>> for BezierSegment<S> {
@@ -28,11 +28,36 @@ enum Verb {
 /// A simple path data structure.
 ///
 /// It can be created using a [Builder](struct.Builder.html), and can be iterated over.
+///
+/// Paths created with a [BuilderWithAttributes](struct.BuilderWithAttributes.html) also
+/// store a fixed number of custom attributes for each endpoint (the points that aren't
+/// control points). Paths without attributes don't store anything extra.
+///
+/// The boundaries of each sub-path are recorded along with an optional user-defined id,
+/// see [SubPath](struct.SubPath.html).
 #[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Path {
     points: Box<[Point]>,
     verbs: Box<[Verb]>,
+    attributes: Box<[f32]>,
+    num_attributes: usize,
+    sub_paths: Box<[SubPath]>,
+}
+
+/// Metadata about a sub-path of a [Path](struct.Path.html).
+///
+/// Each `MoveTo` event starts a new sub-path, including the ones that are implicitly
+/// added when an edge follows a `Close` event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct SubPath {
+    /// Index of the sub-path's `MoveTo` event in the path.
+    pub first_event: u32,
+    /// Number of events in the sub-path, including its `MoveTo` and `Close` events.
+    pub num_events: u32,
+    /// The id set with `Builder::set_sub_path_id`, if any.
+    pub id: Option<u32>,
 }
 
 /// A view on a `Path`.
@@ -40,17 +65,29 @@ pub struct Path {
 pub struct PathSlice<'l> {
     points: &'l [Point],
     verbs: &'l [Verb],
+    attributes: &'l [f32],
+    num_attributes: usize,
+    sub_paths: &'l [SubPath],
 }
 
 impl Path {
     /// Creates a [Builder](struct.Builder.html) to create a path.
     pub fn builder() -> Builder { Builder::new() }
 
+    /// Creates a [BuilderWithAttributes](struct.BuilderWithAttributes.html) to create a path
+    /// with `num_attributes` custom attributes per endpoint.
+    pub fn builder_with_attributes(num_attributes: usize) -> BuilderWithAttributes {
+        BuilderWithAttributes::new(num_attributes)
+    }
+
     /// Creates an Empty `Path`.
     pub fn new() -> Path {
         Path {
             points: Box::new([]),
             verbs: Box::new([]),
+            attributes: Box::new([]),
+            num_attributes: 0,
+            sub_paths: Box::new([]),
         }
     }
 
@@ -59,28 +96,63 @@ impl Path {
         PathSlice {
             points: &self.points[..],
             verbs: &self.verbs[..],
+            attributes: &self.attributes[..],
+            num_attributes: self.num_attributes,
+            sub_paths: &self.sub_paths[..],
         }
     }
 
     /// Iterates over the entire `Path`.
     pub fn iter(&self) -> Iter { Iter::new(&self.points[..], &self.verbs[..]) }
 
+    /// Iterates over the entire `Path` with the custom attributes of each event's endpoint.
+    pub fn iter_with_attributes(&self) -> IterWithAttributes {
+        self.as_slice().iter_with_attributes()
+    }
+
     pub fn points(&self) -> &[Point] { &self.points[..] }
 
     pub fn mut_points(&mut self) -> &mut [Point] { &mut self.points[..] }
 
+    /// The number of custom attributes per endpoint.
+    pub fn num_attributes(&self) -> usize { self.num_attributes }
+
+    /// The custom attributes of all endpoints, stored contiguously.
+    pub fn attributes(&self) -> &[f32] { &self.attributes[..] }
+
+    pub fn mut_attributes(&mut self) -> &mut [f32] { &mut self.attributes[..] }
+
+    /// The sub-paths of this path, in order.
+    pub fn sub_paths(&self) -> &[SubPath] { &self.sub_paths[..] }
+
     /// Concatenate two paths.
+    ///
+    /// Both paths must have the same number of custom attributes.
     pub fn merge(&self, other: &Self) -> Self {
+        assert_eq!(self.num_attributes, other.num_attributes);
         let mut verbs = Vec::with_capacity(self.verbs.len() + other.verbs.len());
         let mut points = Vec::with_capacity(self.points.len() + other.points.len());
+        let mut attributes = Vec::with_capacity(self.attributes.len() + other.attributes.len());
         verbs.extend_from_slice(&self.verbs);
         verbs.extend_from_slice(&other.verbs);
         points.extend_from_slice(&self.points);
         points.extend_from_slice(&other.points);
+        attributes.extend_from_slice(&self.attributes);
+        attributes.extend_from_slice(&other.attributes);
+        let mut sub_paths = Vec::with_capacity(self.sub_paths.len() + other.sub_paths.len());
+        sub_paths.extend_from_slice(&self.sub_paths);
+        let event_offset = self.verbs.len() as u32;
+        sub_paths.extend(other.sub_paths.iter().map(|sub_path| SubPath {
+            first_event: sub_path.first_event + event_offset,
+            ..*sub_path
+        }));
 
         Path {
             verbs: verbs.into_boxed_slice(),
             points: points.into_boxed_slice(),
+            attributes: attributes.into_boxed_slice(),
+            num_attributes: self.num_attributes,
+            sub_paths: sub_paths.into_boxed_slice(),
         }
     }
 
@@ -137,6 +209,26 @@ impl<'l> PathSlice<'l> {
     }
 
     pub fn points(&self) -> &[Point] { self.points }
+
+    /// Iterates over the path with the custom attributes of each event's endpoint.
+    pub fn iter_with_attributes(&self) -> IterWithAttributes<'l> {
+        IterWithAttributes {
+            iter: Iter::new(self.points, self.verbs),
+            attributes: self.attributes,
+            num_attributes: self.num_attributes,
+            first_endpoint: 0,
+            next_endpoint: 0,
+        }
+    }
+
+    /// The number of custom attributes per endpoint.
+    pub fn num_attributes(&self) -> usize { self.num_attributes }
+
+    /// The custom attributes of all endpoints, stored contiguously.
+    pub fn attributes(&self) -> &'l [f32] { self.attributes }
+
+    /// The sub-paths of the path, in order.
+    pub fn sub_paths(&self) -> &'l [SubPath] { self.sub_paths }
 }
 
 impl<'l> IntoIterator for PathSlice<'l> {
@@ -164,6 +256,8 @@ pub struct Builder {
     first_vertex: VertexId,
     first_verb: u32,
     need_moveto: bool,
+    sub_paths: Vec<SubPath>,
+    next_sub_path_id: Option<u32>,
 }
 
 impl Builder {
@@ -178,6 +272,8 @@ impl Builder {
             first_vertex: VertexId(0),
             first_verb: 0,
             need_moveto: true,
+            sub_paths: Vec::new(),
+            next_sub_path_id: None,
         }
     }
 
@@ -196,6 +292,11 @@ impl Builder {
         self.current_position = to;
         self.points.push(to);
         self.verbs.push(Verb::MoveTo);
+        self.sub_paths.push(SubPath {
+            first_event: self.first_verb,
+            num_events: 0,
+            id: self.next_sub_path_id.take(),
+        });
     }
 
     pub fn line_to(&mut self, to: Point) {
@@ -283,6 +384,17 @@ impl Builder {
 
     pub fn current_position(&self) -> Point { self.current_position }
 
+    /// Sets the id of the current sub-path.
+    ///
+    /// If the current sub-path was closed (or if no sub-path was started yet), the id
+    /// applies to the next one instead.
+    pub fn set_sub_path_id(&mut self, id: u32) {
+        match self.sub_paths.last_mut() {
+            Some(sub_path) if !self.need_moveto => { sub_path.id = Some(id); }
+            _ => { self.next_sub_path_id = Some(id); }
+        }
+    }
+
     /// Returns a cursor to the next path event.
     pub fn cursor(&self) -> Cursor {
         if let Some(verb) = self.verbs.last() {
@@ -304,10 +416,138 @@ impl Builder {
         }
     }
 
-    pub fn build(self) -> Path {
+    pub fn build(mut self) -> Path {
         Path {
+            sub_paths: finish_sub_paths(&mut self.sub_paths, self.verbs.len()),
             points: self.points.into_boxed_slice(),
             verbs: self.verbs.into_boxed_slice(),
+            attributes: Box::new([]),
+            num_attributes: 0,
+        }
+    }
+}
+
+// Computes the number of events of each sub-path now that all of them are known.
+fn finish_sub_paths(sub_paths: &mut Vec<SubPath>, num_events: usize) -> Box<[SubPath]> {
+    let mut end = num_events as u32;
+    for sub_path in sub_paths.iter_mut().rev() {
+        sub_path.num_events = end - sub_path.first_event;
+        end = sub_path.first_event;
+    }
+
+    mem::take(sub_paths).into_boxed_slice()
+}
+
+/// Builds a path with custom attributes.
+///
+/// Each endpoint (the position passed to `move_to`, `line_to` and as the last parameter
+/// of the curve methods) comes with the same number of custom attributes, for example
+/// a line width or a color.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lyon_path;
+/// # use lyon_path::Path;
+/// # use lyon_path::math::point;
+/// # fn main() {
+/// // Two attributes per endpoint.
+/// let mut builder = Path::builder_with_attributes(2);
+/// builder.move_to(point(0.0, 0.0), &[1.0, 0.0]);
+/// builder.line_to(point(1.0, 0.0), &[2.0, 0.5]);
+/// builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0), &[3.0, 1.0]);
+/// builder.close();
+/// let path = builder.build();
+///
+/// for (event, attributes) in path.iter_with_attributes() {
+///     println!("{:?} {:?}", event, attributes);
+/// }
+/// # }
+/// ```
+pub struct BuilderWithAttributes {
+    builder: Builder,
+    attributes: Vec<f32>,
+    num_attributes: usize,
+    first_endpoint: usize,
+}
+
+impl BuilderWithAttributes {
+    pub fn new(num_attributes: usize) -> Self {
+        BuilderWithAttributes {
+            builder: Builder::new(),
+            attributes: Vec::new(),
+            num_attributes,
+            first_endpoint: 0,
+        }
+    }
+
+    pub fn move_to(&mut self, to: Point, attributes: &[f32]) {
+        self.first_endpoint = self.attributes.len();
+        self.push_attributes(attributes);
+        self.builder.move_to(to);
+    }
+
+    pub fn line_to(&mut self, to: Point, attributes: &[f32]) {
+        self.move_to_if_needed(attributes);
+        self.push_attributes(attributes);
+        self.builder.line_to(to);
+    }
+
+    pub fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point, attributes: &[f32]) {
+        self.move_to_if_needed(attributes);
+        self.push_attributes(attributes);
+        self.builder.quadratic_bezier_to(ctrl, to);
+    }
+
+    pub fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point, attributes: &[f32]) {
+        self.move_to_if_needed(attributes);
+        self.push_attributes(attributes);
+        self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+    }
+
+    pub fn close(&mut self) {
+        self.builder.close();
+    }
+
+    pub fn current_position(&self) -> Point { self.builder.current_position() }
+
+    /// Sets the id of the current sub-path, see `Builder::set_sub_path_id`.
+    pub fn set_sub_path_id(&mut self, id: u32) { self.builder.set_sub_path_id(id) }
+
+    pub fn build(mut self) -> Path {
+        Path {
+            sub_paths: finish_sub_paths(&mut self.builder.sub_paths, self.builder.verbs.len()),
+            points: self.builder.points.into_boxed_slice(),
+            verbs: self.builder.verbs.into_boxed_slice(),
+            attributes: self.attributes.into_boxed_slice(),
+            num_attributes: self.num_attributes,
+        }
+    }
+
+    fn push_attributes(&mut self, attributes: &[f32]) {
+        assert_eq!(attributes.len(), self.num_attributes);
+        self.attributes.extend_from_slice(attributes);
+    }
+
+    // The inner builder inserts a move_to event when adding an edge after
+    // a close event, duplicate the attributes of the first endpoint accordingly.
+    // If there is no previous sub-path, use the attributes of the new endpoint.
+    fn move_to_if_needed(&mut self, attributes: &[f32]) {
+        if self.builder.need_moveto {
+            if self.attributes.is_empty() {
+                let first = self.builder.first_position;
+                self.move_to(first, attributes);
+                return;
+            }
+
+            let offset = self.first_endpoint;
+            self.first_endpoint = self.attributes.len();
+            for i in 0..self.num_attributes {
+                let attribute = self.attributes[offset + i];
+                self.attributes.push(attribute);
+            }
+            let first = self.builder.first_position;
+            self.builder.move_to(first);
         }
     }
 }
@@ -492,6 +732,85 @@ fn test_reverse_single_moveto() {
 }
 
 
+#[test]
+fn test_path_attributes() {
+    let mut builder = Path::builder_with_attributes(2);
+    builder.move_to(point(0.0, 0.0), &[0.0, 1.0]);
+    builder.line_to(point(1.0, 0.0), &[2.0, 3.0]);
+    builder.cubic_bezier_to(point(2.0, 0.0), point(2.0, 1.0), point(1.0, 1.0), &[4.0, 5.0]);
+    builder.close();
+    builder.quadratic_bezier_to(point(-1.0, 0.0), point(-1.0, -1.0), &[6.0, 7.0]);
+    let path = builder.build();
+
+    assert_eq!(path.num_attributes(), 2);
+
+    let attributes: Vec<&[f32]> = path.iter_with_attributes().map(|(_, attributes)| attributes).collect();
+    assert_eq!(
+        attributes,
+        vec![
+            &[0.0, 1.0][..],
+            &[2.0, 3.0][..],
+            &[4.0, 5.0][..],
+            // Close.
+            &[0.0, 1.0][..],
+            // Implicit move_to after close.
+            &[0.0, 1.0][..],
+            &[6.0, 7.0][..],
+        ]
+    );
+
+    let events: Vec<PathEvent> = path.iter_with_attributes().map(|(event, _)| event).collect();
+    assert_eq!(events, path.iter().collect::<Vec<PathEvent>>());
+
+    // Paths without attributes.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    let path = builder.build();
+    assert_eq!(path.num_attributes(), 0);
+    assert!(path.iter_with_attributes().all(|(_, attributes)| attributes.is_empty()));
+}
+
+#[test]
+fn test_sub_paths() {
+    let mut builder = Path::builder();
+    builder.set_sub_path_id(7);
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.close();
+    // Implicit move_to after close.
+    builder.line_to(point(-1.0, 0.0));
+    builder.move_to(point(5.0, 5.0));
+    builder.set_sub_path_id(3);
+    builder.quadratic_bezier_to(point(6.0, 5.0), point(6.0, 6.0));
+    let path = builder.build();
+
+    assert_eq!(
+        path.sub_paths(),
+        &[
+            SubPath { first_event: 0, num_events: 4, id: Some(7) },
+            SubPath { first_event: 4, num_events: 2, id: None },
+            SubPath { first_event: 6, num_events: 2, id: Some(3) },
+        ][..]
+    );
+    assert_eq!(path.as_slice().sub_paths(), path.sub_paths());
+
+    let merged = path.merge(&path);
+    assert_eq!(merged.sub_paths().len(), 6);
+    assert_eq!(merged.sub_paths()[3], SubPath { first_event: 8, num_events: 4, id: Some(7) });
+    assert_eq!(merged.sub_paths()[5], SubPath { first_event: 14, num_events: 2, id: Some(3) });
+
+    let mut builder = Path::builder_with_attributes(1);
+    builder.move_to(point(0.0, 0.0), &[0.0]);
+    builder.set_sub_path_id(1);
+    builder.line_to(point(1.0, 0.0), &[1.0]);
+    let path = builder.build();
+    assert_eq!(path.sub_paths(), &[SubPath { first_event: 0, num_events: 2, id: Some(1) }][..]);
+
+    assert!(Path::new().sub_paths().is_empty());
+}
+
 #[inline]
 fn nan_check(p: Point) {
     debug_assert!(p.x.is_finite());
@@ -506,10 +825,14 @@ impl Build for Builder {
     fn build_and_reset(&mut self) -> Path {
         self.current_position = Point::new(0.0, 0.0);
         self.first_position = Point::new(0.0, 0.0);
+        self.next_sub_path_id = None;
 
         Path {
+            sub_paths: finish_sub_paths(&mut self.sub_paths, self.verbs.len()),
             points: mem::replace(&mut self.points, Vec::new()).into_boxed_slice(),
             verbs: mem::replace(&mut self.verbs, Vec::new()).into_boxed_slice(),
+            attributes: Box::new([]),
+            num_attributes: 0,
         }
     }
 }
@@ -642,6 +965,40 @@ impl<'l> Iterator for Iter<'l> {
     }
 }
 
+/// An iterator for `Path` and `PathSlice` that also provides the custom attributes of
+/// each event's endpoint.
+///
+/// The attributes of a `Close` event are the ones of the first endpoint of the sub-path.
+#[derive(Clone, Debug)]
+pub struct IterWithAttributes<'l> {
+    iter: Iter<'l>,
+    attributes: &'l [f32],
+    num_attributes: usize,
+    first_endpoint: usize,
+    next_endpoint: usize,
+}
+
+impl<'l> Iterator for IterWithAttributes<'l> {
+    type Item = (PathEvent, &'l [f32]);
+    fn next(&mut self) -> Option<(PathEvent, &'l [f32])> {
+        let event = self.iter.next()?;
+        let endpoint = match event {
+            PathEvent::Close(..) => self.first_endpoint,
+            _ => {
+                let endpoint = self.next_endpoint;
+                self.next_endpoint += 1;
+                endpoint
+            }
+        };
+        if let PathEvent::MoveTo(..) = event {
+            self.first_endpoint = endpoint;
+        }
+
+        let n = self.num_attributes;
+        Some((event, &self.attributes[endpoint * n .. (endpoint + 1) * n]))
+    }
+}
+
 fn n_stored_points(verb: Verb) -> u32 {
     match verb {
         Verb::MoveTo => 1,
@@ -19,7 +19,6 @@
 //!
 //! Advantages:
 //!
-//! - Supports the `NonZero` fill rule.
 //! - More robust against precision errors when paths have many self
 //!   intersections very close to each other.
 //!
@@ -13,8 +13,8 @@ use std::os::raw::c_void;
 ///
 /// When in doubt it is usually preferable to use
 /// [lyon_tessellation](https://docs.rs/lyon_tessellation/)'s `FillTessellator`.
-/// However in some cases, for example when the `NonZero` fill rule
-/// is needed, This tessellator provides a good fallback.
+/// However in some cases, for example when the input has many self intersections
+/// very close to each other, This tessellator provides a good fallback.
 pub struct FillTessellator {
     tess: *mut TESStesselator,
 }
@@ -94,6 +94,21 @@ impl FillTessellator {
                 FillRule::NonZero => {
                     TessWindingRule::TESS_WINDING_NONZERO
                 }
+                FillRule::Positive => {
+                    TessWindingRule::TESS_WINDING_POSITIVE
+                }
+                FillRule::Negative => {
+                    TessWindingRule::TESS_WINDING_NEGATIVE
+                }
+            };
+
+            // Without an explicit normal, libtess2 picks the orientation that makes the
+            // total area positive, which would make the sign of the winding numbers depend
+            // on the input. Provide a normal matching lyon's winding convention instead.
+            let normal: [TESSreal; 3] = [0.0, 0.0, -1.0];
+            let normal_ptr = match options.fill_rule {
+                FillRule::Positive | FillRule::Negative => normal.as_ptr(),
+                _ => ptr::null(),
             };
 
             let res = tessTesselate(self.tess,
@@ -101,7 +116,7 @@ impl FillTessellator {
                 TessElementType::TESS_POLYGONS,
                 3,
                 2,
-                ptr::null_mut(),
+                normal_ptr,
             );
 
             res == 1
@@ -111,7 +126,7 @@ impl FillTessellator {
     fn process_output(&mut self, output: &mut dyn GeometryReceiver<Point>) -> Count {
         unsafe {
             let num_indices = tessGetElementCount(self.tess) as usize * 3;
-            let num_vertices = tessGetElementCount(self.tess) as usize;
+            let num_vertices = tessGetVertexCount(self.tess) as usize;
 
             let vertices = slice::from_raw_parts(
                 tessGetVertices(self.tess) as *const Point,
@@ -125,7 +140,7 @@ impl FillTessellator {
             output.set_geometry(vertices, indices);
 
             Count {
-                vertices: num_indices as u32,
+                vertices: num_vertices as u32,
                 indices: num_indices as u32,
             }
         }
@@ -145,3 +160,57 @@ impl Default for FillTessellator {
         Self::new()
     }
 }
+
+#[cfg(test)]
+use crate::tessellation::geometry_builder::{simple_builder, VertexBuffers};
+#[cfg(test)]
+use crate::path::Path;
+
+#[cfg(test)]
+fn tessellated_area(path: &Path, fill_rule: FillRule) -> f32 {
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+    let mut options = FillOptions::tolerance(0.05);
+    options.fill_rule = fill_rule;
+    FillTessellator::new().tessellate_path(
+        path.iter(),
+        &options,
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    let mut area = 0.0;
+    for triangle in buffers.indices.chunks(3) {
+        let a = buffers.vertices[triangle[0] as usize];
+        let b = buffers.vertices[triangle[1] as usize];
+        let c = buffers.vertices[triangle[2] as usize];
+        area += (b - a).cross(c - a).abs() * 0.5;
+    }
+
+    area
+}
+
+#[test]
+fn test_positive_negative_orientation() {
+    // The normal passed to libtess2 must give clockwise sub-paths (with y pointing down)
+    // a winding number of -1, like lyon's fill tessellator, whatever the rest of the path.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let clockwise = builder.build();
+
+    assert!(tessellated_area(&clockwise, FillRule::Positive) < 0.01);
+    assert!((tessellated_area(&clockwise, FillRule::Negative) - 100.0).abs() < 0.01);
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.close();
+    let counter_clockwise = builder.build();
+
+    assert!((tessellated_area(&counter_clockwise, FillRule::Positive) - 100.0).abs() < 0.01);
+    assert!(tessellated_area(&counter_clockwise, FillRule::Negative) < 0.01);
+}
@@ -621,19 +621,19 @@ pub fn fill_circle(
     let v = [
         output.add_vertex(FillVertex {
             position: center + (left * radius),
-            normal: left
+            normal: left,
         })?,
         output.add_vertex(FillVertex {
             position: center + (up * radius),
-            normal: up
+            normal: up,
         })?,
         output.add_vertex(FillVertex {
             position: center + (right * radius),
-            normal: right
+            normal: right,
         })?,
         output.add_vertex(FillVertex {
             position: center + (down * radius),
-            normal: down
+            normal: down,
         })?,
     ];
 
@@ -0,0 +1,251 @@
+//! Tessellation of many paths in parallel.
+//!
+//! Requires the `rayon` feature.
+//!
+//! The functions of this module tessellate each item of a slice on the rayon thread pool,
+//! using one tessellator per thread, and merge the results into a single `VertexBuffers`.
+//! The indices are rebased so that they refer to the merged vertex buffer, and the range
+//! of vertices and indices produced by each item is recorded in a table.
+//!
+//! ## Example
+//!
+//! ```
+//! # extern crate lyon_tessellation as tess;
+//! # use tess::path::Path;
+//! # use tess::math::point;
+//! # use tess::batch::fill_paths;
+//! # use tess::{FillOptions, FillVertex};
+//! # use tess::geometry_builder::Identity;
+//! # fn main() {
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(1.0, 0.0));
+//! builder.line_to(point(1.0, 1.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let items = vec![(path.as_slice(), FillOptions::DEFAULT); 100];
+//! let output: tess::batch::BatchBuffers<FillVertex, u32> = fill_paths(&items, Identity);
+//!
+//! assert_eq!(output.ranges.len(), 100);
+//! let range = output.ranges[10].as_ref().unwrap();
+//! assert_eq!(range.vertices, 30..33);
+//! assert_eq!(&output.buffers.indices[range.indices.start as usize..range.indices.end as usize], &[31, 30, 32]);
+//! # }
+//! ```
+
+use crate::geometry_builder::{BuffersBuilder, MaxIndex, VertexBuffers, VertexConstructor, VertexId};
+use crate::path::PathSlice;
+use crate::{FillOptions, FillTessellator, FillVertex, StrokeOptions, StrokeTessellator, StrokeVertex};
+use crate::{TessellationError, TessellationResult};
+
+use rayon::prelude::*;
+
+use std::ops::Range;
+
+/// The output of a batch tessellation.
+#[derive(Clone, Debug, Default)]
+pub struct BatchBuffers<VertexType, IndexType> {
+    /// The geometry of all items, in the order of the items.
+    pub buffers: VertexBuffers<VertexType, IndexType>,
+    /// The location of each item's geometry in the buffers, or the error that happened while
+    /// tessellating it.
+    ///
+    /// The items that failed don't contribute any geometry.
+    pub ranges: Vec<Result<BatchRange, TessellationError>>,
+}
+
+/// The vertices and indices produced for one item of a batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchRange {
+    /// Range in the vertex buffer.
+    pub vertices: Range<u32>,
+    /// Range in the index buffer.
+    pub indices: Range<u32>,
+}
+
+/// Tessellates the fill of each path in parallel.
+///
+/// Each item is tessellated with `FillTessellator::tessellate_path_with_attributes`, the
+/// vertices are produced by a clone of `vertex_constructor`.
+pub fn fill_paths<VertexType, IndexType, Ctor>(
+    items: &[(PathSlice, FillOptions)],
+    vertex_constructor: Ctor,
+) -> BatchBuffers<VertexType, IndexType>
+where
+    VertexType: Clone + Send,
+    IndexType: From<VertexId> + MaxIndex,
+    Ctor: VertexConstructor<FillVertex, VertexType> + Clone + Send + Sync,
+{
+    tessellate_items(items, FillTessellator::new, |tessellator, &(path, ref options), buffers| {
+        tessellator.tessellate_path_with_attributes(
+            path,
+            options,
+            &mut BuffersBuilder::new(buffers, vertex_constructor.clone()),
+        )
+    })
+}
+
+/// Tessellates the stroke of each path in parallel.
+///
+/// Each item is tessellated with `StrokeTessellator::tessellate_path_with_attributes`, the
+/// vertices are produced by a clone of `vertex_constructor`.
+pub fn stroke_paths<VertexType, IndexType, Ctor>(
+    items: &[(PathSlice, StrokeOptions)],
+    vertex_constructor: Ctor,
+) -> BatchBuffers<VertexType, IndexType>
+where
+    VertexType: Clone + Send,
+    IndexType: From<VertexId> + MaxIndex,
+    Ctor: VertexConstructor<StrokeVertex, VertexType> + Clone + Send + Sync,
+{
+    tessellate_items(items, StrokeTessellator::new, |tessellator, &(path, ref options), buffers| {
+        tessellator.tessellate_path_with_attributes(
+            path,
+            options,
+            &mut BuffersBuilder::new(buffers, vertex_constructor.clone()),
+        )
+    })
+}
+
+// The geometry produced by one rayon job.
+struct Chunk<VertexType> {
+    buffers: VertexBuffers<VertexType, u32>,
+    ranges: Vec<Result<BatchRange, TessellationError>>,
+}
+
+fn tessellate_items<Item, Tessellator, VertexType, IndexType>(
+    items: &[Item],
+    new_tessellator: impl Fn() -> Tessellator + Send + Sync,
+    tessellate: impl Fn(&mut Tessellator, &Item, &mut VertexBuffers<VertexType, u32>) -> TessellationResult + Send + Sync,
+) -> BatchBuffers<VertexType, IndexType>
+where
+    Item: Sync,
+    Tessellator: Send,
+    VertexType: Clone + Send,
+    IndexType: From<VertexId> + MaxIndex,
+{
+    let chunks: Vec<Chunk<VertexType>> = items.par_iter().fold(
+        || (new_tessellator(), Chunk { buffers: VertexBuffers::new(), ranges: Vec::new() }),
+        |(mut tessellator, mut chunk), item| {
+            let vertex_start = chunk.buffers.vertices.len();
+            let index_start = chunk.buffers.indices.len();
+            let result = tessellate(&mut tessellator, item, &mut chunk.buffers);
+            chunk.ranges.push(match result {
+                Ok(_) => Ok(BatchRange {
+                    vertices: vertex_start as u32..chunk.buffers.vertices.len() as u32,
+                    indices: index_start as u32..chunk.buffers.indices.len() as u32,
+                }),
+                Err(e) => {
+                    // Drop the partial geometry.
+                    chunk.buffers.vertices.truncate(vertex_start);
+                    chunk.buffers.indices.truncate(index_start);
+                    Err(e)
+                }
+            });
+            (tessellator, chunk)
+        },
+    ).map(|(_, chunk)| chunk).collect();
+
+    merge(chunks, items.len())
+}
+
+// Concatenates the chunks, in order, rebasing the indices.
+fn merge<VertexType, IndexType>(
+    chunks: Vec<Chunk<VertexType>>,
+    num_items: usize,
+) -> BatchBuffers<VertexType, IndexType>
+where
+    VertexType: Clone,
+    IndexType: From<VertexId> + MaxIndex,
+{
+    let num_vertices = chunks.iter().map(|chunk| chunk.buffers.vertices.len()).sum();
+    let num_indices = chunks.iter().map(|chunk| chunk.buffers.indices.len()).sum();
+    let mut output = BatchBuffers {
+        buffers: VertexBuffers::with_capacity(num_vertices, num_indices),
+        ranges: Vec::with_capacity(num_items),
+    };
+
+    for chunk in chunks {
+        for range in chunk.ranges {
+            let range = match range {
+                Ok(range) => range,
+                Err(e) => {
+                    output.ranges.push(Err(e));
+                    continue;
+                }
+            };
+
+            let vertices = &chunk.buffers.vertices[range.vertices.start as usize..range.vertices.end as usize];
+            let indices = &chunk.buffers.indices[range.indices.start as usize..range.indices.end as usize];
+
+            let vertex_start = output.buffers.vertices.len();
+            let index_start = output.buffers.indices.len();
+            if vertex_start + vertices.len() > IndexType::max_index() {
+                output.ranges.push(Err(TessellationError::TooManyVertices));
+                continue;
+            }
+
+            output.buffers.vertices.extend_from_slice(vertices);
+            output.buffers.indices.extend(indices.iter().map(|&index| {
+                IndexType::from(VertexId(index - range.vertices.start + vertex_start as u32))
+            }));
+            output.ranges.push(Ok(BatchRange {
+                vertices: vertex_start as u32..output.buffers.vertices.len() as u32,
+                indices: index_start as u32..output.buffers.indices.len() as u32,
+            }));
+        }
+    }
+
+    output
+}
+
+#[test]
+fn batch_matches_sequential() {
+    use crate::geometry_builder::{simple_builder, Identity};
+    use crate::math::point;
+    use crate::path::Path;
+
+    let mut paths = Vec::new();
+    for i in 0..50 {
+        let s = i as f32;
+        let mut builder = Path::builder();
+        builder.move_to(point(0.0, 0.0));
+        builder.line_to(point(10.0 + s, 0.0));
+        builder.quadratic_bezier_to(point(20.0, 20.0 + s), point(0.0, 10.0));
+        builder.close();
+        paths.push(builder.build());
+    }
+
+    let items: Vec<_> = paths.iter().map(|path| (path.as_slice(), FillOptions::DEFAULT)).collect();
+    let batch: BatchBuffers<FillVertex, u32> = fill_paths(&items, Identity);
+
+    let mut tessellator = FillTessellator::new();
+    assert_eq!(batch.ranges.len(), paths.len());
+    for (path, range) in paths.iter().zip(&batch.ranges) {
+        let mut expected: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+        tessellator.tessellate_path(path.iter(), &FillOptions::DEFAULT, &mut simple_builder(&mut expected)).unwrap();
+
+        let range = range.as_ref().unwrap();
+        let vertices = &batch.buffers.vertices[range.vertices.start as usize..range.vertices.end as usize];
+        let indices = &batch.buffers.indices[range.indices.start as usize..range.indices.end as usize];
+        assert_eq!(vertices, &expected.vertices[..]);
+        for (&index, &expected_index) in indices.iter().zip(&expected.indices) {
+            assert_eq!(index, expected_index as u32 + range.vertices.start);
+        }
+    }
+
+    let items: Vec<_> = paths.iter().map(|path| (path.as_slice(), StrokeOptions::DEFAULT)).collect();
+    let batch: BatchBuffers<StrokeVertex, u16> = stroke_paths(&items, Identity);
+    let mut vertices = 0;
+    let mut indices = 0;
+    for range in &batch.ranges {
+        let range = range.as_ref().unwrap();
+        assert_eq!(range.vertices.start, vertices);
+        assert_eq!(range.indices.start, indices);
+        vertices = range.vertices.end;
+        indices = range.indices.end;
+    }
+    assert_eq!(vertices as usize, batch.buffers.vertices.len());
+    assert_eq!(indices as usize, batch.buffers.indices.len());
+}
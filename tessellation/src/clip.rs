@@ -0,0 +1,280 @@
+// Clipping.
+//
+// The fill tessellator clips the shape during the sweep by adding the edges of the clip
+// polygon to its events (see FillEvents::add_clip). Otherwise, the geometry produced by
+// the tessellators is recorded and each triangle is clipped against the edges of the clip
+// polygon before being sent to the output. The vertices created on the clip edges are
+// interpolated from the vertices of the clipped triangle edge, and shared with the
+// neighbor triangles.
+
+use crate::geom::math::*;
+use crate::geometry_builder::{GeometryBuilder, GeometryBuilderError, Count, VertexId, add_recorded_vertex, RecordedAttributes};
+use crate::fringe::FringeVertex;
+use crate::{ClipPolygon, FillVertex, StrokeVertex, TessellationResult, VertexAttributes};
+
+use std::collections::HashMap;
+
+/// Vertex types that can be interpolated to create new vertices on the clip edges.
+pub(crate) trait ClipVertex: FringeVertex {
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl ClipVertex for FillVertex {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        FillVertex {
+            position: self.position.lerp(other.position, t),
+            normal: self.normal.lerp(other.normal, t),
+        }
+    }
+}
+
+impl ClipVertex for StrokeVertex {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        StrokeVertex {
+            position: self.position.lerp(other.position, t),
+            normal: self.normal.lerp(other.normal, t),
+            advancement: lerp(self.advancement, other.advancement),
+            side: if t < 0.5 { self.side } else { other.side },
+        }
+    }
+}
+
+/// Runs `tessellate`, clipping its output to the provided polygon if any.
+///
+/// `half_width` is provided if the vertex positions are expected to be offset by their
+/// normal multiplied by this value later on.
+pub(crate) fn with_clip<V: ClipVertex>(
+    clip: Option<&ClipPolygon>,
+    half_width: Option<f32>,
+    output: &mut dyn GeometryBuilder<V>,
+    tessellate: impl FnOnce(&mut dyn GeometryBuilder<V>) -> TessellationResult,
+) -> TessellationResult {
+    let clip = match clip {
+        Some(clip) => clip,
+        None => { return tessellate(output); }
+    };
+
+    let mut recorder = GeometryRecorder::new();
+
+    tessellate(&mut recorder)?;
+
+    output.begin_geometry();
+    if let Err(e) = recorder.clip(clip, half_width, output) {
+        output.abort_geometry();
+        return Err(e.into());
+    }
+
+    Ok(output.end_geometry())
+}
+
+pub(crate) struct RecordedVertex<V> {
+    pub(crate) vertex: V,
+    attributes: RecordedAttributes,
+    // The id of the vertex in the output, once it is used by a triangle.
+    output: Option<VertexId>,
+}
+
+// Records the geometry, which is sent to the output after being processed.
+pub(crate) struct GeometryRecorder<V> {
+    pub(crate) vertices: Vec<RecordedVertex<V>>,
+    attributes: Vec<f32>,
+    pub(crate) triangles: Vec<[usize; 3]>,
+}
+
+impl<V: ClipVertex> GeometryRecorder<V> {
+    pub(crate) fn new() -> Self {
+        GeometryRecorder {
+            vertices: Vec::new(),
+            attributes: Vec::new(),
+            triangles: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, vertex: V, attributes: VertexAttributes) -> Result<VertexId, GeometryBuilderError> {
+        let attributes = RecordedAttributes::new(attributes, &mut self.attributes);
+        self.vertices.push(RecordedVertex {
+            vertex,
+            attributes,
+            output: None,
+        });
+        Ok(VertexId(self.vertices.len() as u32 - 1))
+    }
+
+    fn clip(
+        &mut self,
+        clip: &ClipPolygon,
+        half_width: Option<f32>,
+        output: &mut dyn GeometryBuilder<V>,
+    ) -> Result<(), GeometryBuilderError> {
+        // Positions of the recorded vertices, extended as new vertices are created.
+        let mut positions: Vec<Point> = self.vertices.iter().map(|v| {
+            v.vertex.fringe_position(half_width)
+        }).collect();
+        // New vertices, indexed by the vertices of the edge they split and the clip edge.
+        let mut intersections: HashMap<(usize, usize, usize), usize> = HashMap::new();
+
+        let clip_points = clip.points();
+        let mut polygon = Vec::new();
+        let mut clipped = Vec::new();
+        let triangles = std::mem::take(&mut self.triangles);
+        for triangle in &triangles {
+            polygon.clear();
+            polygon.extend_from_slice(&triangle[..]);
+
+            for edge in 0..clip_points.len() {
+                let a = clip_points[edge];
+                let b = clip_points[(edge + 1) % clip_points.len()];
+                // Positive on the inside of the clip polygon.
+                let distance = |p: Point| (b - a).cross(p - a);
+
+                clipped.clear();
+                for i in 0..polygon.len() {
+                    let from = polygon[i];
+                    let to = polygon[(i + 1) % polygon.len()];
+                    let d_from = distance(positions[from]);
+                    let d_to = distance(positions[to]);
+                    if d_from >= 0.0 {
+                        clipped.push(from);
+                    }
+                    if (d_from < 0.0 && d_to > 0.0) || (d_from > 0.0 && d_to < 0.0) {
+                        // Always interpolate in the same direction so that the neighbor
+                        // triangle gets the same vertex.
+                        let (lo, hi) = if from < to { (from, to) } else { (to, from) };
+                        let idx = match intersections.get(&(lo, hi, edge)) {
+                            Some(&idx) => idx,
+                            None => {
+                                let d_lo = distance(positions[lo]);
+                                let d_hi = distance(positions[hi]);
+                                let t = d_lo / (d_lo - d_hi);
+                                let idx = self.interpolate(lo, hi, t);
+                                positions.push(self.vertices[idx].vertex.fringe_position(half_width));
+                                intersections.insert((lo, hi, edge), idx);
+                                idx
+                            }
+                        };
+                        clipped.push(idx);
+                    }
+                }
+
+                std::mem::swap(&mut polygon, &mut clipped);
+                if polygon.len() < 3 {
+                    break;
+                }
+            }
+
+            if polygon.len() < 3 {
+                continue;
+            }
+
+            let first = self.output_id(polygon[0], output)?;
+            let mut prev = self.output_id(polygon[1], output)?;
+            for &vertex in &polygon[2..] {
+                let id = self.output_id(vertex, output)?;
+                output.add_triangle(first, prev, id);
+                prev = id;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Creates a vertex between two recorded vertices, taking the source of the closest one.
+    pub(crate) fn interpolate(&mut self, a: usize, b: usize, t: f32) -> usize {
+        let vertex = self.vertices[a].vertex.lerp(&self.vertices[b].vertex, t);
+        let attributes = self.vertices[a].attributes.lerp(&self.vertices[b].attributes, t, &mut self.attributes);
+        self.vertices.push(RecordedVertex {
+            vertex,
+            attributes,
+            output: None,
+        });
+
+        self.vertices.len() - 1
+    }
+
+    // Returns the id of a vertex in the output, adding it to the output if needed.
+    pub(crate) fn output_id(
+        &mut self,
+        idx: usize,
+        output: &mut dyn GeometryBuilder<V>,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let vertex = &mut self.vertices[idx];
+        if let Some(id) = vertex.output {
+            return Ok(id);
+        }
+
+        let id = add_recorded_vertex(output, vertex.vertex, vertex.attributes.get(&self.attributes))?;
+        vertex.output = Some(id);
+
+        Ok(id)
+    }
+
+    fn clear(&mut self) {
+        self.vertices.clear();
+        self.attributes.clear();
+        self.triangles.clear();
+    }
+}
+
+impl<V: ClipVertex> GeometryBuilder<V> for GeometryRecorder<V> {
+    fn begin_geometry(&mut self) {
+        self.clear();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        // The count is provided once the geometry is sent to the output.
+        Count { vertices: 0, indices: 0 }
+    }
+
+    fn add_vertex(&mut self, vertex: V) -> Result<VertexId, GeometryBuilderError> {
+        self.record(vertex, VertexAttributes::NONE)
+    }
+
+    fn add_vertex_with_attributes(
+        &mut self,
+        vertex: V,
+        attributes: VertexAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.record(vertex, attributes)
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.triangles.push([a.to_usize(), b.to_usize(), c.to_usize()]);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.clear();
+    }
+}
+
+// Replaces a convex polygon with its intersection with the clip polygon.
+pub(crate) fn clip_convex_polygon(polygon: &mut Vec<Point>, clip: &ClipPolygon) {
+    let clip_points = clip.points();
+    let mut clipped = Vec::with_capacity(polygon.len() + clip_points.len());
+    for edge in 0..clip_points.len() {
+        let a = clip_points[edge];
+        let b = clip_points[(edge + 1) % clip_points.len()];
+        // Positive on the inside of the clip polygon.
+        let distance = |p: Point| (b - a).cross(p - a);
+
+        clipped.clear();
+        for i in 0..polygon.len() {
+            let from = polygon[i];
+            let to = polygon[(i + 1) % polygon.len()];
+            let d_from = distance(from);
+            let d_to = distance(to);
+            if d_from >= 0.0 {
+                clipped.push(from);
+            }
+            if (d_from < 0.0 && d_to > 0.0) || (d_from > 0.0 && d_to < 0.0) {
+                clipped.push(from.lerp(to, d_from / (d_from - d_to)));
+            }
+        }
+
+        std::mem::swap(polygon, &mut clipped);
+        if polygon.len() < 3 {
+            polygon.clear();
+            return;
+        }
+    }
+}
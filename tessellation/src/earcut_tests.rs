@@ -191,7 +191,8 @@ fn issue_16() {
 
 #[test]
 fn issue_17() {
-    earcut_test_f32(&[
+    // The coordinates are out of the range of the fixed point numbers.
+    earcut_test_f32_with_options(&[
         &[
             [-20037508.34,19971868.877628453],
             [-20037508.34,-19971868.877628453],
@@ -207,7 +208,7 @@ fn issue_17() {
             [537711.0379352621,5906645.06648362],
             [537629.886026485,5907533.69114742]
         ]
-    ]);
+    ], &FillOptions::tolerance(0.05).with_normalized_coordinates());
 }
 
 #[test]
@@ -1288,6 +1289,10 @@ fn earcut_test(path: &[&[[i32; 2]]]) {
 }
 
 fn earcut_test_f32(path: &[&[[f32; 2]]]) {
+    earcut_test_f32_with_options(path, &FillOptions::tolerance(0.05));
+}
+
+fn earcut_test_f32_with_options(path: &[&[[f32; 2]]], options: &FillOptions) {
     let mut builder = Path::builder();
     for &sub_path in path {
         if sub_path.len() == 0 {
@@ -1301,11 +1306,11 @@ fn earcut_test_f32(path: &[&[[f32; 2]]]) {
         builder.close();
     }
     let path = builder.build();
-    test_path(path.as_slice());
+    test_path_with_options(path.as_slice(), options);
 }
 
 #[cfg(test)]
-fn tessellate_path(path: PathSlice, log: bool) -> Result<usize, TessellationError> {
+fn tessellate_path(path: PathSlice, log: bool, options: &FillOptions) -> Result<usize, TessellationError> {
     let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
     {
         let mut vertex_builder = simple_builder(&mut buffers);
@@ -1315,7 +1320,7 @@ fn tessellate_path(path: PathSlice, log: bool) -> Result<usize, TessellationErro
         }
         tess.tessellate_path(
             path.iter(),
-            &FillOptions::tolerance(0.05),
+            options,
             &mut vertex_builder
         )?;
     }
@@ -1324,7 +1329,12 @@ fn tessellate_path(path: PathSlice, log: bool) -> Result<usize, TessellationErro
 
 #[cfg(test)]
 fn test_path(path: PathSlice) {
-    let res = ::std::panic::catch_unwind(|| tessellate_path(path, false));
+    test_path_with_options(path, &FillOptions::tolerance(0.05));
+}
+
+#[cfg(test)]
+fn test_path_with_options(path: PathSlice, options: &FillOptions) {
+    let res = ::std::panic::catch_unwind(|| tessellate_path(path, false, options));
 
     if let Ok(Ok(_)) = res {
         return;
@@ -1332,9 +1342,9 @@ fn test_path(path: PathSlice) {
 
     crate::extra::debugging::find_reduced_test_case(
         path,
-        &|path: Path| { return tessellate_path(path.as_slice(), false).is_err(); },
+        &|path: Path| { return tessellate_path(path.as_slice(), false, options).is_err(); },
     );
 
-    tessellate_path(path, true).unwrap();
+    tessellate_path(path, true, options).unwrap();
     panic!();
 }
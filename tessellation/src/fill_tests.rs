@@ -3,7 +3,11 @@ use crate::geometry_builder::*;
 use crate::path::builder::{Build, FlatPathBuilder, PathBuilder};
 use crate::path::{Path, PathSlice};
 use crate::extra::rust_logo::build_logo_path;
-use crate::{FillTessellator, TessellationError, FillOptions, FillVertex, OnError};
+use crate::path::iterator::PathIterator;
+use crate::path::FlattenedEvent;
+use crate::geom::CubicBezierSegment;
+use crate::geom::cubic_to_quadratic::cubic_to_quadratics;
+use crate::{FillTessellator, TessellationError, FillOptions, FillRule, FillVertex, OnError, CurveCoordinates, ClipPolygon, MeshQuality, FillEvents};
 
 use std::env;
 
@@ -26,6 +30,21 @@ fn tessellate_path(path: PathSlice, log: bool) -> Result<usize, TessellationErro
     return Ok(buffers.indices.len() / 3);
 }
 
+fn tessellated_area(path: PathSlice, fill_rule: FillRule) -> f32 {
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    {
+        let mut vertex_builder = simple_builder(&mut buffers);
+        let mut options = FillOptions::tolerance(0.05);
+        options.fill_rule = fill_rule;
+        FillTessellator::new().tessellate_path(
+            path.iter(),
+            &options,
+            &mut vertex_builder
+        ).unwrap();
+    }
+
+    triangles_area(&buffers, |v| v.position)
+}
 
 fn test_path(path: PathSlice) {
     test_path_internal(path, None);
@@ -1126,3 +1145,945 @@ fn test_exp_no_intersection_01() {
     // SVG path syntax:
     // "M 80.041534 19.24472 L 76.56131 23.062233 L 67.26949 23.039438 L 48.42367 28.978098 Z"
 }
+
+fn assert_fill_rule_areas(path: PathSlice, expected_even_odd: f32, expected_non_zero: f32) {
+    let even_odd = tessellated_area(path, FillRule::EvenOdd);
+    let non_zero = tessellated_area(path, FillRule::NonZero);
+    assert!(
+        (even_odd - expected_even_odd).abs() < 0.01,
+        "even-odd: expected area {}, got {}", expected_even_odd, even_odd
+    );
+    assert!(
+        (non_zero - expected_non_zero).abs() < 0.01,
+        "non-zero: expected area {}, got {}", expected_non_zero, non_zero
+    );
+}
+
+fn add_square(builder: &mut dyn FlatPathBuilder, min: Point, max: Point, clockwise: bool) {
+    builder.move_to(min);
+    if clockwise {
+        builder.line_to(point(max.x, min.y));
+        builder.line_to(max);
+        builder.line_to(point(min.x, max.y));
+    } else {
+        builder.line_to(point(min.x, max.y));
+        builder.line_to(max);
+        builder.line_to(point(max.x, min.y));
+    }
+    builder.close();
+}
+
+#[test]
+fn test_fill_rules_without_overlap() {
+    // Without overlapping sub-paths, both fill rules should produce the same shape.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(2.0, 1.0));
+    builder.line_to(point(2.0, 3.0));
+    builder.line_to(point(1.0, 2.0));
+    builder.line_to(point(0.0, 3.0));
+    builder.close();
+    let path = builder.build();
+    let area = tessellated_area(path.as_slice(), FillRule::EvenOdd);
+    assert_fill_rule_areas(path.as_slice(), area, area);
+
+    let mut builder = Path::builder().with_svg();
+    build_logo_path(&mut builder);
+    let path = builder.build();
+    let even_odd = tessellated_area(path.as_slice(), FillRule::EvenOdd);
+    let non_zero = tessellated_area(path.as_slice(), FillRule::NonZero);
+    assert!(non_zero >= even_odd - 0.01);
+}
+
+#[test]
+fn test_fill_rules_nested_squares() {
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(10.0, 10.0), true);
+    add_square(&mut builder, point(2.0, 2.0), point(8.0, 8.0), true);
+    assert_fill_rule_areas(builder.build().as_slice(), 64.0, 100.0);
+
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(10.0, 10.0), true);
+    add_square(&mut builder, point(2.0, 2.0), point(8.0, 8.0), false);
+    assert_fill_rule_areas(builder.build().as_slice(), 64.0, 64.0);
+}
+
+#[test]
+fn test_fill_rules_overlapping_squares() {
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(2.0, 2.0), true);
+    add_square(&mut builder, point(1.0, 1.0), point(3.0, 3.0), true);
+    assert_fill_rule_areas(builder.build().as_slice(), 6.0, 7.0);
+
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(2.0, 2.0), true);
+    add_square(&mut builder, point(1.0, 1.0), point(3.0, 3.0), false);
+    assert_fill_rule_areas(builder.build().as_slice(), 6.0, 6.0);
+
+    // Identical squares.
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(1.0, 1.0), true);
+    add_square(&mut builder, point(0.0, 0.0), point(1.0, 1.0), true);
+    assert_fill_rule_areas(builder.build().as_slice(), 0.0, 1.0);
+}
+
+#[test]
+fn test_fill_rules_star() {
+    // A five pointed star drawn in a single stroke. The non-zero fill rule covers
+    // the pentagon in the middle while the even-odd fill rule doesn't.
+    use std::f32::consts::PI;
+    let mut builder = Path::builder();
+    for i in 0..5 {
+        let angle = (i * 2) as f32 * 2.0 * PI / 5.0;
+        let p = point(angle.cos(), angle.sin()) * 10.0;
+        if i == 0 {
+            builder.move_to(p);
+        } else {
+            builder.line_to(p);
+        }
+    }
+    builder.close();
+    let path = builder.build();
+
+    let even_odd = tessellated_area(path.as_slice(), FillRule::EvenOdd);
+    let non_zero = tessellated_area(path.as_slice(), FillRule::NonZero);
+
+    // Area of the inner pentagon, its circumradius is r * cos(2π/5) / cos(π/5).
+    let inner_radius = 10.0 * (2.0 * PI / 5.0).cos() / (PI / 5.0).cos();
+    let pentagon = 2.5 * inner_radius * inner_radius * (2.0 * PI / 5.0).sin();
+    assert!((non_zero - even_odd - pentagon).abs() < 0.01);
+}
+
+#[test]
+fn test_fill_rules_positive_negative() {
+    // With y pointing down, clockwise sub-paths have a winding number of -1.
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(10.0, 10.0), true);
+    let path = builder.build();
+    assert!(tessellated_area(path.as_slice(), FillRule::Positive) < 0.01);
+    assert!((tessellated_area(path.as_slice(), FillRule::Negative) - 100.0).abs() < 0.01);
+
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(10.0, 10.0), false);
+    let path = builder.build();
+    assert!((tessellated_area(path.as_slice(), FillRule::Positive) - 100.0).abs() < 0.01);
+    assert!(tessellated_area(path.as_slice(), FillRule::Negative) < 0.01);
+
+    // The inner square brings the winding number back to zero.
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(10.0, 10.0), false);
+    add_square(&mut builder, point(2.0, 2.0), point(8.0, 8.0), true);
+    let path = builder.build();
+    assert!((tessellated_area(path.as_slice(), FillRule::Positive) - 64.0).abs() < 0.01);
+    assert!(tessellated_area(path.as_slice(), FillRule::Negative) < 0.01);
+
+    // Overlapping squares with opposite orientations.
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(2.0, 2.0), true);
+    add_square(&mut builder, point(1.0, 1.0), point(3.0, 3.0), false);
+    let path = builder.build();
+    assert!((tessellated_area(path.as_slice(), FillRule::Positive) - 3.0).abs() < 0.01);
+    assert!((tessellated_area(path.as_slice(), FillRule::Negative) - 3.0).abs() < 0.01);
+
+    // Overlapping squares with the same orientation.
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(2.0, 2.0), false);
+    add_square(&mut builder, point(1.0, 1.0), point(3.0, 3.0), false);
+    let path = builder.build();
+    assert!((tessellated_area(path.as_slice(), FillRule::Positive) - 7.0).abs() < 0.01);
+    assert!(tessellated_area(path.as_slice(), FillRule::Negative) < 0.01);
+}
+
+#[test]
+fn test_fill_rules_n_segments_intersecting() {
+    // Same as n_segments_intersecting, with the fill rules that need to classify the
+    // edges at the many intersections.
+    use std::f32::consts::PI;
+
+    for i in 1..6 {
+        let mut builder = Path::builder();
+
+        let center = point(-2.0, -5.0);
+        let n = i * 4 - 1;
+        let delta = PI / n as f32;
+        let mut radius = 1000.0;
+        builder.move_to(center + vector(radius, 0.0));
+        builder.line_to(center - vector(-radius, 0.0));
+        for i in 0..n {
+            let (s, c) = (i as f32 * delta).sin_cos();
+            builder.line_to(center + vector(c, s) * radius);
+            builder.line_to(center - vector(c, s) * radius);
+            radius = -radius;
+        }
+        builder.close();
+        let path = builder.build();
+
+        // The areas don't depend on the rotation.
+        let expected_non_zero = tessellated_area(path.as_slice(), FillRule::NonZero);
+        let expected_positive = tessellated_area(path.as_slice(), FillRule::Positive);
+        assert!(expected_non_zero > 0.0);
+
+        let mut angle = 0.0;
+        while angle < PI * 2.0 {
+            let mut rotated_path = path.clone();
+            let (sin, cos) = angle.sin_cos();
+            for v in rotated_path.mut_points() {
+                let (x, y) = (v.x, v.y);
+                v.x = x * cos + y * sin;
+                v.y = y * cos - x * sin;
+            }
+
+            let non_zero = tessellated_area(rotated_path.as_slice(), FillRule::NonZero);
+            let positive = tessellated_area(rotated_path.as_slice(), FillRule::Positive);
+            let negative = tessellated_area(rotated_path.as_slice(), FillRule::Negative);
+            let tolerance = expected_non_zero * 0.001;
+            assert!((non_zero - expected_non_zero).abs() < tolerance);
+            assert!((positive - expected_positive).abs() < tolerance);
+            // The positive and negative regions split the non-zero region.
+            assert!((positive + negative - non_zero).abs() < tolerance);
+
+            // Mirroring the path reverses the winding, which swaps the two regions.
+            let mut mirrored_path = rotated_path.clone();
+            for v in mirrored_path.mut_points() {
+                v.y = -v.y;
+            }
+            assert!((tessellated_area(mirrored_path.as_slice(), FillRule::Positive) - negative).abs() < tolerance);
+            assert!((tessellated_area(mirrored_path.as_slice(), FillRule::Negative) - positive).abs() < tolerance);
+
+            angle += 0.03;
+        }
+    }
+}
+
+#[test]
+fn test_split_event_with_start_events() {
+    // The top of the two triangles is a split vertex with four edges below it, the
+    // two edges in the middle form a start event.
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(10.0, 10.0), true);
+    builder.move_to(point(5.0, 2.0));
+    builder.line_to(point(2.0, 8.0));
+    builder.line_to(point(4.0, 8.0));
+    builder.close();
+    builder.move_to(point(5.0, 2.0));
+    builder.line_to(point(6.0, 8.0));
+    builder.line_to(point(8.0, 8.0));
+    builder.close();
+    assert_fill_rule_areas(builder.build().as_slice(), 88.0, 88.0);
+
+    // Same thing below a merge vertex.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(5.0, 1.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(5.0, 2.0));
+    builder.line_to(point(2.0, 8.0));
+    builder.line_to(point(4.0, 8.0));
+    builder.close();
+    builder.move_to(point(5.0, 2.0));
+    builder.line_to(point(6.0, 8.0));
+    builder.line_to(point(8.0, 8.0));
+    builder.close();
+    assert_fill_rule_areas(builder.build().as_slice(), 83.0, 83.0);
+}
+
+fn tessellate_with_attributes(path: &Path, fill_rule: FillRule) -> VertexBuffers<AttributedVertex, u16> {
+    let mut buffers = VertexBuffers::new();
+    let mut options = FillOptions::tolerance(0.05);
+    options.fill_rule = fill_rule;
+    FillTessellator::new().tessellate_path_with_attributes(
+        path.as_slice(),
+        &options,
+        &mut BuffersBuilder::new(&mut buffers, WithAttributes),
+    ).unwrap();
+
+    buffers
+}
+
+#[test]
+fn test_path_attributes() {
+    // The first attribute follows the x coordinate and the second one is constant
+    // on each edge of a self-intersecting path, so the first attribute must match
+    // at the intersections whichever edge is picked. The curve is a straight line
+    // so that its length is proportional to x as well.
+    let mut builder = Path::builder_with_attributes(2);
+    builder.move_to(point(0.0, 0.0), &[0.0, 1.0]);
+    builder.line_to(point(10.0, 10.0), &[10.0, 1.0]);
+    builder.line_to(point(10.0, 0.0), &[10.0, 1.0]);
+    builder.quadratic_bezier_to(point(5.0, 5.0), point(0.0, 10.0), &[0.0, 1.0]);
+    builder.close();
+    let path = builder.build();
+
+    for &fill_rule in &[FillRule::EvenOdd, FillRule::NonZero] {
+        let buffers = tessellate_with_attributes(&path, fill_rule);
+        assert!(!buffers.indices.is_empty());
+        for vertex in &buffers.vertices {
+            assert_eq!(vertex.attributes.len(), 2);
+            assert!((vertex.attributes[1] - 1.0).abs() < 0.001);
+            assert!((vertex.attributes[0] - vertex.position.x).abs() < 0.01, "{:?}", vertex);
+        }
+    }
+
+    let mut builder = Path::builder_with_attributes(1);
+    builder.move_to(point(0.0, 0.0), &[1.0]);
+    builder.line_to(point(10.0, 0.0), &[2.0]);
+    builder.line_to(point(10.0, 10.0), &[3.0]);
+    builder.line_to(point(0.0, 10.0), &[4.0]);
+    builder.close();
+    let path = builder.build();
+
+    let buffers = tessellate_with_attributes(&path, FillRule::EvenOdd);
+    assert_eq!(buffers.vertices.len(), 4);
+    for vertex in &buffers.vertices {
+        let expected = match (vertex.position.x == 0.0, vertex.position.y == 0.0) {
+            (true, true) => 1.0,
+            (false, true) => 2.0,
+            (false, false) => 3.0,
+            (true, false) => 4.0,
+        };
+        assert_eq!(vertex.attributes, &[expected]);
+    }
+
+    // The vertices at the intersections of two sub-paths take the average of their
+    // attributes, the other ones only depend on the edges they are on.
+    let mut builder = Path::builder_with_attributes(1);
+    builder.move_to(point(0.0, 0.0), &[0.0]);
+    builder.line_to(point(10.0, 0.0), &[0.0]);
+    builder.line_to(point(10.0, 10.0), &[0.0]);
+    builder.line_to(point(0.0, 10.0), &[0.0]);
+    builder.close();
+    builder.move_to(point(5.0, 5.0), &[1.0]);
+    builder.line_to(point(15.0, 5.0), &[1.0]);
+    builder.line_to(point(15.0, 15.0), &[1.0]);
+    builder.line_to(point(5.0, 15.0), &[1.0]);
+    builder.close();
+    let path = builder.build();
+
+    for &fill_rule in &[FillRule::EvenOdd, FillRule::NonZero] {
+        let buffers = tessellate_with_attributes(&path, fill_rule);
+        for vertex in &buffers.vertices {
+            let p = vertex.position;
+            let expected = if (p == point(10.0, 5.0)) || (p == point(5.0, 10.0)) {
+                0.5
+            } else if p.x <= 10.0 && p.y <= 10.0 && (p.x == 0.0 || p.y == 0.0 || p.x == 10.0 || p.y == 10.0) {
+                0.0
+            } else {
+                1.0
+            };
+            assert_eq!(vertex.attributes, &[expected], "{:?}", vertex);
+        }
+    }
+
+    // Paths without attributes don't go through add_vertex_with_attributes.
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(1.0, 1.0), false);
+    let buffers = tessellate_with_attributes(&builder.build(), FillRule::EvenOdd);
+    for vertex in &buffers.vertices {
+        assert!(vertex.attributes.is_empty());
+    }
+}
+
+#[test]
+fn test_vertex_sources() {
+    let mut builder = Path::builder();
+    builder.set_sub_path_id(10);
+    add_square(&mut builder, point(0.0, 0.0), point(10.0, 10.0), false);
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    builder.quadratic_bezier_to(point(40.0, 5.0), point(30.0, 10.0));
+    builder.line_to(point(20.0, 10.0));
+    builder.close();
+    builder.move_to(point(40.0, 0.0));
+    builder.set_sub_path_id(20);
+    builder.line_to(point(50.0, 0.0));
+    builder.line_to(point(45.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let options = FillOptions::tolerance(0.05);
+    for options in &[options, options.with_anti_aliasing(1.0), options.with_normalized_coordinates()] {
+        let mut buffers: VertexBuffers<AttributedVertex, u16> = VertexBuffers::new();
+        FillTessellator::new().tessellate_path_with_sources(
+            path.as_slice(),
+            options,
+            &mut BuffersBuilder::new(&mut buffers, WithAttributes),
+        ).unwrap();
+
+        assert!(!buffers.indices.is_empty());
+        for vertex in &buffers.vertices {
+            let (position, source) = (vertex.position, vertex.source.unwrap());
+            let (sub_path, sub_path_id) = if position.x < 15.0 {
+                (0, Some(10))
+            } else if position.x < 37.0 {
+                (1, None)
+            } else {
+                (2, Some(20))
+            };
+            assert_eq!(source.sub_path, sub_path);
+            assert_eq!(source.sub_path_id, sub_path_id);
+            assert!(source.segment < 4);
+            // The flattened curve.
+            if sub_path == 1 && position.x > 30.5 && position.y > 1.0 && position.y < 9.0 {
+                assert_eq!(source.segment, 1);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_anti_aliasing_fringe() {
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(10.0, 10.0), false);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<AttributedVertex, u16> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        path.iter(),
+        &FillOptions::tolerance(0.05).with_anti_aliasing(1.0),
+        &mut BuffersBuilder::new(&mut buffers, WithAttributes),
+    ).unwrap();
+
+    // Two triangles for the square and two per side for the fringe.
+    assert_eq!(buffers.indices.len(), 3 * (2 + 8));
+    assert_eq!(buffers.vertices.len(), 8);
+
+    let mut inner_area = 0.0;
+    let mut fringe_area = 0.0;
+    for triangle in buffers.indices.chunks(3) {
+        let a = &buffers.vertices[triangle[0] as usize];
+        let b = &buffers.vertices[triangle[1] as usize];
+        let c = &buffers.vertices[triangle[2] as usize];
+        let area = (b.position - a.position).cross(c.position - a.position).abs() * 0.5;
+        if a.coverage == 1.0 && b.coverage == 1.0 && c.coverage == 1.0 {
+            inner_area += area;
+        } else {
+            fringe_area += area;
+        }
+    }
+    assert!((inner_area - 100.0).abs() < 0.001);
+    assert!((fringe_area - 44.0).abs() < 0.001);
+
+    for vertex in &buffers.vertices {
+        let outer = vertex.position.x < 0.0 || vertex.position.x > 10.0;
+        assert_eq!(vertex.coverage, if outer { 0.0 } else { 1.0 });
+        if outer {
+            assert!(vertex.position.x == -1.0 || vertex.position.x == 11.0);
+            assert!(vertex.position.y == -1.0 || vertex.position.y == 11.0);
+        }
+    }
+
+    // Overlapping sub-paths only get a fringe along the outline of the union.
+    let mut builder = Path::builder();
+    add_square(&mut builder, point(0.0, 0.0), point(10.0, 10.0), false);
+    add_square(&mut builder, point(5.0, 0.0), point(15.0, 10.0), false);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<AttributedVertex, u16> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        path.iter(),
+        &FillOptions::non_zero().with_anti_aliasing(1.0),
+        &mut BuffersBuilder::new(&mut buffers, WithAttributes),
+    ).unwrap();
+
+    let mut fringe_area = 0.0;
+    for triangle in buffers.indices.chunks(3) {
+        let a = &buffers.vertices[triangle[0] as usize];
+        let b = &buffers.vertices[triangle[1] as usize];
+        let c = &buffers.vertices[triangle[2] as usize];
+        if a.coverage != 1.0 || b.coverage != 1.0 || c.coverage != 1.0 {
+            fringe_area += (b.position - a.position).cross(c.position - a.position).abs() * 0.5;
+        }
+    }
+    assert!((fringe_area - 54.0).abs() < 0.001);
+}
+
+#[test]
+fn test_curve_triangles() {
+    use crate::geom::QuadraticBezierSegment;
+
+    // Checks the rendered shape against the shape of the path, using a grid of samples.
+    fn check(path: &Path, fill_rule: FillRule, expected_curves: &[f32]) {
+        let mut buffers: VertexBuffers<AttributedVertex, u16> = VertexBuffers::new();
+        let mut options = FillOptions::tolerance(0.05).with_curve_triangles();
+        options.fill_rule = fill_rule;
+        FillTessellator::new().tessellate_path(
+            path.iter(),
+            &options,
+            &mut BuffersBuilder::new(&mut buffers, WithAttributes),
+        ).unwrap();
+
+        let signs: Vec<f32> = buffers.indices.chunks(3).filter_map(|triangle| {
+            let curve = buffers.vertices[triangle[0] as usize].curve;
+            if curve == CurveCoordinates::INTERIOR { None } else { Some(curve.sign) }
+        }).collect();
+        assert_eq!(&signs[..], expected_curves);
+
+        let mut edges = Vec::new();
+        for evt in path.iter().flattened(0.001) {
+            if let FlattenedEvent::Line(segment) | FlattenedEvent::Close(segment) = evt {
+                edges.push(segment);
+            }
+        }
+
+        for i in 0..50 {
+            for j in 0..50 {
+                let p = point(-2.0 + i as f32 * 0.513, -2.0 + j as f32 * 0.497);
+
+                // Cubic curves are only approximated within the tolerance.
+                let near_edge = edges.iter().any(|edge| {
+                    let v = edge.to_vector();
+                    let t = ((p - edge.from).dot(v) / v.square_length()).max(0.0).min(1.0);
+                    (edge.sample(t) - p).length() < 0.05
+                });
+                if near_edge {
+                    continue;
+                }
+
+                let mut winding = 0;
+                for edge in &edges {
+                    if (edge.from.y <= p.y) == (edge.to.y <= p.y) {
+                        continue;
+                    }
+                    let x = edge.from.x + (p.y - edge.from.y) * (edge.to.x - edge.from.x) / (edge.to.y - edge.from.y);
+                    if x < p.x {
+                        winding += if edge.to.y > edge.from.y { 1 } else { -1 };
+                    }
+                }
+                let expected = fill_rule.is_in(winding);
+
+                let mut covered = false;
+                for triangle in buffers.indices.chunks(3) {
+                    let a = &buffers.vertices[triangle[0] as usize];
+                    let b = &buffers.vertices[triangle[1] as usize];
+                    let c = &buffers.vertices[triangle[2] as usize];
+                    let area = (b.position - a.position).cross(c.position - a.position);
+                    let wa = (b.position - p).cross(c.position - p) / area;
+                    let wb = (c.position - p).cross(a.position - p) / area;
+                    let wc = 1.0 - wa - wb;
+                    if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+                        continue;
+                    }
+                    let u = a.curve.u * wa + b.curve.u * wb + c.curve.u * wc;
+                    let v = a.curve.v * wa + b.curve.v * wb + c.curve.v * wc;
+                    let sign = a.curve.sign * wa + b.curve.sign * wb + c.curve.sign * wc;
+                    if sign * (u * u - v) <= 0.0 {
+                        covered = true;
+                    }
+                }
+
+                assert_eq!(covered, expected, "{:?}", p);
+            }
+        }
+    }
+
+    // A square with a curve bulging out of it.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.quadratic_bezier_to(point(5.0, 20.0), point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+    check(&path, FillRule::EvenOdd, &[1.0]);
+    check(&path, FillRule::NonZero, &[1.0]);
+
+    // A square with a curve going into it, in the other direction.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.quadratic_bezier_to(point(5.0, 2.0), point(10.0, 10.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.close();
+    let path = builder.build();
+    check(&path, FillRule::EvenOdd, &[-1.0]);
+    check(&path, FillRule::NonZero, &[-1.0]);
+
+    // Two curves going into a square whose triangles overlap without the curves
+    // intersecting. They are subdivided until the triangles don't overlap anymore.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.quadratic_bezier_to(point(5.0, 2.0), point(10.0, 10.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(5.0, 8.0), point(0.0, 0.0));
+    builder.close();
+    let path = builder.build();
+    check(&path, FillRule::EvenOdd, &[-1.0; 4]);
+    check(&path, FillRule::NonZero, &[-1.0; 4]);
+
+    // A sub-path crossing a curve. The parts of the curve whose triangles still overlap
+    // the edges after the subdivisions are flattened.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.quadratic_bezier_to(point(5.0, 20.0), point(0.0, 10.0));
+    builder.close();
+    add_square(&mut builder, point(4.0, 12.0), point(6.0, 18.0), false);
+    let path = builder.build();
+    check(&path, FillRule::EvenOdd, &[1.0, 1.0, -1.0, -1.0, 1.0, 1.0]);
+
+    // Cubic curves are approximated with quadratic ones.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(20.0, 0.0), point(20.0, 20.0), point(0.0, 20.0));
+    builder.close();
+    let path = builder.build();
+    let mut num_curves = 0;
+    let segment = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(20.0, 0.0),
+        ctrl2: point(20.0, 20.0),
+        to: point(0.0, 20.0),
+    };
+    cubic_to_quadratics(&segment, 0.05, &mut |_: &QuadraticBezierSegment<f32>| num_curves += 1);
+    check(&path, FillRule::EvenOdd, &vec![1.0; num_curves]);
+}
+
+#[test]
+fn test_clip() {
+    fn check(clip: ClipPolygon, expected_area: f32) {
+        let mut builder = Path::builder();
+        builder.move_to(point(0.0, 0.0));
+        builder.line_to(point(10.0, 0.0));
+        builder.line_to(point(10.0, 10.0));
+        builder.line_to(point(0.0, 10.0));
+        builder.close();
+        builder.move_to(point(2.0, 2.0));
+        builder.line_to(point(2.0, 4.0));
+        builder.line_to(point(4.0, 4.0));
+        builder.line_to(point(4.0, 2.0));
+        builder.close();
+        let path = builder.build();
+
+        for options in &[
+            FillOptions::even_odd(),
+            FillOptions::non_zero(),
+            FillOptions::default().with_normalized_coordinates(),
+            FillOptions::default().with_robust_predicates(),
+        ] {
+            let mut buffers: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+            FillTessellator::new().tessellate_path(
+                path.iter(),
+                &options.with_clip(clip),
+                &mut simple_builder(&mut buffers),
+            ).unwrap();
+
+            let area = triangles_area(&buffers, |v| v.position);
+            assert!((area - expected_area).abs() < 0.001, "{} != {}", area, expected_area);
+
+            // All vertices are inside of the clip polygon.
+            let points = clip.points();
+            for vertex in &buffers.vertices {
+                for i in 0..points.len() {
+                    let a = points[i];
+                    let b = points[(i + 1) % points.len()];
+                    assert!((b - a).cross(vertex.position - a) >= -0.001);
+                }
+            }
+
+            // The vertices are shared between the triangles.
+            for (i, a) in buffers.vertices.iter().enumerate() {
+                for b in &buffers.vertices[i + 1..] {
+                    assert!(a.position != b.position);
+                }
+            }
+        }
+    }
+
+    // Covers the corner of the square and its hole.
+    check(ClipPolygon::from_rect(&rect(3.0, -5.0, 10.0, 10.0)).unwrap(), 7.0 * 5.0 - 1.0 * 2.0);
+    // Entirely inside.
+    check(ClipPolygon::from_rect(&rect(-5.0, -5.0, 20.0, 20.0)).unwrap(), 100.0 - 4.0);
+    // Entirely outside.
+    check(ClipPolygon::from_rect(&rect(20.0, 20.0, 5.0, 5.0)).unwrap(), 0.0);
+    // A triangle, in both winding orders.
+    let triangle = [point(0.0, 0.0), point(10.0, 0.0), point(0.0, 10.0)];
+    check(ClipPolygon::new(&triangle).unwrap(), 50.0 - 4.0);
+    check(ClipPolygon::new(&[triangle[2], triangle[1], triangle[0]]).unwrap(), 50.0 - 4.0);
+    // Far beyond the range of the fixed point coordinates.
+    check(ClipPolygon::from_rect(&rect(-1.0e9, -1.0e9, 2.0e9, 2.0e9)).unwrap(), 100.0 - 4.0);
+
+    // Invalid polygons.
+    assert!(ClipPolygon::new(&triangle[..2]).is_err());
+    assert!(ClipPolygon::new(&[point(0.0, 0.0); ClipPolygon::MAX_POINTS + 1]).is_err());
+    assert!(ClipPolygon::new(&[point(0.0, 0.0), point(1.0, 0.0), point(2.0, 0.0)]).is_err());
+    assert!(ClipPolygon::new(&[point(0.0, 0.0), point(1.0, 0.0), point(std::f32::NAN, 1.0)]).is_err());
+    assert!(ClipPolygon::from_rect(&rect(0.0, 0.0, 0.0, 10.0)).is_err());
+    // Concave.
+    assert!(ClipPolygon::new(&[
+        point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(5.0, 2.0), point(0.0, 10.0),
+    ]).is_err());
+    // Self-intersecting star whose turns all go in the same direction.
+    let star: Vec<Point> = (0..5).map(|i| {
+        let angle = i as f32 * 4.0 * std::f32::consts::PI / 5.0;
+        point(angle.cos(), angle.sin())
+    }).collect();
+    assert!(ClipPolygon::new(&star).is_err());
+}
+
+#[test]
+fn test_mesh_quality() {
+    fn min_angle(a: Point, b: Point, c: Point) -> f32 {
+        let angle = |p: Point, p1: Point, p2: Point| {
+            let (v1, v2) = (p1 - p, p2 - p);
+            v1.cross(v2).abs().atan2(v1.dot(v2))
+        };
+        angle(a, b, c).min(angle(b, c, a)).min(angle(c, a, b))
+    }
+
+    // A long and thin shape, which the sweep line tends to tessellate into slivers.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    for i in 1..10 {
+        builder.line_to(point(i as f32 * 10.0, if i % 2 == 0 { 0.0 } else { 1.0 }));
+    }
+    builder.line_to(point(100.0, 10.0));
+    for i in (0..10).rev() {
+        builder.line_to(point(i as f32 * 10.0, if i % 2 == 0 { 10.0 } else { 9.0 }));
+    }
+    builder.close();
+    let path = builder.build();
+
+    let tessellate = |options: &FillOptions| {
+        let mut buffers: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+        FillTessellator::new().tessellate_path(
+            path.iter(),
+            options,
+            &mut simple_builder(&mut buffers),
+        ).unwrap();
+
+        let mut area = 0.0;
+        let mut smallest_angle = std::f32::consts::PI;
+        let mut largest_area: f32 = 0.0;
+        for triangle in buffers.indices.chunks(3) {
+            let a = buffers.vertices[triangle[0] as usize].position;
+            let b = buffers.vertices[triangle[1] as usize].position;
+            let c = buffers.vertices[triangle[2] as usize].position;
+            let triangle_area = (b - a).cross(c - a).abs() * 0.5;
+            area += triangle_area;
+            largest_area = largest_area.max(triangle_area);
+            smallest_angle = smallest_angle.min(min_angle(a, b, c));
+        }
+
+        (buffers, area, smallest_angle, largest_area)
+    };
+
+    let (_, default_area, default_angle, _) = tessellate(&FillOptions::default());
+
+    let (buffers, area, angle, _) = tessellate(&FillOptions::default().with_delaunay());
+    assert!((area - default_area).abs() < 0.001, "{} != {}", area, default_area);
+    assert!(angle >= default_angle);
+
+    // The vertices are shared between the triangles.
+    for (i, a) in buffers.vertices.iter().enumerate() {
+        for b in &buffers.vertices[i + 1..] {
+            assert!(a.position != b.position);
+        }
+    }
+
+    let quality = MeshQuality::DELAUNAY.with_max_area(5.0);
+    let (_, area, _, largest_area) = tessellate(&FillOptions::default().with_mesh_quality(quality));
+    assert!((area - default_area).abs() < 0.01, "{} != {}", area, default_area);
+    assert!(largest_area <= 5.0, "{}", largest_area);
+
+    let quality = MeshQuality::DELAUNAY.with_min_angle(0.3);
+    let (_, area, angle, _) = tessellate(&FillOptions::default().with_mesh_quality(quality));
+    assert!((area - default_area).abs() < 0.01, "{} != {}", area, default_area);
+    assert!(angle > default_angle);
+}
+
+#[test]
+fn test_max_edge_length() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(100.0, 0.0));
+    builder.line_to(point(100.0, 50.0));
+    builder.quadratic_bezier_to(point(50.0, 100.0), point(0.0, 50.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut expected: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        path.iter(),
+        &FillOptions::default(),
+        &mut simple_builder(&mut expected),
+    ).unwrap();
+
+    let mut buffers: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        path.iter(),
+        &FillOptions::default().with_max_edge_length(10.0),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    let area = |buffers: &VertexBuffers<FillVertex, u16>| triangles_area(buffers, |v| v.position);
+    assert!((area(&buffers) - area(&expected)).abs() < 0.01);
+
+    for triangle in buffers.indices.chunks(3) {
+        for i in 0..3 {
+            let a = buffers.vertices[triangle[i] as usize].position;
+            let b = buffers.vertices[triangle[(i + 1) % 3] as usize].position;
+            assert!((b - a).length() <= 10.0, "{:?} {:?}", a, b);
+        }
+    }
+
+    // There are vertices in the interior of the shape.
+    assert!(buffers.vertices.iter().any(|v| {
+        v.position.x > 10.0 && v.position.x < 90.0 && v.position.y > 10.0 && v.position.y < 40.0
+    }));
+}
+
+#[test]
+fn test_mesh_quality_limits() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(100.0, 0.0));
+    builder.line_to(point(100.0, 50.0));
+    builder.line_to(point(0.0, 50.0));
+    builder.close();
+    let path = builder.build();
+
+    let tessellate = |options: &FillOptions| {
+        let mut buffers: VertexBuffers<FillVertex, u32> = VertexBuffers::new();
+        FillTessellator::new().tessellate_path(
+            path.iter(),
+            options,
+            &mut simple_builder(&mut buffers),
+        ).map(|_| buffers)
+    };
+
+    for &length in &[0.0, -1.0, std::f32::NAN, std::f32::INFINITY] {
+        let result = tessellate(&FillOptions::default().with_max_edge_length(length));
+        assert_eq!(result.err(), Some(TessellationError::UnsupportedParamater));
+    }
+    let quality = MeshQuality::DELAUNAY.with_min_angle(1.5);
+    let result = tessellate(&FillOptions::default().with_mesh_quality(quality));
+    assert_eq!(result.err(), Some(TessellationError::UnsupportedParamater));
+
+    // A tiny limit stops at the maximum number of vertices.
+    let quality = MeshQuality::DELAUNAY.with_max_edge_length(0.0001).with_max_vertices(1000);
+    let buffers = tessellate(&FillOptions::default().with_mesh_quality(quality)).unwrap();
+    assert!(buffers.vertices.len() <= 1000);
+    assert!((triangles_area(&buffers, |v| v.position) - 5000.0).abs() < 0.01);
+}
+
+#[test]
+fn test_transformed_events() {
+    use crate::geom::euclid::Angle;
+
+    let mut builder = Path::builder().with_svg();
+    build_logo_path(&mut builder);
+    let path = builder.build();
+
+    let tolerance = 0.05;
+    let events = FillEvents::from_path(tolerance, path.iter());
+
+    let area = |buffers: &VertexBuffers<FillVertex, u32>| triangles_area(buffers, |v| v.position);
+
+    let transforms = [
+        Transform2D::create_translation(10.0, -5.0),
+        Transform2D::create_scale(2.0, 3.0).post_translate(vector(1.0, 2.0)),
+        Transform2D::create_rotation(Angle::radians(0.7)),
+        Transform2D::create_scale(-1.0, 1.0),
+        Transform2D::create_scale(1.5, -0.5),
+    ];
+    let mut tessellator = FillTessellator::new();
+    for transform in &transforms {
+        let mut expected: VertexBuffers<FillVertex, u32> = VertexBuffers::new();
+        tessellator.tessellate_path(
+            path.iter().transformed(transform),
+            &FillOptions::tolerance(tolerance).with_normals(false),
+            &mut simple_builder(&mut expected),
+        ).unwrap();
+
+        let mut buffers: VertexBuffers<FillVertex, u32> = VertexBuffers::new();
+        tessellator.tessellate_events(
+            &events.transformed(transform),
+            &FillOptions::tolerance(tolerance).with_normals(false),
+            &mut simple_builder(&mut buffers),
+        ).unwrap();
+
+        // The curves are not flattened the same way.
+        let expected_area = area(&expected);
+        assert!((area(&buffers) - expected_area).abs() < expected_area * 0.01);
+    }
+
+    // Applying the transforms in place.
+    let mut events = events.clone();
+    for transform in &transforms {
+        events.transform(transform);
+    }
+    let mut buffers: VertexBuffers<FillVertex, u32> = VertexBuffers::new();
+    tessellator.tessellate_events(
+        &events,
+        &FillOptions::tolerance(tolerance).with_normals(false),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+    assert!(!buffers.indices.is_empty());
+}
+
+#[test]
+fn test_error_display() {
+    use crate::{InternalError, InternalErrorCode};
+    use crate::geom::LineSegment;
+
+    fn assert_error<E: std::error::Error>(_: &E) {}
+
+    let err = TessellationError::Internal(InternalError {
+        code: InternalErrorCode::E03,
+        description: "active edge in an invalid state",
+        position: point(1.0, 2.0),
+        edge: Some(LineSegment { from: point(0.0, 0.0), to: point(3.0, 4.0) }),
+    });
+    assert_error(&err);
+
+    let msg = format!("{}", err);
+    assert!(msg.contains("E03"));
+    assert!(msg.contains("active edge in an invalid state"));
+    assert!(msg.contains("at (1, 2)"));
+    assert!(msg.contains("to (3, 4)"));
+
+    assert_eq!(format!("{}", TessellationError::TooManyVertices), "too many vertices for the output's index type");
+}
+
+#[test]
+fn test_normalized_coordinates() {
+    let mut builder = Path::builder().with_svg();
+    build_logo_path(&mut builder);
+    let path = builder.build();
+
+    let area = |buffers: &VertexBuffers<FillVertex, u32>| triangles_area(buffers, |v| v.position);
+
+    let mut tessellator = FillTessellator::new();
+    let mut expected: VertexBuffers<FillVertex, u32> = VertexBuffers::new();
+    tessellator.tessellate_path(
+        path.iter(),
+        &FillOptions::tolerance(0.05),
+        &mut simple_builder(&mut expected),
+    ).unwrap();
+    let expected_area = area(&expected);
+
+    // Coordinates that are too large or too small for the fixed point numbers.
+    for &(scale, offset) in &[(1000.0, vector(1.0e7, -2.0e7)), (0.0001, vector(-0.5, 0.25))] {
+        let transform = Transform2D::create_scale(scale, scale).post_translate(offset);
+        let mut buffers: VertexBuffers<FillVertex, u32> = VertexBuffers::new();
+        tessellator.tessellate_path(
+            path.iter().transformed(&transform),
+            &FillOptions::tolerance(0.05 * scale).with_normalized_coordinates(),
+            &mut simple_builder(&mut buffers),
+        ).unwrap();
+
+        let bounds = lyon_algorithms::aabb::fast_bounding_rect(path.iter().transformed(&transform))
+            .inflate(scale, scale);
+        for vertex in &buffers.vertices {
+            assert!(bounds.contains(vertex.position));
+        }
+
+        let scaled_area = area(&buffers) / (scale * scale);
+        assert!((scaled_area - expected_area).abs() < expected_area * 0.01);
+    }
+}
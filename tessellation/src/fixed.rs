@@ -153,6 +153,10 @@ macro_rules! impl_fixed_point {
             #[inline]
             pub fn from_f64(val: f64) -> Self { Self::from_raw((val * f64::from(1 << F::bits())) as $bits_type) } // TODO
 
+            /// Converts from a 64 bits floating point value, rounding to the nearest value.
+            #[inline]
+            pub fn from_f64_rounded(val: f64) -> Self { Self::from_raw((val * f64::from(1 << F::bits())).round() as $bits_type) }
+
             /// Converts to a 64 bits floating point value.
             #[inline]
             pub fn to_f64(self) -> f64 { self.bits as f64 / f64::from(1 << F::bits()) } // TODO
@@ -0,0 +1,297 @@
+// Anti-aliasing fringe.
+//
+// The fringe is generated after the tessellation by looking for the edges of the
+// produced triangles that are not shared with another triangle. These edges form the
+// outline of the shape and are extruded outward into a band of triangles, the vertices
+// of the outer side of the band having a coverage of zero.
+
+use crate::geom::math::*;
+use crate::geometry_builder::{GeometryBuilder, GeometryBuilderError, Count, VertexId, add_recorded_vertex, RecordedAttributes};
+use crate::{FillVertex, StrokeVertex, TessellationResult, VertexAttributes};
+
+use std::collections::HashMap;
+
+// Maximum length of the extrusion at sharp corners, relative to the fringe width.
+const MAX_MITER_LENGTH: f32 = 4.0;
+
+/// Vertex types that can be extruded to form the anti-aliasing fringe.
+pub(crate) trait FringeVertex: Copy {
+    /// The position of the vertex in the final geometry.
+    ///
+    /// `half_width` is provided if the vertex position is expected to be
+    /// offset by its normal multiplied by this value later on.
+    fn fringe_position(&self, half_width: Option<f32>) -> Point;
+
+    /// Creates the corresponding vertex on the outer side of the fringe.
+    fn extrude(&self, offset: Vector, half_width: Option<f32>) -> Self;
+}
+
+impl FringeVertex for FillVertex {
+    fn fringe_position(&self, _: Option<f32>) -> Point { self.position }
+
+    fn extrude(&self, offset: Vector, _: Option<f32>) -> Self {
+        FillVertex {
+            position: self.position + offset,
+            normal: self.normal,
+        }
+    }
+}
+
+impl FringeVertex for StrokeVertex {
+    fn fringe_position(&self, half_width: Option<f32>) -> Point {
+        match half_width {
+            Some(half_width) => self.position + self.normal * half_width,
+            None => self.position,
+        }
+    }
+
+    fn extrude(&self, offset: Vector, half_width: Option<f32>) -> Self {
+        let (position, normal) = match half_width {
+            Some(half_width) => (self.position, self.normal + offset / half_width),
+            None => (self.position + offset, self.normal),
+        };
+        StrokeVertex {
+            position,
+            normal,
+            advancement: self.advancement,
+            side: self.side,
+        }
+    }
+}
+
+/// Runs `tessellate`, adding an anti-aliasing fringe of the provided width to its output
+/// if `width` is not `None`.
+pub(crate) fn with_fringe<V: FringeVertex>(
+    width: Option<f32>,
+    half_width: Option<f32>,
+    output: &mut dyn GeometryBuilder<V>,
+    tessellate: impl FnOnce(&mut dyn GeometryBuilder<V>) -> TessellationResult,
+) -> TessellationResult {
+    let width = match width {
+        Some(width) if width > 0.0 && half_width != Some(0.0) => width,
+        _ => { return tessellate(output); }
+    };
+
+    let mut fringe = FringeBuilder {
+        width,
+        half_width,
+        vertices: Vec::new(),
+        vertex_indices: HashMap::new(),
+        attributes: Vec::new(),
+        triangles: Vec::new(),
+        output,
+    };
+
+    tessellate(&mut fringe)?;
+
+    if let Err(e) = fringe.add_fringe() {
+        fringe.output.abort_geometry();
+        return Err(e.into());
+    }
+
+    Ok(fringe.output.end_geometry())
+}
+
+struct RecordedVertex<V> {
+    id: VertexId,
+    vertex: V,
+    attributes: RecordedAttributes,
+}
+
+// Records the geometry while forwarding it to the output.
+//
+// end_geometry is deferred until the fringe is added.
+struct FringeBuilder<'l, V> {
+    width: f32,
+    half_width: Option<f32>,
+    vertices: Vec<RecordedVertex<V>>,
+    vertex_indices: HashMap<VertexId, usize>,
+    attributes: Vec<f32>,
+    triangles: Vec<[VertexId; 3]>,
+    output: &'l mut dyn GeometryBuilder<V>,
+}
+
+// An edge of the welded triangle mesh.
+struct MeshEdge {
+    count: u32,
+    from: usize,
+    to: usize,
+    // A point on the side of the triangle that contains the edge.
+    inside: Point,
+}
+
+impl<'l, V: FringeVertex> FringeBuilder<'l, V> {
+    fn record(&mut self, id: VertexId, vertex: V, attributes: VertexAttributes) {
+        let attributes = RecordedAttributes::new(attributes, &mut self.attributes);
+        self.vertex_indices.insert(id, self.vertices.len());
+        self.vertices.push(RecordedVertex { id, vertex, attributes });
+    }
+
+    fn add_fringe(&mut self) -> Result<(), GeometryBuilderError> {
+        // The tessellators don't always share the vertices between neighbor triangles,
+        // so vertices are welded by position to find the outline.
+        let mut welded: HashMap<(u32, u32), usize> = HashMap::new();
+        let mut positions: Vec<Point> = Vec::new();
+        let mut welded_vertices: Vec<usize> = Vec::with_capacity(self.vertices.len());
+        for vertex in &self.vertices {
+            let position = vertex.vertex.fringe_position(self.half_width);
+            let key = (position.x.to_bits(), position.y.to_bits());
+            let idx = *welded.entry(key).or_insert_with(|| {
+                positions.push(position);
+                positions.len() - 1
+            });
+            welded_vertices.push(idx);
+        }
+
+        let mut edges: HashMap<(usize, usize), MeshEdge> = HashMap::new();
+        for triangle in &self.triangles {
+            let v = [
+                self.vertex_indices[&triangle[0]],
+                self.vertex_indices[&triangle[1]],
+                self.vertex_indices[&triangle[2]],
+            ];
+            // Degenerate triangles don't cover anything, their edges can't be part of the
+            // outline.
+            let p = [
+                positions[welded_vertices[v[0]]],
+                positions[welded_vertices[v[1]]],
+                positions[welded_vertices[v[2]]],
+            ];
+            if (p[1] - p[0]).cross(p[2] - p[0]).abs() < 1e-6 {
+                continue;
+            }
+            for i in 0..3 {
+                let from = v[i];
+                let to = v[(i + 1) % 3];
+                let (a, b) = (welded_vertices[from], welded_vertices[to]);
+                if a == b {
+                    continue;
+                }
+                let key = if a < b { (a, b) } else { (b, a) };
+                edges.entry(key).or_insert(MeshEdge {
+                    count: 0,
+                    from,
+                    to,
+                    inside: positions[welded_vertices[v[(i + 2) % 3]]],
+                }).count += 1;
+            }
+        }
+
+        // Outward normals of the outline edges.
+        let mut outline = Vec::new();
+        let mut normal_sums = vec![vector(0.0, 0.0); positions.len()];
+        let mut normal_counts = vec![0u32; positions.len()];
+        for edge in edges.values() {
+            if edge.count != 1 {
+                continue;
+            }
+            let (a, b) = (welded_vertices[edge.from], welded_vertices[edge.to]);
+            let d = positions[b] - positions[a];
+            if d.square_length() == 0.0 {
+                continue;
+            }
+            let mut n = vector(d.y, -d.x).normalize();
+            if n.dot(edge.inside - positions[a]) > 0.0 {
+                n = -n;
+            }
+            normal_sums[a] += n;
+            normal_sums[b] += n;
+            normal_counts[a] += 1;
+            normal_counts[b] += 1;
+            outline.push((edge.from, edge.to, n));
+        }
+        // Visit the outline in a deterministic order.
+        outline.sort_by_key(|&(from, to, _)| (from, to));
+
+        let mut outer_ids: Vec<Option<VertexId>> = vec![None; positions.len()];
+        for &(from, to, n) in &outline {
+            let outer_from = self.outer_vertex(from, n, &welded_vertices, &normal_sums, &normal_counts, &mut outer_ids)?;
+            let outer_to = self.outer_vertex(to, n, &welded_vertices, &normal_sums, &normal_counts, &mut outer_ids)?;
+            let from = self.vertices[from].id;
+            let to = self.vertices[to].id;
+            self.output.add_triangle(from, to, outer_to);
+            self.output.add_triangle(from, outer_to, outer_from);
+        }
+
+        Ok(())
+    }
+
+    // Returns the outer fringe vertex corresponding to a vertex of the outline, creating it
+    // if needed.
+    fn outer_vertex(
+        &mut self,
+        vertex: usize,
+        edge_normal: Vector,
+        welded_vertices: &[usize],
+        normal_sums: &[Vector],
+        normal_counts: &[u32],
+        outer_ids: &mut [Option<VertexId>],
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let idx = welded_vertices[vertex];
+        if let Some(id) = outer_ids[idx] {
+            return Ok(id);
+        }
+
+        // Miter between the normals of the outline edges meeting at this vertex.
+        let sum = normal_sums[idx];
+        let mut normal = if sum.square_length() > 1e-6 {
+            sum * normal_counts[idx] as f32 / sum.square_length()
+        } else {
+            edge_normal
+        };
+        if normal.square_length() > MAX_MITER_LENGTH * MAX_MITER_LENGTH {
+            normal = normal.normalize() * MAX_MITER_LENGTH;
+        }
+
+        let recorded = &self.vertices[vertex];
+        let outer = recorded.vertex.extrude(normal * self.width, self.half_width);
+        let attributes = VertexAttributes {
+            coverage: 0.0,
+            ..recorded.attributes.get(&self.attributes)
+        };
+        let id = add_recorded_vertex(&mut *self.output, outer, attributes)?;
+        outer_ids[idx] = Some(id);
+
+        Ok(id)
+    }
+}
+
+impl<'l, V: FringeVertex> GeometryBuilder<V> for FringeBuilder<'l, V> {
+    fn begin_geometry(&mut self) {
+        self.output.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        // Deferred until the fringe is added, the count is provided by with_fringe.
+        Count { vertices: 0, indices: 0 }
+    }
+
+    fn add_vertex(&mut self, vertex: V) -> Result<VertexId, GeometryBuilderError> {
+        let id = self.output.add_vertex(vertex)?;
+        self.record(id, vertex, VertexAttributes::NONE);
+        Ok(id)
+    }
+
+    fn add_vertex_with_attributes(
+        &mut self,
+        vertex: V,
+        attributes: VertexAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let id = self.output.add_vertex_with_attributes(vertex, attributes)?;
+        self.record(id, vertex, attributes);
+        Ok(id)
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.triangles.push([a, b, c]);
+        self.output.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.vertices.clear();
+        self.vertex_indices.clear();
+        self.attributes.clear();
+        self.triangles.clear();
+        self.output.abort_geometry();
+    }
+}
@@ -7,7 +7,7 @@ use crate::FillOptions;
 use crate::TessellationError;
 use crate::OnError;
 
-fn tessellate_path(path: PathSlice, log: bool, on_error: OnError) -> Result<usize, TessellationError> {
+fn tessellate_path(path: PathSlice, log: bool, options: &FillOptions) -> Result<usize, TessellationError> {
     let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
     {
         let mut vertex_builder = simple_builder(&mut buffers);
@@ -17,7 +17,7 @@ fn tessellate_path(path: PathSlice, log: bool, on_error: OnError) -> Result<usiz
         }
         tess.tessellate_path(
             path,
-            &FillOptions::tolerance(0.05).on_error(on_error),
+            options,
             &mut vertex_builder
         )?;
     }
@@ -25,8 +25,13 @@ fn tessellate_path(path: PathSlice, log: bool, on_error: OnError) -> Result<usiz
 }
 
 fn test_path(path: PathSlice) {
-    let on_error = OnError::Panic;
-    let res = ::std::panic::catch_unwind(|| tessellate_path(path, false, on_error));
+    test_path_with_options(path, FillOptions::tolerance(0.05));
+    test_path_with_options(path, FillOptions::tolerance(0.05).with_robust_predicates());
+}
+
+fn test_path_with_options(path: PathSlice, options: FillOptions) {
+    let options = options.on_error(OnError::Panic);
+    let res = ::std::panic::catch_unwind(|| tessellate_path(path, false, &options));
 
     if res.is_ok() {
         return;
@@ -34,13 +39,13 @@ fn test_path(path: PathSlice) {
 
     // First see if the tessellator detect the error without panicking
     let recover_mode = ::std::panic::catch_unwind(
-        || tessellate_path(path, false, OnError::Recover)
+        || tessellate_path(path, false, &options.clone().on_error(OnError::Recover))
     );
 
 
     crate::extra::debugging::find_reduced_test_case(
         path,
-        &|path: Path| { return tessellate_path(path.as_slice(), false, on_error).is_err(); },
+        &|path: Path| { return tessellate_path(path.as_slice(), false, &options).is_err(); },
     );
 
     print!(" -- Tessellating with OnError::Recover ");
@@ -50,7 +55,7 @@ fn test_path(path: PathSlice) {
         println!("panicked.");
     }
 
-    tessellate_path(path, true, on_error).unwrap();
+    tessellate_path(path, true, &options).unwrap();
 
     panic!("Test failed.");
 }
@@ -548,9 +553,7 @@ fn fuzzing_test_case_12() {
     // "M 759.9981 59.831738 L 960.42285 418.38144 L 912.67645 193.0542 L 74.49103 176.2433 L 542.925 579.97253 L 920.04016 75.902466 L 658.5332 792.19904 L 134.72163 905.7226 Z"
 }
 
-#[test]
-#[ignore]
-fn fuzzing_test_case_13() {
+fn fuzzing_test_case_13_path() -> Path {
     let mut builder = Path::builder();
 
     // There are some very close almost horizontal segments somwhere around
@@ -583,12 +586,26 @@ fn fuzzing_test_case_13() {
     builder.line_to(point(303.23447, 681.25366));
     builder.close();
 
-    test_path(builder.build().as_slice());
+    builder.build()
 
     // SVG path syntax:
     // "M 410.68304 821.1684 L 930.137 143.92328 L 104.892136 433.69412 L 660.3361 814.7637 L 677.3176 775.74384 L 1.0851622 766.8102 L 422.32645 774.1579 L 965.11993 775.9433 L 543.46405 972.5189 L 498.56973 739.5371 L 59.104202 990.2475 L 222.88525 571.51117 L 454.01312 816.9873 L 219.92206 961.8081 L 198.50409 103.8456 L 409.76535 863.5788 L 273.72992 489.06696 L 479.42303 773.7393 L 61.974644 866.6973 L 769.39044 347.60333 L 594.88464 818.56824 L 36.028625 811.2928 L 333.66275 314.22592 L 110.678795 817.20044 L 303.23447 681.25366 Z"
 }
 
+#[test]
+#[ignore]
+fn fuzzing_test_case_13() {
+    test_path_with_options(fuzzing_test_case_13_path().as_slice(), FillOptions::tolerance(0.05));
+}
+
+#[test]
+fn fuzzing_test_case_13_robust() {
+    test_path_with_options(
+        fuzzing_test_case_13_path().as_slice(),
+        FillOptions::tolerance(0.05).with_robust_predicates(),
+    );
+}
+
 #[test]
 fn fuzzing_test_case_14() {
     let mut builder = Path::builder();
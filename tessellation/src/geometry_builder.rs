@@ -34,6 +34,12 @@
 //!   Another, simpler example of vertex constructor is the [`Identity`](struct.Identity.html)
 //!   constructor which just returns its input, untransformed.
 //!   `VertexConstructor<Input, Output>` is implemented for all closures `Fn(Input) -> Output`.
+//! * The struct [`ChunkedBuffersBuilder`](struct.ChunkedBuffersBuilder.html) which writes into
+//!   several [`VertexBuffers`](struct.VertexBuffers.html) with 16 bits indices, starting a new
+//!   one when the previous one is full.
+//! * The struct [`OptimizingBuilder`](struct.OptimizingBuilder.html) which welds vertices
+//!   and reorders triangles for the vertex cache before forwarding them to another builder.
+//!   The errors of the other builder are only reported by `OptimizingBuilder::tessellate`.
 //!
 //! Geometry builders are a practical way to add one last step to the tessellation pipeline,
 //! such as applying a transform or clipping the geometry.
@@ -227,8 +233,14 @@
 
 pub use crate::path::{VertexId, Index};
 
+use crate::math::Point;
+use crate::{FillVertex, StrokeVertex, Side, CurveCoordinates, VertexAttributes, VertexSource};
+use crate::TessellationResult;
+
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::ops::Add;
+use std::fmt;
 use std::convert::From;
 use std;
 
@@ -239,6 +251,17 @@ pub enum GeometryBuilderError {
     TooManyVertices,
 }
 
+impl fmt::Display for GeometryBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GeometryBuilderError::InvalidVertex => write!(f, "invalid vertex"),
+            GeometryBuilderError::TooManyVertices => write!(f, "too many vertices"),
+        }
+    }
+}
+
+impl std::error::Error for GeometryBuilderError {}
+
 /// An interface separating tessellators and other geometry generation algorithms from the
 /// actual vertex construction.
 ///
@@ -260,6 +283,22 @@ pub trait GeometryBuilder<Input> {
     /// This method can only be called between begin_geometry and end_geometry.
     fn add_vertex(&mut self, vertex: Input) -> Result<VertexId, GeometryBuilderError>;
 
+    /// Inserts a vertex along with its custom attributes and source (see `VertexAttributes`).
+    ///
+    /// This is only called by the tessellators when tessellating a path that has custom
+    /// attributes, in which case the attributes are interpolated from the ones of the
+    /// path's endpoints, or when the sources of the vertices are requested. The default
+    /// implementation ignores the attributes.
+    ///
+    /// This method can only be called between begin_geometry and end_geometry.
+    fn add_vertex_with_attributes(
+        &mut self,
+        vertex: Input,
+        _attributes: VertexAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.add_vertex(vertex)
+    }
+
     /// Insert a triangle made of vertices that were added after the last call to begin_geometry.
     ///
     /// This method can only be called between begin_geometry and end_geometry.
@@ -274,6 +313,27 @@ pub trait GeometryBuilder<Input> {
 }
 
 
+/// A geometry builder that can also receive triangle strips.
+///
+/// See `StrokeTessellator::tessellate_path_strips`.
+pub trait StripGeometryBuilder<Input>: GeometryBuilder<Input> {
+    /// Adds a vertex to the current strip.
+    ///
+    /// Each vertex forms a triangle with the two previous vertices of the strip.
+    fn add_strip_vertex(&mut self, id: VertexId);
+
+    /// Ends the current strip, the next vertex starts a new one.
+    fn end_strip(&mut self);
+}
+
+/// A geometry builder that can also receive lines.
+///
+/// See `StrokeTessellator::tessellate_path_lines`.
+pub trait LineGeometryBuilder<Input>: GeometryBuilder<Input> {
+    /// Adds a line between two vertices.
+    fn add_line(&mut self, a: VertexId, b: VertexId);
+}
+
 /// An interface with similar goals to `GeometryBuilder` for algorithms that pre-build
 /// the vertex and index buffers.
 ///
@@ -366,9 +426,100 @@ where
 /// A trait specifying how to create vertex values.
 pub trait VertexConstructor<Input, VertexType> {
     fn new_vertex(&mut self, input: Input) -> VertexType;
+
+    /// Creates a vertex from the tessellator's output, the custom attributes interpolated
+    /// from the path and the location in the path it was generated from.
+    ///
+    /// The default implementation ignores the attributes.
+    fn new_vertex_with_attributes(&mut self, input: Input, _attributes: VertexAttributes) -> VertexType {
+        self.new_vertex(input)
+    }
+}
+
+// Sends a recorded vertex to the output along with the side data it came with.
+pub(crate) fn add_recorded_vertex<Input>(
+    output: &mut dyn GeometryBuilder<Input>,
+    vertex: Input,
+    attributes: VertexAttributes,
+) -> Result<VertexId, GeometryBuilderError> {
+    if attributes.is_empty() {
+        output.add_vertex(vertex)
+    } else {
+        output.add_vertex_with_attributes(vertex, attributes)
+    }
+}
+
+// The side data of a vertex recorded by a geometry builder, the custom attributes being
+// stored in a separate buffer.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct RecordedAttributes {
+    // Range in the buffer.
+    custom: (usize, usize),
+    source: Option<VertexSource>,
+    coverage: f32,
+    curve: CurveCoordinates,
+}
+
+impl RecordedAttributes {
+    pub(crate) fn new(attributes: VertexAttributes, buffer: &mut Vec<f32>) -> Self {
+        let start = buffer.len();
+        buffer.extend_from_slice(attributes.custom);
+        RecordedAttributes {
+            custom: (start, buffer.len()),
+            source: attributes.source,
+            coverage: attributes.coverage,
+            curve: attributes.curve,
+        }
+    }
+
+    pub(crate) fn get<'l>(&self, buffer: &'l [f32]) -> VertexAttributes<'l> {
+        VertexAttributes {
+            custom: &buffer[self.custom.0..self.custom.1],
+            source: self.source,
+            coverage: self.coverage,
+            curve: self.curve,
+        }
+    }
+
+    // Interpolates the attributes, taking the source of the closest vertex.
+    pub(crate) fn lerp(&self, other: &Self, t: f32, buffer: &mut Vec<f32>) -> Self {
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        let start = buffer.len();
+        for i in 0..(self.custom.1 - self.custom.0) {
+            let from = buffer[self.custom.0 + i];
+            let to = buffer[other.custom.0 + i];
+            buffer.push(from + (to - from) * t);
+        }
+        RecordedAttributes {
+            custom: (start, buffer.len()),
+            source: if t < 0.5 { self.source } else { other.source },
+            coverage: lerp(self.coverage, other.coverage),
+            curve: CurveCoordinates {
+                u: lerp(self.curve.u, other.curve.u),
+                v: lerp(self.curve.v, other.curve.v),
+                sign: lerp(self.curve.sign, other.curve.sign),
+            },
+        }
+    }
+
+    // Whether the vertices can be welded: the other attributes are within `epsilon` of
+    // each other and the vertices come from the same sub-path.
+    pub(crate) fn can_weld(&self, other: &Self, buffer: &[f32], epsilon: f32) -> bool {
+        let a = &buffer[self.custom.0..self.custom.1];
+        let b = &buffer[other.custom.0..other.custom.1];
+        let close = |a: f32, b: f32| (a - b).abs() <= epsilon;
+        a.len() == b.len()
+            && a.iter().zip(b).all(|(a, b)| close(*a, *b))
+            && close(self.coverage, other.coverage)
+            && close(self.curve.u, other.curve.u)
+            && close(self.curve.v, other.curve.v)
+            && close(self.curve.sign, other.curve.sign)
+            && self.source.map(|s| s.sub_path) == other.source.map(|s| s.sub_path)
+    }
 }
 
 /// A dummy vertex constructor that just forwards its inputs.
+#[derive(Copy, Clone, Debug)]
 pub struct Identity;
 impl<T> VertexConstructor<T, T> for Identity {
     fn new_vertex(&mut self, input: T) -> T { input }
@@ -383,11 +534,16 @@ impl<F, Input, VertexType> VertexConstructor<Input, VertexType> for F
 }
 
 /// A `BuffersBuilder` that takes the actual vertex type as input.
-pub type SimpleBuffersBuilder<'l, VertexType> = BuffersBuilder<'l, VertexType, u16, VertexType, Identity>;
+///
+/// The index type defaults to `u16`, any type implementing `MaxIndex` can be used.
+pub type SimpleBuffersBuilder<'l, VertexType, IndexType = u16> = BuffersBuilder<'l, VertexType, IndexType, VertexType, Identity>;
 
 /// Creates a `SimpleBuffersBuilder`.
-pub fn simple_builder<VertexType>(buffers: &mut VertexBuffers<VertexType, u16>)
-    -> SimpleBuffersBuilder<VertexType> {
+///
+/// The index type is the one of the buffers, for example `u32` to tessellate shapes
+/// that have more than 65535 vertices.
+pub fn simple_builder<VertexType, IndexType>(buffers: &mut VertexBuffers<VertexType, IndexType>)
+    -> SimpleBuffersBuilder<VertexType, IndexType> {
     let vertex_offset = buffers.vertices.len() as Index;
     let index_offset = buffers.indices.len() as Index;
     BuffersBuilder {
@@ -445,6 +601,20 @@ where
         Ok(VertexId((len - 1) as Index - self.vertex_offset))
     }
 
+    fn add_vertex_with_attributes(
+        &mut self,
+        v: Input,
+        attributes: VertexAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let vertex = self.vertex_constructor.new_vertex_with_attributes(v, attributes);
+        self.buffers.vertices.push(vertex);
+        let len = self.buffers.vertices.len();
+        if len > IndexType::max_index() {
+            return Err(GeometryBuilderError::TooManyVertices);
+        }
+        Ok(VertexId((len - 1) as Index - self.vertex_offset))
+    }
+
     fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
         self.buffers.indices.push((a + self.vertex_offset).into());
         self.buffers.indices.push((b + self.vertex_offset).into());
@@ -457,6 +627,38 @@ where
     }
 }
 
+impl<'l, VertexType, IndexType, Input, Ctor> StripGeometryBuilder<Input>
+    for BuffersBuilder<'l, VertexType, IndexType, Input, Ctor>
+where
+    VertexType: 'l + Clone,
+    IndexType: Add + From<VertexId> + MaxIndex,
+    Ctor: VertexConstructor<Input, VertexType>,
+{
+    fn add_strip_vertex(&mut self, id: VertexId) {
+        self.buffers.indices.push((id + self.vertex_offset).into());
+    }
+
+    /// Adds the primitive restart index, which is the maximum value of the index type.
+    ///
+    /// Vertices can't use this index, so the buffers must have less vertices than that.
+    fn end_strip(&mut self) {
+        self.buffers.indices.push(VertexId(IndexType::max_index() as Index).into());
+    }
+}
+
+impl<'l, VertexType, IndexType, Input, Ctor> LineGeometryBuilder<Input>
+    for BuffersBuilder<'l, VertexType, IndexType, Input, Ctor>
+where
+    VertexType: 'l + Clone,
+    IndexType: Add + From<VertexId> + MaxIndex,
+    Ctor: VertexConstructor<Input, VertexType>,
+{
+    fn add_line(&mut self, a: VertexId, b: VertexId) {
+        self.buffers.indices.push((a + self.vertex_offset).into());
+        self.buffers.indices.push((b + self.vertex_offset).into());
+    }
+}
+
 impl<'l, VertexType, IndexType, InputVertex, Ctor> GeometryReceiver<InputVertex>
     for BuffersBuilder<'l, VertexType, IndexType, InputVertex, Ctor>
 where
@@ -523,6 +725,579 @@ impl<V> GeometryReceiver<V> for NoOutput {
     fn set_geometry(&mut self, _vertices: &[V], _indices: &[u32]) {}
 }
 
+/// A geometry builder that writes into several `VertexBuffers` with 16 bits indices.
+///
+/// When a vertex doesn't fit in the current chunk (its index would be larger than
+/// `u16::MAX`), a new chunk is started. The vertices of earlier chunks that are used by
+/// triangles of the new chunk are duplicated in the new chunk, so that each chunk can be
+/// drawn separately. As a result, adding vertices never fails with `TooManyVertices`.
+///
+/// The `Count` returned by `end_geometry` includes the duplicated vertices.
+///
+/// ## Example
+///
+/// ```
+/// # extern crate lyon_tessellation as tess;
+/// # use tess::geometry_builder::{ChunkedBuffersBuilder, Identity, VertexBuffers};
+/// # use tess::basic_shapes::fill_circle;
+/// # use tess::math::point;
+/// # use tess::{FillOptions, FillVertex};
+/// # fn main() {
+/// let mut chunks: Vec<VertexBuffers<FillVertex, u16>> = Vec::new();
+/// fill_circle(
+///     point(0.0, 0.0),
+///     100.0,
+///     &FillOptions::tolerance(0.01),
+///     // Use tiny chunks for the sake of the example.
+///     &mut ChunkedBuffersBuilder::new(&mut chunks, Identity).with_max_vertices_per_chunk(100),
+/// ).unwrap();
+///
+/// assert!(chunks.len() > 1);
+/// for chunk in &chunks {
+///     assert!(chunk.vertices.len() <= 100);
+/// }
+/// # }
+/// ```
+pub struct ChunkedBuffersBuilder<'l, VertexType: 'l, Input, Ctor> {
+    chunks: &'l mut Vec<VertexBuffers<VertexType, u16>>,
+    vertex_constructor: Ctor,
+    max_vertices: usize,
+    // The chunk and the index in the chunk of each vertex of the current geometry.
+    locations: Vec<(usize, u16)>,
+    // State at the beginning of the geometry, to be able to abort it.
+    num_chunks: usize,
+    vertex_offset: usize,
+    index_offset: usize,
+    count: Count,
+    _marker: PhantomData<Input>,
+}
+
+impl<'l, VertexType: 'l, Input, Ctor> ChunkedBuffersBuilder<'l, VertexType, Input, Ctor> {
+    /// Creates a builder that adds geometry to the last chunk of `chunks` and to new chunks.
+    pub fn new(chunks: &'l mut Vec<VertexBuffers<VertexType, u16>>, ctor: Ctor) -> Self {
+        ChunkedBuffersBuilder {
+            chunks,
+            vertex_constructor: ctor,
+            max_vertices: u16::MAX as usize + 1,
+            locations: Vec::new(),
+            num_chunks: 0,
+            vertex_offset: 0,
+            index_offset: 0,
+            count: Count { vertices: 0, indices: 0 },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets the maximum number of vertices per chunk, at most (and by default) 65536.
+    pub fn with_max_vertices_per_chunk(mut self, max_vertices: usize) -> Self {
+        assert!(max_vertices >= 3);
+        self.max_vertices = max_vertices.min(u16::MAX as usize + 1);
+        self
+    }
+
+    pub fn chunks<'a, 'b: 'a>(&'b self) -> &'a [VertexBuffers<VertexType, u16>] {
+        self.chunks
+    }
+
+    // Makes sure that the last chunk can receive `num_vertices` more vertices.
+    fn reserve(&mut self, num_vertices: usize) {
+        let full = match self.chunks.last() {
+            Some(chunk) => chunk.vertices.len() + num_vertices > self.max_vertices,
+            None => true,
+        };
+        if full {
+            self.chunks.push(VertexBuffers::new());
+        }
+    }
+
+    fn push_vertex(&mut self, vertex: VertexType) -> Result<VertexId, GeometryBuilderError> {
+        self.reserve(1);
+        let chunk_idx = self.chunks.len() - 1;
+        let chunk = &mut self.chunks[chunk_idx];
+        chunk.vertices.push(vertex);
+        self.locations.push((chunk_idx, (chunk.vertices.len() - 1) as u16));
+        self.count.vertices += 1;
+
+        Ok(VertexId::from_usize(self.locations.len() - 1))
+    }
+}
+
+impl<'l, VertexType, Input, Ctor> GeometryBuilder<Input>
+    for ChunkedBuffersBuilder<'l, VertexType, Input, Ctor>
+where
+    VertexType: 'l + Clone,
+    Ctor: VertexConstructor<Input, VertexType>,
+{
+    fn begin_geometry(&mut self) {
+        self.locations.clear();
+        self.count = Count { vertices: 0, indices: 0 };
+        self.num_chunks = self.chunks.len();
+        let (vertex_offset, index_offset) = match self.chunks.last() {
+            Some(chunk) => (chunk.vertices.len(), chunk.indices.len()),
+            None => (0, 0),
+        };
+        self.vertex_offset = vertex_offset;
+        self.index_offset = index_offset;
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        self.count
+    }
+
+    fn add_vertex(&mut self, v: Input) -> Result<VertexId, GeometryBuilderError> {
+        let vertex = self.vertex_constructor.new_vertex(v);
+        self.push_vertex(vertex)
+    }
+
+    fn add_vertex_with_attributes(
+        &mut self,
+        v: Input,
+        attributes: VertexAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let vertex = self.vertex_constructor.new_vertex_with_attributes(v, attributes);
+        self.push_vertex(vertex)
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        let ids = [a.to_usize(), b.to_usize(), c.to_usize()];
+        let last = self.chunks.len() - 1;
+        let num_missing = ids.iter().filter(|&&id| self.locations[id].0 != last).count();
+        if num_missing > 0 {
+            // The vertices that are in earlier chunks are copied into the last one, or
+            // all of them into a new chunk if they don't fit.
+            self.reserve(num_missing);
+            let last = self.chunks.len() - 1;
+            for &id in &ids {
+                let (chunk, idx) = self.locations[id];
+                if chunk == last {
+                    continue;
+                }
+                let vertex = self.chunks[chunk].vertices[idx as usize].clone();
+                let vertices = &mut self.chunks[last].vertices;
+                vertices.push(vertex);
+                self.locations[id] = (last, (vertices.len() - 1) as u16);
+                self.count.vertices += 1;
+            }
+        }
+
+        let last = self.chunks.len() - 1;
+        for &id in &ids {
+            let idx = self.locations[id].1;
+            self.chunks[last].indices.push(idx);
+        }
+        self.count.indices += 3;
+    }
+
+    fn abort_geometry(&mut self) {
+        self.chunks.truncate(self.num_chunks);
+        if let Some(chunk) = self.chunks.last_mut() {
+            chunk.vertices.truncate(self.vertex_offset);
+            chunk.indices.truncate(self.index_offset);
+        }
+        self.locations.clear();
+    }
+}
+
+/// Vertex types that can be welded by the `OptimizingBuilder`.
+pub trait WeldVertex {
+    /// The position of the vertex.
+    fn position(&self) -> Point;
+
+    /// Adds the other components of the vertex that must be equal for two vertices to be
+    /// welded.
+    fn components(&self, _output: &mut Vec<f32>) {}
+}
+
+impl WeldVertex for Point {
+    fn position(&self) -> Point { *self }
+}
+
+impl WeldVertex for FillVertex {
+    fn position(&self) -> Point { self.position }
+
+    fn components(&self, output: &mut Vec<f32>) {
+        output.extend_from_slice(&[
+            self.normal.x,
+            self.normal.y,
+        ]);
+    }
+}
+
+impl WeldVertex for StrokeVertex {
+    fn position(&self) -> Point { self.position }
+
+    fn components(&self, output: &mut Vec<f32>) {
+        let side = match self.side {
+            Side::Left => -1.0,
+            Side::Right => 1.0,
+        };
+        output.extend_from_slice(&[
+            self.normal.x,
+            self.normal.y,
+            self.advancement,
+            side,
+        ]);
+    }
+}
+
+/// A geometry builder that welds vertices and reorders triangles before sending them to
+/// another geometry builder.
+///
+/// The geometry is recorded until `end_geometry` is called. Then:
+///
+/// - Vertices whose positions, other components (see `WeldVertex`) and attributes
+///   (see `VertexAttributes`) are all within `epsilon` of each other are merged into a single vertex. Vertices
+///   generated from different sub-paths (see `VertexSource`) are not merged.
+/// - Triangles that became degenerate are removed.
+/// - Triangles are reordered to improve the hit rate of the GPU's post-transform vertex
+///   cache (using the Tipsify algorithm), and vertices are sent to the output in the order
+///   in which the triangles first use them.
+///
+/// `end_geometry` returns the count of the optimized geometry, while `input_count` provides
+/// the count of the geometry produced by the tessellator.
+///
+/// ## Errors
+///
+/// The geometry is only sent to the output in `end_geometry`, which can't return an error.
+/// If the output fails to receive a vertex, the geometry is aborted, `end_geometry` returns
+/// an empty count and the tessellator reports a success. Run the tessellator with
+/// `OptimizingBuilder::tessellate` to get the error of the output, or check `error`
+/// after the tessellation.
+///
+/// ## Example
+///
+/// ```
+/// # extern crate lyon_tessellation as tess;
+/// # use tess::geometry_builder::{OptimizingBuilder, VertexBuffers, simple_builder};
+/// # use tess::basic_shapes::stroke_polyline;
+/// # use tess::math::point;
+/// # use tess::{StrokeOptions, StrokeVertex};
+/// # fn main() {
+/// let mut buffers: VertexBuffers<StrokeVertex, u16> = VertexBuffers::new();
+/// let mut output = simple_builder(&mut buffers);
+/// let mut builder = OptimizingBuilder::new(&mut output);
+/// let count = builder.tessellate(|builder| {
+///     stroke_polyline(
+///         [point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0)].iter().cloned(),
+///         false,
+///         &StrokeOptions::default(),
+///         builder,
+///     )
+/// }).unwrap();
+///
+/// assert!(count.vertices <= builder.input_count().vertices);
+/// # }
+/// ```
+pub struct OptimizingBuilder<'l, Input> {
+    output: &'l mut dyn GeometryBuilder<Input>,
+    epsilon: f32,
+    cache_size: usize,
+    vertices: Vec<Input>,
+    attributes: Vec<RecordedAttributes>,
+    custom_attributes: Vec<f32>,
+    triangles: Vec<[usize; 3]>,
+    input_count: Count,
+    error: Option<GeometryBuilderError>,
+}
+
+impl<'l, Input: WeldVertex + Clone> OptimizingBuilder<'l, Input> {
+    /// Default maximum distance between the components of welded vertices.
+    pub const DEFAULT_EPSILON: f32 = 0.0001;
+    /// Default size of the vertex cache that the triangles are ordered for.
+    pub const DEFAULT_CACHE_SIZE: usize = 16;
+
+    pub fn new(output: &'l mut dyn GeometryBuilder<Input>) -> Self {
+        OptimizingBuilder {
+            output,
+            epsilon: Self::DEFAULT_EPSILON,
+            cache_size: Self::DEFAULT_CACHE_SIZE,
+            vertices: Vec::new(),
+            attributes: Vec::new(),
+            custom_attributes: Vec::new(),
+            triangles: Vec::new(),
+            input_count: Count { vertices: 0, indices: 0 },
+            error: None,
+        }
+    }
+
+    /// Sets the maximum distance between the components of welded vertices.
+    ///
+    /// With an epsilon of zero, only identical vertices are welded.
+    pub fn with_epsilon(mut self, epsilon: f32) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Sets the size of the vertex cache that the triangles are ordered for.
+    pub fn with_cache_size(mut self, cache_size: usize) -> Self {
+        self.cache_size = cache_size;
+        self
+    }
+
+    /// The number of vertices and indices produced by the tessellator for the last geometry.
+    pub fn input_count(&self) -> Count {
+        self.input_count
+    }
+
+    /// The error that happened while sending the last geometry to the output, if any.
+    ///
+    /// The tessellators don't see this error, see `OptimizingBuilder::tessellate`.
+    pub fn error(&self) -> Option<GeometryBuilderError> {
+        self.error
+    }
+
+    /// Runs a tessellation with this builder.
+    ///
+    /// Returns the error of the tessellation, or the error that happened while sending the
+    /// optimized geometry to the output.
+    pub fn tessellate(
+        &mut self,
+        tessellate: impl FnOnce(&mut Self) -> TessellationResult,
+    ) -> TessellationResult {
+        let count = tessellate(self)?;
+        match self.error {
+            Some(e) => Err(e.into()),
+            None => Ok(count),
+        }
+    }
+
+    fn record(
+        &mut self,
+        vertex: Input,
+        attributes: VertexAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.attributes.push(RecordedAttributes::new(attributes, &mut self.custom_attributes));
+        self.vertices.push(vertex);
+        self.input_count.vertices += 1;
+
+        Ok(VertexId::from_usize(self.vertices.len() - 1))
+    }
+
+    fn clear(&mut self) {
+        self.vertices.clear();
+        self.attributes.clear();
+        self.custom_attributes.clear();
+        self.triangles.clear();
+        self.input_count = Count { vertices: 0, indices: 0 };
+    }
+
+    // Maps each vertex to the first vertex it is welded with.
+    fn weld(&self) -> Vec<usize> {
+        let epsilon = self.epsilon;
+        let cell = |p: Point| -> (i64, i64) {
+            if epsilon > 0.0 {
+                ((p.x / epsilon).floor() as i64, (p.y / epsilon).floor() as i64)
+            } else {
+                (p.x.to_bits() as i64, p.y.to_bits() as i64)
+            }
+        };
+        let neighbors: &[i64] = if epsilon > 0.0 { &[-1, 0, 1] } else { &[0] };
+
+        let mut components = Vec::new();
+        let mut component_ranges = Vec::with_capacity(self.vertices.len());
+        for vertex in &self.vertices {
+            let start = components.len();
+            vertex.components(&mut components);
+            component_ranges.push((start, components.len()));
+        }
+
+        let close = |a: &[f32], b: &[f32]| {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() <= epsilon)
+        };
+
+        let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        let mut representatives = Vec::with_capacity(self.vertices.len());
+        for (idx, vertex) in self.vertices.iter().enumerate() {
+            let position = vertex.position();
+            let (x, y) = cell(position);
+            let (c_start, c_end) = component_ranges[idx];
+
+            let mut found = None;
+            'search: for dx in neighbors {
+                for dy in neighbors {
+                    let candidates = match grid.get(&(x + dx, y + dy)) {
+                        Some(candidates) => candidates,
+                        None => { continue; }
+                    };
+                    for &candidate in candidates {
+                        let other = self.vertices[candidate].position();
+                        let (oc_start, oc_end) = component_ranges[candidate];
+                        let attributes = &self.attributes[candidate];
+                        if (position.x - other.x).abs() <= epsilon
+                            && (position.y - other.y).abs() <= epsilon
+                            && close(&components[c_start..c_end], &components[oc_start..oc_end])
+                            && self.attributes[idx].can_weld(attributes, &self.custom_attributes, epsilon) {
+                            found = Some(candidate);
+                            break 'search;
+                        }
+                    }
+                }
+            }
+
+            representatives.push(match found {
+                Some(candidate) => candidate,
+                None => {
+                    grid.entry((x, y)).or_default().push(idx);
+                    idx
+                }
+            });
+        }
+
+        representatives
+    }
+
+    fn emit(&mut self, triangles: &[[usize; 3]]) -> Result<(), GeometryBuilderError> {
+        let mut ids: Vec<Option<VertexId>> = vec![None; self.vertices.len()];
+        for triangle in triangles {
+            let mut output_ids = [VertexId::INVALID; 3];
+            for i in 0..3 {
+                let idx = triangle[i];
+                output_ids[i] = match ids[idx] {
+                    Some(id) => id,
+                    None => {
+                        let vertex = self.vertices[idx].clone();
+                        let attributes = self.attributes[idx].get(&self.custom_attributes);
+                        let id = add_recorded_vertex(&mut *self.output, vertex, attributes)?;
+                        ids[idx] = Some(id);
+                        id
+                    }
+                };
+            }
+            self.output.add_triangle(output_ids[0], output_ids[1], output_ids[2]);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'l, Input: WeldVertex + Clone> GeometryBuilder<Input> for OptimizingBuilder<'l, Input> {
+    fn begin_geometry(&mut self) {
+        self.clear();
+        self.error = None;
+    }
+
+    // Errors are stored in self.error, see OptimizingBuilder::tessellate.
+    fn end_geometry(&mut self) -> Count {
+        let representatives = self.weld();
+        let mut triangles = Vec::with_capacity(self.triangles.len());
+        for triangle in &self.triangles {
+            let [a, b, c] = [representatives[triangle[0]], representatives[triangle[1]], representatives[triangle[2]]];
+            if a != b && b != c && a != c {
+                triangles.push([a, b, c]);
+            }
+        }
+        let triangles = tipsify(&triangles, self.vertices.len(), self.cache_size);
+
+        self.output.begin_geometry();
+        if let Err(e) = self.emit(&triangles) {
+            self.output.abort_geometry();
+            self.error = Some(e);
+            return Count { vertices: 0, indices: 0 };
+        }
+
+        self.output.end_geometry()
+    }
+
+    fn add_vertex(&mut self, vertex: Input) -> Result<VertexId, GeometryBuilderError> {
+        self.record(vertex, VertexAttributes::NONE)
+    }
+
+    fn add_vertex_with_attributes(
+        &mut self,
+        vertex: Input,
+        attributes: VertexAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.record(vertex, attributes)
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.triangles.push([a.to_usize(), b.to_usize(), c.to_usize()]);
+        self.input_count.indices += 3;
+    }
+
+    fn abort_geometry(&mut self) {
+        self.clear();
+    }
+}
+
+// Orders the triangles for a vertex cache of the provided size.
+//
+// See "Fast Triangle Reordering for Vertex Locality and Reduced Overdraw",
+// Sander, Nehab and Barczak, 2007.
+fn tipsify(triangles: &[[usize; 3]], num_vertices: usize, cache_size: usize) -> Vec<[usize; 3]> {
+    // The triangles that use each vertex.
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); num_vertices];
+    for (idx, triangle) in triangles.iter().enumerate() {
+        for &vertex in triangle {
+            adjacency[vertex].push(idx);
+        }
+    }
+    // Number of triangles that use each vertex and are not emitted yet.
+    let mut live: Vec<usize> = adjacency.iter().map(|triangles| triangles.len()).collect();
+    // Time at which each vertex entered the cache.
+    let mut cache_time = vec![0; num_vertices];
+    let mut emitted = vec![false; triangles.len()];
+    let mut dead_end = Vec::new();
+    let mut time = cache_size + 1;
+    let mut cursor = 0;
+
+    let mut output = Vec::with_capacity(triangles.len());
+    let mut candidates = Vec::new();
+    let mut fanning = live.iter().position(|&count| count > 0);
+    while let Some(vertex) = fanning {
+        candidates.clear();
+        for &t in &adjacency[vertex] {
+            if emitted[t] {
+                continue;
+            }
+            emitted[t] = true;
+            output.push(triangles[t]);
+            for &v in &triangles[t] {
+                dead_end.push(v);
+                candidates.push(v);
+                live[v] -= 1;
+                if time - cache_time[v] > cache_size {
+                    cache_time[v] = time;
+                    time += 1;
+                }
+            }
+        }
+
+        // Prefer the candidates that will still be in the cache once their remaining
+        // triangles are emitted.
+        let mut best = None;
+        let mut best_priority = 0;
+        for &v in &candidates {
+            if live[v] == 0 {
+                continue;
+            }
+            let age = time - cache_time[v];
+            let priority = if age + 2 * live[v] <= cache_size { age + 1 } else { 0 };
+            if best.is_none() || priority > best_priority {
+                best = Some(v);
+                best_priority = priority;
+            }
+        }
+
+        fanning = best.or_else(|| {
+            while let Some(v) = dead_end.pop() {
+                if live[v] > 0 {
+                    return Some(v);
+                }
+            }
+            while cursor < num_vertices {
+                cursor += 1;
+                if live[cursor - 1] > 0 {
+                    return Some(cursor - 1);
+                }
+            }
+            None
+        });
+    }
+
+    output
+}
+
 /// Provides the maximum value of an index.
 ///
 /// This should be the maximum value representable by the index type up
@@ -544,6 +1319,71 @@ impl MaxIndex for i64 { fn max_index() -> usize { std::u32::MAX as usize } }
 impl MaxIndex for usize { fn max_index() -> usize { std::u32::MAX as usize } }
 impl MaxIndex for isize { fn max_index() -> usize { std::u32::MAX as usize } }
 
+/// Sum of the areas of the triangles in the buffers.
+#[cfg(test)]
+pub(crate) fn triangles_area<V, I>(buffers: &VertexBuffers<V, I>, position: impl Fn(&V) -> Point) -> f32
+where
+    I: Copy + Into<u32>,
+{
+    buffers.indices.chunks(3).map(|t| {
+        let a = position(&buffers.vertices[t[0].into() as usize]);
+        let b = position(&buffers.vertices[t[1].into() as usize]);
+        let c = position(&buffers.vertices[t[2].into() as usize]);
+        (b - a).cross(c - a).abs() * 0.5
+    }).sum()
+}
+
+// A vertex that records the data passed to `add_vertex_with_attributes`.
+#[cfg(test)]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct AttributedVertex {
+    pub(crate) position: Point,
+    pub(crate) normal: crate::math::Vector,
+    pub(crate) attributes: Vec<f32>,
+    pub(crate) source: Option<VertexSource>,
+    pub(crate) coverage: f32,
+    pub(crate) curve: CurveCoordinates,
+}
+
+#[cfg(test)]
+pub(crate) struct WithAttributes;
+
+#[cfg(test)]
+impl VertexConstructor<FillVertex, AttributedVertex> for WithAttributes {
+    fn new_vertex(&mut self, vertex: FillVertex) -> AttributedVertex {
+        self.new_vertex_with_attributes(vertex, VertexAttributes::NONE)
+    }
+
+    fn new_vertex_with_attributes(&mut self, vertex: FillVertex, attributes: VertexAttributes) -> AttributedVertex {
+        AttributedVertex {
+            position: vertex.position,
+            normal: vertex.normal,
+            attributes: attributes.custom.to_vec(),
+            source: attributes.source,
+            coverage: attributes.coverage,
+            curve: attributes.curve,
+        }
+    }
+}
+
+#[cfg(test)]
+impl VertexConstructor<StrokeVertex, AttributedVertex> for WithAttributes {
+    fn new_vertex(&mut self, vertex: StrokeVertex) -> AttributedVertex {
+        self.new_vertex_with_attributes(vertex, VertexAttributes::NONE)
+    }
+
+    fn new_vertex_with_attributes(&mut self, vertex: StrokeVertex, attributes: VertexAttributes) -> AttributedVertex {
+        AttributedVertex {
+            position: vertex.position,
+            normal: vertex.normal,
+            attributes: attributes.custom.to_vec(),
+            source: attributes.source,
+            coverage: attributes.coverage,
+            curve: attributes.curve,
+        }
+    }
+}
+
 #[test]
 fn test_simple_quad() {
     #[derive(Copy, Clone, PartialEq, Debug)]
@@ -692,3 +1532,163 @@ fn test_closure() {
         point(1.0, 1.0),
     ]);
 }
+
+#[test]
+fn test_simple_builder_index_types() {
+    use crate::math::{Point, point};
+
+    fn add_vertices<Builder: GeometryBuilder<Point>>(builder: &mut Builder, n: u32) -> Result<Count, GeometryBuilderError> {
+        builder.begin_geometry();
+        for i in 0..n {
+            let id = builder.add_vertex(point(i as f32, 0.0))?;
+            if i >= 2 {
+                builder.add_triangle(id - 2, id - 1, id);
+            }
+        }
+        Ok(builder.end_geometry())
+    }
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    assert_eq!(
+        add_vertices(&mut simple_builder(&mut buffers), 70_000),
+        Err(GeometryBuilderError::TooManyVertices)
+    );
+
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+    let count = add_vertices(&mut simple_builder(&mut buffers), 70_000).unwrap();
+    assert_eq!(count.vertices, 70_000);
+    assert_eq!(buffers.indices.last(), Some(&69_999));
+}
+
+#[test]
+fn test_chunked_builder() {
+    use crate::math::{Point, point};
+    use crate::path::Path;
+    use crate::{FillOptions, FillTessellator, FillVertex};
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(100.0, -50.0), point(200.0, 250.0), point(0.0, 100.0));
+    builder.close();
+    builder.move_to(point(10.0, 10.0));
+    builder.quadratic_bezier_to(point(50.0, 20.0), point(20.0, 50.0));
+    builder.close();
+    let path = builder.build();
+    let options = FillOptions::tolerance(0.01);
+
+    let mut expected: VertexBuffers<FillVertex, u32> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(path.iter(), &options, &mut simple_builder(&mut expected)).unwrap();
+
+    // Start with a non-empty chunk.
+    let mut chunks = vec![VertexBuffers::new()];
+    chunks[0].vertices.push(expected.vertices[0]);
+    let count = FillTessellator::new().tessellate_path(
+        path.iter(),
+        &options,
+        &mut ChunkedBuffersBuilder::new(&mut chunks, Identity).with_max_vertices_per_chunk(50),
+    ).unwrap();
+
+    assert!(chunks.len() > 2);
+    assert_eq!(count.indices as usize, expected.indices.len());
+    let num_vertices: usize = chunks.iter().map(|chunk| chunk.vertices.len()).sum();
+    assert_eq!(count.vertices as usize, num_vertices - 1);
+
+    let triangles = |vertices: &[FillVertex], indices: &[u32]| -> Vec<[Point; 3]> {
+        indices.chunks(3).map(|t| [
+            vertices[t[0] as usize].position,
+            vertices[t[1] as usize].position,
+            vertices[t[2] as usize].position,
+        ]).collect()
+    };
+    let mut chunked_triangles = Vec::new();
+    for chunk in &chunks {
+        assert!(chunk.vertices.len() <= 50);
+        let indices: Vec<u32> = chunk.indices.iter().map(|&i| i as u32).collect();
+        chunked_triangles.extend(triangles(&chunk.vertices, &indices));
+    }
+    assert_eq!(chunked_triangles, triangles(&expected.vertices, &expected.indices));
+}
+
+#[test]
+fn test_optimizing_builder() {
+    use crate::math::{Point, point};
+
+    // Average number of cache misses per triangle with a FIFO cache.
+    fn acmr(indices: &[u16], cache_size: usize) -> f32 {
+        let mut cache = std::collections::VecDeque::new();
+        let mut misses = 0;
+        for &index in indices {
+            if !cache.contains(&index) {
+                misses += 1;
+                cache.push_back(index);
+                if cache.len() > cache_size {
+                    cache.pop_front();
+                }
+            }
+        }
+        misses as f32 / (indices.len() / 3) as f32
+    }
+
+    // A grid of quads that don't share their vertices, with a degenerate triangle.
+    fn add_grid<Builder: GeometryBuilder<Point>>(output: &mut Builder, size: u32) -> Count {
+        output.begin_geometry();
+        for y in 0..size {
+            for x in 0..size {
+                let (x, y) = (x as f32, y as f32);
+                let a = output.add_vertex(point(x, y)).unwrap();
+                let b = output.add_vertex(point(x + 1.0, y)).unwrap();
+                let c = output.add_vertex(point(x + 1.0, y + 1.00001)).unwrap();
+                let d = output.add_vertex(point(x, y + 1.0)).unwrap();
+                output.add_triangle(a, b, c);
+                output.add_triangle(a, c, d);
+            }
+        }
+        let a = output.add_vertex(point(0.0, 0.0)).unwrap();
+        let b = output.add_vertex(point(0.0, 0.0)).unwrap();
+        let c = output.add_vertex(point(1.0, 0.0)).unwrap();
+        output.add_triangle(a, b, c);
+        output.end_geometry()
+    }
+
+    let mut expected: VertexBuffers<Point, u16> = VertexBuffers::new();
+    add_grid(&mut simple_builder(&mut expected), 20);
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut output = simple_builder(&mut buffers);
+    let mut optimizer = OptimizingBuilder::new(&mut output);
+    let count = add_grid(&mut optimizer, 20);
+    let input_count = optimizer.input_count();
+    assert!(optimizer.error().is_none());
+
+    assert_eq!(input_count.vertices as usize, expected.vertices.len());
+    assert_eq!(input_count.indices as usize, expected.indices.len());
+    assert_eq!(count.vertices as usize, buffers.vertices.len());
+    assert_eq!(count.indices as usize, buffers.indices.len());
+    assert_eq!(buffers.vertices.len(), 21 * 21);
+    assert_eq!(buffers.indices.len(), 20 * 20 * 6);
+
+    // The same area is covered.
+    let area = |buffers: &VertexBuffers<Point, u16>| triangles_area(buffers, |&p| p);
+    assert!((area(&buffers) - area(&expected)).abs() < 0.01);
+
+    let cache_size = OptimizingBuilder::<Point>::DEFAULT_CACHE_SIZE;
+    assert!(acmr(&buffers.indices, cache_size) < 1.0);
+    assert!(acmr(&buffers.indices, cache_size) < acmr(&expected.indices, cache_size));
+
+    // Without epsilon, only the identical vertices are welded. The top right corner of the
+    // grid is only produced with the offset.
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut output = simple_builder(&mut buffers);
+    add_grid(&mut OptimizingBuilder::new(&mut output).with_epsilon(0.0), 20);
+    assert_eq!(buffers.vertices.len(), 21 * 21 - 1 + 20 * 20);
+
+    // The errors of the output are reported by tessellate.
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut output = simple_builder(&mut buffers);
+    let mut optimizer = OptimizingBuilder::new(&mut output);
+    let result = optimizer.tessellate(|builder| Ok(add_grid(builder, 20)));
+    assert!(result.is_ok());
+    let result = optimizer.tessellate(|builder| Ok(add_grid(builder, 300)));
+    assert_eq!(result, Err(crate::TessellationError::TooManyVertices));
+    assert_eq!(optimizer.error(), Some(GeometryBuilderError::TooManyVertices));
+}
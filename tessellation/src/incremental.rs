@@ -0,0 +1,635 @@
+use crate::geometry_builder::{BuffersBuilder, Identity, VertexBuffers};
+use crate::math::*;
+use crate::geom::LineSegment;
+use crate::path::{Path, PathEvent, FlattenedEvent};
+use crate::path::iterator::PathIterator;
+use crate::path::Cursor;
+use crate::{FillEvents, FillOptions, FillTessellator, FillVertex, TessellationError};
+
+use std::mem::discriminant;
+use std::ops::Range;
+
+/// A fill tessellation that is kept around to be updated when the path changes.
+///
+/// The shape is tessellated in horizontal bands of a fixed height which are stored
+/// contiguously in the vertex and index buffers. The flattened sub-paths and the
+/// `FillEvents` of each band are kept. When some of the path's events change, only the
+/// modified sub-paths are flattened again, and among the bands overlapping the events
+/// before and after the change, only the ones whose events differ are tessellated again.
+/// The returned `DirtyRanges` tell which parts of the buffers must be uploaded to the GPU.
+///
+/// The indices refer to the whole vertex buffer. The vertices on the boundary of two
+/// bands are duplicated in each band, and their normals are not meaningful.
+///
+/// The `anti_aliasing` and `curve_triangles` fill options are not supported and ignored.
+///
+/// ## Example
+///
+/// ```
+/// # extern crate lyon_tessellation as tess;
+/// # use tess::path::Path;
+/// # use tess::math::point;
+/// # use tess::{FillOptions, IncrementalFillTessellator};
+/// # fn main() {
+/// let mut builder = Path::builder();
+/// builder.move_to(point(0.0, 0.0));
+/// builder.line_to(point(100.0, 0.0));
+/// builder.line_to(point(100.0, 80.0));
+/// builder.line_to(point(50.0, 100.0));
+/// let cursor = builder.cursor();
+/// builder.line_to(point(0.0, 80.0));
+/// builder.close();
+/// let mut path = builder.build();
+///
+/// let mut tessellator = IncrementalFillTessellator::new(10.0);
+/// tessellator.tessellate_path(&path, &FillOptions::DEFAULT).unwrap();
+///
+/// // Move the point at (50.0, 100.0) and update the two segments it belongs to.
+/// path.mut_points()[3] = point(55.0, 110.0);
+/// let mut end = cursor;
+/// end.next(&path);
+/// end.next(&path);
+/// let dirty = tessellator.update(&path, cursor..end).unwrap();
+///
+/// // Only the bottom bands were tessellated again.
+/// assert!(dirty.vertices.start > 0);
+/// # }
+/// ```
+pub struct IncrementalFillTessellator {
+    tessellator: FillTessellator,
+    options: FillOptions,
+    band_height: f32,
+    path: Path,
+    // The flattened sub-paths of the path.
+    polygons: Vec<Polygon>,
+    // Index of the first band, the band n covers the y range [n * band_height, (n + 1) * band_height].
+    first_band: i32,
+    bands: Vec<Band>,
+    buffers: VertexBuffers<FillVertex, u32>,
+}
+
+/// The parts of the buffers that were modified by an update.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirtyRanges {
+    pub vertices: Range<u32>,
+    pub indices: Range<u32>,
+}
+
+// A flattened sub-path and its y extent.
+#[derive(Clone, Debug)]
+struct Polygon {
+    points: Vec<Point>,
+    min_y: f32,
+    max_y: f32,
+}
+
+// The events of the part of the shape that is in a band, and its geometry in the buffers.
+#[derive(Clone, Debug, Default)]
+struct Band {
+    events: FillEvents,
+    num_vertices: u32,
+    num_indices: u32,
+}
+
+impl IncrementalFillTessellator {
+    /// Constructor.
+    ///
+    /// Smaller bands make updates cheaper at the cost of more vertices.
+    pub fn new(band_height: f32) -> Self {
+        assert!(band_height > 0.0);
+        IncrementalFillTessellator {
+            tessellator: FillTessellator::new(),
+            options: FillOptions::DEFAULT,
+            band_height,
+            path: Path::new(),
+            polygons: Vec::new(),
+            first_band: 0,
+            bands: Vec::new(),
+            buffers: VertexBuffers::new(),
+        }
+    }
+
+    /// The current tessellation.
+    pub fn buffers(&self) -> &VertexBuffers<FillVertex, u32> {
+        &self.buffers
+    }
+
+    /// Tessellates the whole path, replacing the previous tessellation.
+    pub fn tessellate_path(
+        &mut self,
+        path: &Path,
+        options: &FillOptions,
+    ) -> Result<DirtyRanges, TessellationError> {
+        let mut options = *options;
+        options.anti_aliasing = None;
+        options.curve_triangles = false;
+
+        // Start from a clean state if the tessellation fails.
+        self.bands.clear();
+        self.buffers.vertices.clear();
+        self.buffers.indices.clear();
+
+        self.polygons = flatten(path.iter(), options.tolerance);
+        let mut y_range = None;
+        for polygon in &self.polygons {
+            add_y(&mut y_range, polygon.min_y);
+            add_y(&mut y_range, polygon.max_y);
+        }
+        let bands = match y_range {
+            Some((min, max)) => self.band(min)..(self.band(max) + 1),
+            None => 0..0,
+        };
+
+        let mut buffers = VertexBuffers::new();
+        let mut band_geometry = Vec::with_capacity(bands.len());
+        for band in bands.clone() {
+            let events = self.band_events(band, options.tolerance);
+            band_geometry.push(tessellate_band(&mut self.tessellator, events, &options, &mut buffers)?);
+        }
+
+        self.options = options;
+        self.path = path.clone();
+        self.first_band = bands.start;
+        self.bands = band_geometry;
+        self.buffers = buffers;
+
+        Ok(DirtyRanges {
+            vertices: 0..self.buffers.vertices.len() as u32,
+            indices: 0..self.buffers.indices.len() as u32,
+        })
+    }
+
+    /// Updates the tessellation after some of the path's points moved.
+    ///
+    /// `changed` must contain all of the events of the path that were modified. Moving
+    /// the endpoint of a segment also modifies the event of the next segment.
+    /// The whole path is tessellated again if its structure changed, or if the previous
+    /// tessellation failed.
+    pub fn update(
+        &mut self,
+        path: &Path,
+        changed: Range<Cursor>,
+    ) -> Result<DirtyRanges, TessellationError> {
+        if self.bands.is_empty() || !same_structure(&self.path, path) {
+            let options = self.options;
+            return self.tessellate_path(path, &options);
+        }
+
+        let mut y_range = None;
+        events_y_range(&self.path, &changed, &mut y_range);
+        events_y_range(path, &changed, &mut y_range);
+        let (min_y, max_y) = match y_range {
+            Some(range) => range,
+            None => {
+                self.path = path.clone();
+                return Ok(self.no_change());
+            }
+        };
+
+        self.update_polygons(path);
+        self.path = path.clone();
+
+        // Also include the bands that only touch the changed range on their boundary.
+        let first = (min_y / self.band_height).ceil() as i32 - 1;
+        let last = self.band(max_y);
+
+        let result = self.update_bands(first, last);
+        if result.is_err() {
+            self.bands.clear();
+        }
+
+        result
+    }
+
+    // Tessellates the bands between first and last (included) whose events changed, and
+    // replaces their geometry in the buffers.
+    fn update_bands(&mut self, first: i32, last: i32) -> Result<DirtyRanges, TessellationError> {
+        // Add empty bands if the shape grew.
+        if first < self.first_band {
+            let n = (self.first_band - first) as usize;
+            self.bands.splice(0..0, vec![Band::default(); n]);
+            self.first_band = first;
+        }
+        let num_bands = (last - self.first_band + 1) as usize;
+        if num_bands > self.bands.len() {
+            self.bands.resize(num_bands, Band::default());
+        }
+
+        // Only the bands with different events need to be tessellated again.
+        let tolerance = self.options.tolerance;
+        let mut changed_events = Vec::new();
+        for band in first..(last + 1) {
+            let events = self.band_events(band, tolerance);
+            if events != self.bands[(band - self.first_band) as usize].events {
+                changed_events.push((band, events));
+            }
+        }
+        let (first, last) = match (changed_events.first(), changed_events.last()) {
+            (Some(&(first, _)), Some(&(last, _))) => (first, last),
+            _ => { return Ok(self.no_change()); }
+        };
+
+        let options = self.options;
+        let mut new_geometry = VertexBuffers::new();
+        let mut new_bands = Vec::with_capacity((last - first + 1) as usize);
+        let mut changed_events = changed_events.into_iter().peekable();
+        for band in first..(last + 1) {
+            // The bands in between the changed ones are kept as they are but their geometry
+            // is moved, so it is simpler to tessellate them as well.
+            let events = match changed_events.peek() {
+                Some(&(changed, _)) if changed == band => changed_events.next().unwrap().1,
+                _ => self.bands[(band - self.first_band) as usize].events.clone(),
+            };
+            new_bands.push(tessellate_band(&mut self.tessellator, events, &options, &mut new_geometry)?);
+        }
+
+        // Replace the geometry of the bands in the buffers.
+        let first_idx = (first - self.first_band) as usize;
+        let last_idx = (last - self.first_band) as usize;
+        let mut vertex_start = 0;
+        let mut index_start = 0;
+        for band in &self.bands[..first_idx] {
+            vertex_start += band.num_vertices;
+            index_start += band.num_indices;
+        }
+        let mut old_vertices = 0;
+        let mut old_indices = 0;
+        for band in &self.bands[first_idx..(last_idx + 1)] {
+            old_vertices += band.num_vertices;
+            old_indices += band.num_indices;
+        }
+        self.bands.splice(first_idx..(last_idx + 1), new_bands);
+
+        let new_vertices = new_geometry.vertices.len() as u32;
+        let new_indices = new_geometry.indices.len() as u32;
+        let vertex_range = vertex_start as usize..(vertex_start + old_vertices) as usize;
+        let index_range = index_start as usize..(index_start + old_indices) as usize;
+        self.buffers.vertices.splice(vertex_range, new_geometry.vertices);
+        self.buffers.indices.splice(
+            index_range,
+            new_geometry.indices.iter().map(|index| index + vertex_start),
+        );
+
+        let index_end = (index_start + new_indices) as usize;
+        if new_vertices != old_vertices {
+            // Rebase the indices of the following bands.
+            for index in &mut self.buffers.indices[index_end..] {
+                *index = *index + new_vertices - old_vertices;
+            }
+        }
+
+        let vertex_end = if new_vertices == old_vertices {
+            vertex_start + new_vertices
+        } else {
+            self.buffers.vertices.len() as u32
+        };
+        let index_end = if new_vertices == old_vertices && new_indices == old_indices {
+            index_start + new_indices
+        } else {
+            self.buffers.indices.len() as u32
+        };
+
+        Ok(DirtyRanges {
+            vertices: vertex_start..vertex_end,
+            indices: index_start..index_end,
+        })
+    }
+
+    // Flattens the sub-paths of the new path that differ from the current path again.
+    //
+    // Both paths must have the same structure.
+    fn update_polygons(&mut self, path: &Path) {
+        let tolerance = self.options.tolerance;
+        let mut sub_path = Vec::new();
+        let mut polygon_idx = None;
+        let mut dirty = false;
+        let mut events = path.iter().zip(self.path.iter()).peekable();
+        while let Some((evt, old_evt)) = events.next() {
+            if let PathEvent::MoveTo(..) = evt {
+                sub_path.clear();
+                dirty = false;
+                polygon_idx = Some(polygon_idx.map_or(0, |idx| idx + 1));
+            }
+            sub_path.push(evt);
+            dirty |= evt != old_evt;
+
+            let sub_path_end = matches!(events.peek(), Some(&(PathEvent::MoveTo(..), _)) | None);
+            if let (true, true, Some(idx)) = (sub_path_end, dirty, polygon_idx) {
+                if let Some(polygon) = flatten(sub_path.drain(..), tolerance).pop() {
+                    self.polygons[idx] = polygon;
+                }
+            }
+        }
+    }
+
+    // Builds the events of the part of the shape that is in a band.
+    fn band_events(&self, band: i32, tolerance: f32) -> FillEvents {
+        let min_y = band as f32 * self.band_height;
+        let max_y = (band + 1) as f32 * self.band_height;
+
+        let mut events = Vec::new();
+        let mut clipped = Vec::new();
+        for polygon in &self.polygons {
+            // Polygons that only touch the band don't contribute anything.
+            if polygon.max_y <= min_y || polygon.min_y >= max_y {
+                continue;
+            }
+            clip_to_band(&polygon.points, min_y, max_y, &mut clipped);
+            if clipped.len() < 3 || clipped.iter().all(|p| p.y == clipped[0].y) {
+                continue;
+            }
+            events.push(PathEvent::MoveTo(clipped[0]));
+            for i in 1..clipped.len() {
+                events.push(PathEvent::Line(LineSegment { from: clipped[i - 1], to: clipped[i] }));
+            }
+            events.push(PathEvent::Close(LineSegment { from: clipped[clipped.len() - 1], to: clipped[0] }));
+        }
+
+        FillEvents::from_path(tolerance, events.into_iter())
+    }
+
+    fn no_change(&self) -> DirtyRanges {
+        let end = self.buffers.vertices.len() as u32;
+        let index_end = self.buffers.indices.len() as u32;
+        DirtyRanges { vertices: end..end, indices: index_end..index_end }
+    }
+
+    fn band(&self, y: f32) -> i32 {
+        (y / self.band_height).floor() as i32
+    }
+}
+
+// Tessellates the part of the shape that is in a band, with indices relative to
+// the first vertex of the band.
+fn tessellate_band(
+    tessellator: &mut FillTessellator,
+    events: FillEvents,
+    options: &FillOptions,
+    output: &mut VertexBuffers<FillVertex, u32>,
+) -> Result<Band, TessellationError> {
+    let vertex_offset = output.vertices.len() as u32;
+    let index_offset = output.indices.len() as u32;
+    if !events.is_empty() {
+        let mut buffers: VertexBuffers<FillVertex, u32> = VertexBuffers::new();
+        tessellator.tessellate_events(
+            &events,
+            options,
+            &mut BuffersBuilder::new(&mut buffers, Identity),
+        )?;
+        output.vertices.extend_from_slice(&buffers.vertices);
+        output.indices.extend(buffers.indices.iter().map(|index| index + vertex_offset));
+    }
+
+    Ok(Band {
+        events,
+        num_vertices: output.vertices.len() as u32 - vertex_offset,
+        num_indices: output.indices.len() as u32 - index_offset,
+    })
+}
+
+// Whether two paths have the same sequence of events, with different positions.
+fn same_structure(a: &Path, b: &Path) -> bool {
+    let mut a = a.iter();
+    let mut b = b.iter();
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => { return true; }
+            (Some(a), Some(b)) if discriminant(&a) == discriminant(&b) => {}
+            _ => { return false; }
+        }
+    }
+}
+
+fn add_y(range: &mut Option<(f32, f32)>, y: f32) {
+    *range = match *range {
+        Some((min, max)) => Some((min.min(y), max.max(y))),
+        None => Some((y, y)),
+    };
+}
+
+// Extends the range with the y extent of the events between the cursors.
+fn events_y_range(path: &Path, range: &Range<Cursor>, y_range: &mut Option<(f32, f32)>) {
+    if path.points().is_empty() {
+        return;
+    }
+
+    let mut cursor = range.start;
+    while cursor != range.end {
+        match cursor.event(path) {
+            PathEvent::MoveTo(to) => {
+                add_y(y_range, to.y);
+                // The sub-path is implicitly closed with a segment ending at this point.
+                let mut last = cursor;
+                loop {
+                    let mut next = last;
+                    if !next.next(path) {
+                        break;
+                    }
+                    if let PathEvent::MoveTo(..) = next.event(path) {
+                        break;
+                    }
+                    last = next;
+                }
+                add_y(y_range, endpoint(last.event(path)).y);
+            }
+            PathEvent::Line(segment) | PathEvent::Close(segment) => {
+                add_y(y_range, segment.from.y);
+                add_y(y_range, segment.to.y);
+            }
+            PathEvent::Quadratic(segment) => {
+                add_y(y_range, segment.from.y);
+                add_y(y_range, segment.ctrl.y);
+                add_y(y_range, segment.to.y);
+            }
+            PathEvent::Cubic(segment) => {
+                add_y(y_range, segment.from.y);
+                add_y(y_range, segment.ctrl1.y);
+                add_y(y_range, segment.ctrl2.y);
+                add_y(y_range, segment.to.y);
+            }
+        }
+
+        if !cursor.next(path) {
+            break;
+        }
+    }
+}
+
+fn endpoint(evt: PathEvent) -> Point {
+    match evt {
+        PathEvent::MoveTo(to) => to,
+        PathEvent::Line(segment) | PathEvent::Close(segment) => segment.to,
+        PathEvent::Quadratic(segment) => segment.to,
+        PathEvent::Cubic(segment) => segment.to,
+    }
+}
+
+fn flatten<Iter: Iterator<Item = PathEvent>>(events: Iter, tolerance: f32) -> Vec<Polygon> {
+    let mut polygons: Vec<Polygon> = Vec::new();
+    for evt in events.flattened(tolerance) {
+        let to = match evt {
+            FlattenedEvent::MoveTo(to) => {
+                polygons.push(Polygon { points: vec![to], min_y: to.y, max_y: to.y });
+                continue;
+            }
+            FlattenedEvent::Line(segment) => segment.to,
+            FlattenedEvent::Close(..) => { continue; }
+        };
+        if let Some(polygon) = polygons.last_mut() {
+            polygon.points.push(to);
+            polygon.min_y = polygon.min_y.min(to.y);
+            polygon.max_y = polygon.max_y.max(to.y);
+        }
+    }
+
+    polygons
+}
+
+// Clips a polygon to the horizontal band between min_y and max_y.
+//
+// The parts of the polygon that are outside of the band are projected on its boundary,
+// which preserves the winding number of the points in the band. The intersections are
+// always computed from the original edges so that neighbor bands agree on the positions
+// of the vertices on their common boundary.
+fn clip_to_band(polygon: &[Point], min_y: f32, max_y: f32, output: &mut Vec<Point>) {
+    output.clear();
+    let mut push = |p: Point| {
+        if output.last() != Some(&p) {
+            output.push(p);
+        }
+    };
+
+    let clamp = |p: Point| point(p.x, p.y.max(min_y).min(max_y));
+    let crossing = |from: Point, to: Point, y: f32| {
+        point(from.x + (y - from.y) * (to.x - from.x) / (to.y - from.y), y)
+    };
+
+    for i in 0..polygon.len() {
+        let from = polygon[i];
+        let to = polygon[(i + 1) % polygon.len()];
+
+        // Points outside of the band are projected on the boundary.
+        push(clamp(from));
+        let (a, b) = if from.y < to.y { (min_y, max_y) } else { (max_y, min_y) };
+        for &y in &[a, b] {
+            if from.y < y && to.y > y || from.y > y && to.y < y {
+                push(crossing(from, to, y));
+            }
+        }
+    }
+
+    if output.len() > 1 && output.first() == output.last() {
+        output.pop();
+    }
+}
+
+#[cfg(test)]
+use crate::geometry_builder::triangles_area;
+
+#[test]
+fn incremental_matches_full_tessellation() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(50.0, 5.0));
+    builder.quadratic_bezier_to(point(70.0, 40.0), point(45.0, 60.0));
+    let start = builder.cursor();
+    builder.line_to(point(10.0, 45.0));
+    builder.close();
+    builder.move_to(point(20.0, 20.0));
+    builder.line_to(point(30.0, 20.0));
+    builder.line_to(point(25.0, 50.0));
+    let bottom = builder.cursor();
+    builder.close();
+    let mut path = builder.build();
+
+    let mut end = start;
+    end.next(&path);
+    end.next(&path);
+
+    let options = FillOptions::tolerance(0.05);
+    let mut incremental = IncrementalFillTessellator::new(7.0);
+    incremental.tessellate_path(&path, &options).unwrap();
+
+    let mut reference: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        path.iter(),
+        &options,
+        &mut crate::geometry_builder::simple_builder(&mut reference),
+    ).unwrap();
+    let reference_area = triangles_area(&reference, |v| v.position);
+    assert!((triangles_area(incremental.buffers(), |v| v.position) - reference_area).abs() < 0.01);
+
+    for &(ctrl, to) in &[
+        (point(80.0, 40.0), point(45.0, 60.0)),
+        (point(80.0, 40.0), point(45.0, 80.0)),
+        (point(20.0, 10.0), point(45.0, 30.0)),
+    ] {
+        // The endpoint of the curve is the start of the next segment.
+        path.mut_points()[2] = ctrl;
+        path.mut_points()[3] = to;
+        let dirty = incremental.update(&path, start..end).unwrap();
+
+        let mut full = IncrementalFillTessellator::new(7.0);
+        full.tessellate_path(&path, &options).unwrap();
+        assert_eq!(incremental.buffers().vertices, full.buffers().vertices);
+        assert_eq!(incremental.buffers().indices, full.buffers().indices);
+        assert!(dirty.vertices.end as usize <= incremental.buffers().vertices.len());
+        assert!(dirty.indices.end as usize <= incremental.buffers().indices.len());
+    }
+
+    // Moving a point at the bottom of the shape doesn't affect the top bands.
+    let start = bottom;
+    let mut end = start;
+    end.next(&path);
+    end.next(&path);
+    match start.event(&path) {
+        PathEvent::Line(segment) => { assert_eq!(segment.to, point(25.0, 50.0)); }
+        evt => { panic!("{:?}", evt); }
+    }
+    path.mut_points()[7] = point(26.0, 52.0);
+    let dirty = incremental.update(&path, start..end).unwrap();
+    assert!(dirty.vertices.start > 0);
+    assert!(dirty.indices.start > 0);
+
+    let mut full = IncrementalFillTessellator::new(7.0);
+    full.tessellate_path(&path, &options).unwrap();
+    assert_eq!(incremental.buffers().vertices, full.buffers().vertices);
+    assert_eq!(incremental.buffers().indices, full.buffers().indices);
+}
+
+
+#[test]
+fn incremental_structure_change() {
+    let options = FillOptions::tolerance(0.05);
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(50.0, 5.0));
+    let start = builder.cursor();
+    builder.quadratic_bezier_to(point(70.0, 40.0), point(45.0, 60.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut incremental = IncrementalFillTessellator::new(7.0);
+    incremental.tessellate_path(&path, &options).unwrap();
+
+    // Same number of points, but the curve is replaced with two line segments.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(50.0, 5.0));
+    builder.line_to(point(70.0, 40.0));
+    builder.line_to(point(45.0, 60.0));
+    builder.close();
+    let path = builder.build();
+    assert_eq!(path.points().len(), incremental.path.points().len());
+
+    let mut end = start;
+    end.next(&path);
+    let dirty = incremental.update(&path, start..end).unwrap();
+    assert_eq!(dirty.vertices.start, 0);
+
+    let mut full = IncrementalFillTessellator::new(7.0);
+    full.tessellate_path(&path, &options).unwrap();
+    assert_eq!(incremental.buffers().vertices, full.buffers().vertices);
+    assert_eq!(incremental.buffers().indices, full.buffers().indices);
+}
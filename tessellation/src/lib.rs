@@ -190,8 +190,16 @@ pub extern crate serde;
 pub mod basic_shapes;
 pub mod geometry_builder;
 pub mod debugger;
+#[cfg(feature = "rayon")]
+pub mod batch;
 mod path_fill;
 mod path_stroke;
+mod stroke_outline;
+mod fringe;
+mod clip;
+mod quality;
+mod strip;
+mod incremental;
 mod math_utils;
 mod fixed;
 
@@ -204,6 +212,8 @@ mod fuzz_tests;
 
 pub use crate::path::math;
 
+use std::fmt;
+
 pub use crate::path::geom;
 
 #[doc(inline)]
@@ -212,10 +222,16 @@ pub use crate::path_fill::*;
 #[doc(inline)]
 pub use crate::path_stroke::*;
 
+#[doc(inline)]
+pub use crate::stroke_outline::*;
+
+#[doc(inline)]
+pub use crate::incremental::*;
+
 #[doc(inline)]
 pub use crate::geometry_builder::{GeometryBuilder, GeometryReceiver, VertexBuffers, BuffersBuilder, VertexConstructor, Count};
 
-pub use crate::path::FillRule;
+pub use crate::path::{FillRule, LineCap, LineJoin};
 
 /// The fill tessellator's result type.
 pub type TessellationResult = Result<Count, TessellationError>;
@@ -229,11 +245,60 @@ pub enum TessellationError {
     Internal(InternalError)
 }
 
+impl fmt::Display for TessellationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TessellationError::UnsupportedParamater => write!(f, "unsupported tessellation parameter"),
+            TessellationError::InvalidVertex => write!(f, "the geometry builder rejected a vertex"),
+            TessellationError::TooManyVertices => write!(f, "too many vertices for the output's index type"),
+            TessellationError::Internal(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for TessellationError {}
+
 /// Something unexpectedly put the tessellator in a bad state.
 ///
-/// If you run into this error code, please [file an issue](https://github.com/nical/lyon/issues).
+/// The error records where the sweep line was and, when it is known, the edge that
+/// the tessellator was processing, which is usually close to the part of the path that
+/// caused the problem. `lyon_extra::debugging::find_reduced_test_case` can then be used to
+/// find a smaller path that reproduces the error.
+///
+/// If you run into this error, please [file an issue](https://github.com/nical/lyon/issues).
 #[derive(Clone, Debug, PartialEq)]
-pub enum InternalError {
+pub struct InternalError {
+    /// Identifies the check that failed.
+    pub code: InternalErrorCode,
+    /// A short description of the error.
+    pub description: &'static str,
+    /// The position of the sweep line when the error happened.
+    pub position: math::Point,
+    /// The edge that was being processed, if any.
+    pub edge: Option<geom::LineSegment<f32>>,
+}
+
+impl fmt::Display for InternalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "internal error {:?}: {} at ({}, {})",
+            self.code, self.description, self.position.x, self.position.y,
+        )?;
+        if let Some(edge) = self.edge {
+            write!(
+                f, " (edge from ({}, {}) to ({}, {}))",
+                edge.from.x, edge.from.y, edge.to.x, edge.to.y,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InternalError {}
+
+/// The check that failed in an `InternalError`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InternalErrorCode {
     E01,
     E02,
     E03,
@@ -298,6 +363,143 @@ pub struct StrokeVertex {
     pub side: Side,
 }
 
+/// Vertex produced by the hairline mode of the stroke tessellator.
+///
+/// The vertices are on the path, and are meant to be extruded in the vertex shader, after
+/// the path has been transformed, so that the stroke has a constant width on the screen
+/// regardless of the transform (see `HairlineVertex::extrude`).
+///
+/// See `StrokeTessellator::tessellate_path_hairline`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct HairlineVertex {
+    /// Position of the vertex on the path.
+    pub position: math::Point,
+    /// Normalized direction of the path before this vertex.
+    pub previous_tangent: math::Vector,
+    /// Normalized direction of the path after this vertex.
+    ///
+    /// The vertices of the segments and the caps have the direction of their segment in
+    /// both tangents.
+    pub next_tangent: math::Vector,
+    /// How far along the path this vertex is.
+    pub advancement: f32,
+    /// Whether the vertex is on the left or right side of the path.
+    ///
+    /// Only meaningful with `HairlineExtrusion::Side`.
+    pub side: Side,
+    /// How far the vertex must be moved along the tangent, in half line widths.
+    ///
+    /// Equal to -1.0 on start caps, 1.0 on end caps and 0.0 elsewhere. Square caps use it
+    /// to move the vertices of the ends of the sub-path, round caps to orient their fan.
+    pub cap: f32,
+    /// How the vertex is extruded.
+    pub extrusion: HairlineExtrusion,
+}
+
+/// How a `HairlineVertex` is extruded, in half line widths.
+///
+/// In the descriptions below, the normals are the tangents rotated by 90 degrees, the
+/// left side being in the direction of the normal. The joins are on the outer side of the
+/// turn, which is computed after the transform since reflections change it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum HairlineExtrusion {
+    /// Along the normal of `next_tangent` on the vertex's side, and along the tangent by
+    /// `cap`. Used by the segments and the square caps.
+    Side,
+    /// Not extruded. Used by the center of the joins and the round caps.
+    Center,
+    /// On the outer side of the join, at the provided fraction of the angle between the
+    /// normal of `previous_tangent` (at 0.0) and the normal of `next_tangent` (at 1.0).
+    /// Used by all joins, and to form the arc of round joins.
+    Join(f32),
+    /// At the tip of a miter join, or in the middle of the bevel if the miter limit is
+    /// exceeded.
+    Miter,
+    /// At the tip of a miter join, clipped at the miter limit on the side of the previous
+    /// segment (at 0.0) or the next segment (at 1.0).
+    MiterClip(f32),
+    /// On a round cap, at the provided fraction of the half turn from the left side (at 0.0)
+    /// to the right side (at 1.0), going around the end of the sub-path as given by `cap`.
+    RoundCap(f32),
+}
+
+impl HairlineVertex {
+    /// Computes the position of the vertex once extruded, in the destination space of
+    /// `transform`, for a stroke of `line_width` in that space.
+    ///
+    /// The length of the miters is limited to `miter_limit` half line widths, from the
+    /// center of the join. This is meant to be done in a vertex shader, this function
+    /// provides a reference implementation.
+    pub fn extrude(&self, transform: &math::Transform2D, line_width: f32, miter_limit: f32) -> math::Point {
+        let tangent = |v: math::Vector| {
+            let v = transform.transform_vector(v);
+            let length = v.length();
+            if length > 0.0 { v / length } else { v }
+        };
+        let previous_tangent = tangent(self.previous_tangent);
+        let next_tangent = tangent(self.next_tangent);
+        let n0 = math::vector(-previous_tangent.y, previous_tangent.x);
+        let n1 = math::vector(-next_tangent.y, next_tangent.x);
+
+        // The path turns towards the left side when the cross product is positive.
+        let outer = if previous_tangent.cross(next_tangent) > 0.0 { -1.0 } else { 1.0 };
+        let miter = || {
+            let d = 1.0 + n0.dot(n1);
+            if d > 1e-4 { Some((n0 + n1) * outer / d) } else { None }
+        };
+
+        let offset = match self.extrusion {
+            HairlineExtrusion::Side => {
+                let side = match self.side {
+                    Side::Left => 1.0,
+                    Side::Right => -1.0,
+                };
+                n1 * side + next_tangent * self.cap
+            }
+            HairlineExtrusion::Center => math::vector(0.0, 0.0),
+            HairlineExtrusion::Join(t) => {
+                let angle = n0.cross(n1).atan2(n0.dot(n1)) * t;
+                let (sin, cos) = angle.sin_cos();
+                math::vector(n0.x * cos - n0.y * sin, n0.x * sin + n0.y * cos) * outer
+            }
+            HairlineExtrusion::Miter => match miter() {
+                Some(miter) if miter.length() <= miter_limit => miter,
+                _ => (n0 + n1) * outer * 0.5,
+            },
+            HairlineExtrusion::MiterClip(t) => match miter() {
+                Some(miter) if miter.length() <= miter_limit => miter,
+                _ => {
+                    // Intersect the outer edge of the segment with the line that clips the
+                    // miter, which is perpendicular to its direction.
+                    let direction = match miter() {
+                        Some(miter) => miter.normalize(),
+                        None => previous_tangent,
+                    };
+                    let (normal, edge) = if t < 0.5 {
+                        (n0 * outer, previous_tangent)
+                    } else {
+                        (n1 * outer, -next_tangent)
+                    };
+                    let d = edge.dot(direction);
+                    if d.abs() > 1e-4 {
+                        normal + edge * ((miter_limit - normal.dot(direction)) / d)
+                    } else {
+                        normal
+                    }
+                }
+            },
+            HairlineExtrusion::RoundCap(t) => {
+                let (sin, cos) = (t * std::f32::consts::PI).sin_cos();
+                n1 * cos + next_tangent * self.cap * sin
+            }
+        };
+
+        transform.transform_point(self.position) + offset * line_width * 0.5
+    }
+}
+
 /// Vertex produced by the fill tessellators.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -312,67 +514,306 @@ pub struct FillVertex {
     pub normal: math::Vector,
 }
 
-/// Line cap as defined by the SVG specification.
+/// Coordinates of a vertex in a curve triangle, in the style of Loop-Blinn.
+///
+/// A point of a triangle is part of the shape if `sign * (u * u - v) <= 0.0`, where
+/// `u`, `v` and `sign` are interpolated from the triangle's vertices.
+///
+/// The vertices of a curve triangle are the endpoints and the control point of a quadratic
+/// bézier curve, with `(u, v)` respectively equal to `(0.0, 0.0)`, `(1.0, 1.0)` and
+/// `(0.5, 0.0)`, which puts the curve at `u * u - v == 0.0`. The sign selects the side of
+/// the curve that is inside the shape.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct CurveCoordinates {
+    pub u: f32,
+    pub v: f32,
+    pub sign: f32,
+}
+
+impl CurveCoordinates {
+    /// Coordinates of the vertices that are not part of a curve triangle, for which
+    /// the whole triangle is inside of the shape.
+    pub const INTERIOR: Self = CurveCoordinates { u: 0.0, v: 1.0, sign: 1.0 };
+}
+
+/// The location in the input path that a vertex was generated from.
 ///
-/// See: https://svgwg.org/specs/strokes/#StrokeLinecapProperty
+/// See `FillTessellator::tessellate_path_with_sources` and
+/// `StrokeTessellator::tessellate_path_with_sources`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct VertexSource {
+    /// Index of the sub-path in `Path::sub_paths`.
+    pub sub_path: u32,
+    /// The user-defined id of the sub-path, if any.
+    pub sub_path_id: Option<u32>,
+    /// Index of the edge in the sub-path, starting at zero with the edge that follows
+    /// the `MoveTo` event. The closing edge of a closed sub-path is counted as well.
+    pub segment: u32,
+}
+
+/// Data provided along with a vertex to `GeometryBuilder::add_vertex_with_attributes`.
+///
+/// The tessellators only provide it when tessellating a path that has custom attributes,
+/// when the source of each vertex is requested, on the outer edge of the anti-aliasing
+/// fringe and for the curve triangles.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VertexAttributes<'l> {
+    /// The custom attributes of the path, interpolated at the vertex.
+    ///
+    /// Empty if the path doesn't have custom attributes.
+    pub custom: &'l [f32],
+    /// The location in the input path that the vertex was generated from.
+    ///
+    /// Only provided by the `tessellate_path_with_sources` methods.
+    pub source: Option<VertexSource>,
+    /// Coverage of the shape at this vertex.
+    ///
+    /// Equal to 1.0 except on the outer edge of the anti-aliasing fringe
+    /// (see `FillOptions::anti_aliasing` and `StrokeOptions::anti_aliasing`) where it is 0.0.
+    pub coverage: f32,
+    /// Coordinates used to render the curves on the GPU.
+    ///
+    /// Equal to `CurveCoordinates::INTERIOR` except for the curve triangles
+    /// (see `FillOptions::curve_triangles`).
+    pub curve: CurveCoordinates,
+}
+
+impl<'l> VertexAttributes<'l> {
+    /// No custom attributes, no source, full coverage and interior curve coordinates.
+    pub const NONE: VertexAttributes<'static> = VertexAttributes {
+        custom: &[],
+        source: None,
+        coverage: 1.0,
+        curve: CurveCoordinates::INTERIOR,
+    };
+
+    /// Creates attributes without source.
+    pub fn custom(custom: &'l [f32]) -> Self {
+        VertexAttributes { custom, ..VertexAttributes::NONE }
+    }
+
+    /// Whether these attributes are equal to `VertexAttributes::NONE`.
+    pub fn is_empty(&self) -> bool {
+        self.custom.is_empty()
+            && self.source.is_none()
+            && self.coverage == 1.0
+            && self.curve == CurveCoordinates::INTERIOR
+    }
+}
+
+/// A convex polygon that the output of the tessellators can be clipped to.
 ///
-/// <svg viewBox="0 0 400 399.99998" height="400" width="400">
-///   <g transform="translate(0,-652.36229)">
-///     <path style="opacity:1;fill:#80b3ff;stroke:#000000;stroke-width:1;stroke-linejoin:round;" d="m 240,983 a 30,30 0 0 1 -25,-15 30,30 0 0 1 0,-30.00001 30,30 0 0 1 25.98076,-15 l 0,30 z"/>
-///     <path style="fill:#80b3ff;stroke:#000000;stroke-width:1px;stroke-linecap:butt;" d="m 390,782.6 -150,0 0,-60 150,0.5"/>
-///     <circle style="opacity:1;fill:#ff7f2a;stroke:#000000;stroke-width:1;stroke-linejoin:round;" r="10" cy="752.89227" cx="240.86813"/>
-///     <path style="fill:none;stroke:#000000;stroke-width:1px;stroke-linejoin:round;" d="m 240,722.6 150,60"/>
-///     <path style="fill:#80b3ff;stroke:#000000;stroke-width:1px;stroke-linecap:butt;" d="m 390,882 -180,0 0,-60 180,0.4"/>
-///     <circle style="opacity:1;fill:#ff7f2a;stroke:#000000;stroke-width:1;stroke-linejoin:round;" cx="239.86813" cy="852.20868" r="10" />
-///     <path style="fill:none;stroke:#000000;stroke-width:1px;stroke-linejoin:round;" d="m 210.1,822.3 180,60"/>
-///     <path style="fill:#80b3ff;stroke:#000000;stroke-width:1px;stroke-linecap:butt;" d="m 390,983 -150,0 0,-60 150,0.4"/>
-///     <circle style="opacity:1;fill:#ff7f2a;stroke:#000000;stroke-width:1;stroke-linejoin:round;" cx="239.86813" cy="953.39734" r="10" />
-///     <path style="fill:none;stroke:#000000;stroke-width:1px;stroke-linejoin:round;" d="m 390,983 -150,-60 L 210,953 l 30,30 -21.5,-9.5 L 210,953 218.3,932.5 240,923.4"/>
-///     <text y="757.61273" x="183.65314" style="font-style:normal;font-weight:normal;font-size:20px;line-height:125%;font-family:Sans;text-align:end;text-anchor:end;fill:#000000;stroke:none;">
-///        <tspan y="757.61273" x="183.65314">LineCap::Butt</tspan>
-///        <tspan y="857.61273" x="183.65314">LineCap::Square</tspan>
-///        <tspan y="957.61273" x="183.65314">LineCap::Round</tspan>
-///      </text>
-///   </g>
-/// </svg>
+/// See `FillOptions::clip` and `StrokeOptions::clip`.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
-pub enum LineCap {
-    /// The stroke for each sub-path does not extend beyond its two endpoints.
-    /// A zero length sub-path will therefore not have any stroke.
-    Butt,
-    /// At the end of each sub-path, the shape representing the stroke will be
-    /// extended by a rectangle with the same width as the stroke width and
-    /// whose length is half of the stroke width. If a sub-path has zero length,
-    /// then the resulting effect is that the stroke for that sub-path consists
-    /// solely of a square with side length equal to the stroke width, centered
-    /// at the sub-path's point.
-    Square,
-    /// At each end of each sub-path, the shape representing the stroke will be extended
-    /// by a half circle with a radius equal to the stroke width.
-    /// If a sub-path has zero length, then the resulting effect is that the stroke for
-    /// that sub-path consists solely of a full circle centered at the sub-path's point.
-    Round,
-}
-
-/// Line join as defined by the SVG specification.
+pub struct ClipPolygon {
+    points: [math::Point; ClipPolygon::MAX_POINTS],
+    num_points: u8,
+}
+
+impl ClipPolygon {
+    /// Maximum number of vertices of a clip polygon.
+    pub const MAX_POINTS: usize = 16;
+
+    /// Creates a clip polygon from the vertices of a convex polygon, in either winding order.
+    ///
+    /// Returns `TessellationError::UnsupportedParamater` if the polygon has less than 3 or
+    /// more than `MAX_POINTS` vertices, if a coordinate isn't finite, or if the polygon
+    /// is empty or not convex.
+    pub fn new(points: &[math::Point]) -> Result<Self, TessellationError> {
+        if points.len() < 3 || points.len() > Self::MAX_POINTS {
+            return Err(TessellationError::UnsupportedParamater);
+        }
+        if points.iter().any(|p| !p.x.is_finite() || !p.y.is_finite()) {
+            return Err(TessellationError::UnsupportedParamater);
+        }
+
+        let mut area = 0.0;
+        for i in 0..points.len() {
+            area += points[i].to_vector().cross(points[(i + 1) % points.len()].to_vector());
+        }
+        if area == 0.0 || !area.is_finite() {
+            return Err(TessellationError::UnsupportedParamater);
+        }
+
+        // All of the turns go in the same direction as the polygon, and they add up to a
+        // single revolution.
+        let mut angle = 0.0;
+        for i in 0..points.len() {
+            let a = points[(i + 1) % points.len()] - points[i];
+            let b = points[(i + 2) % points.len()] - points[(i + 1) % points.len()];
+            let cross = a.cross(b);
+            if cross * area < 0.0 {
+                return Err(TessellationError::UnsupportedParamater);
+            }
+            angle += cross.atan2(a.dot(b)).abs();
+        }
+        if (angle - 2.0 * std::f32::consts::PI).abs() > 0.01 {
+            return Err(TessellationError::UnsupportedParamater);
+        }
+
+        let mut clip = ClipPolygon {
+            points: [math::point(0.0, 0.0); Self::MAX_POINTS],
+            num_points: points.len() as u8,
+        };
+        clip.points[..points.len()].copy_from_slice(points);
+        // Store the vertices in the winding order where the inside is on the left of the edges.
+        if area < 0.0 {
+            clip.points[..points.len()].reverse();
+        }
+
+        Ok(clip)
+    }
+
+    /// Creates a clip polygon from a rectangle.
+    ///
+    /// Returns `TessellationError::UnsupportedParamater` if the rectangle is empty or
+    /// if a coordinate isn't finite.
+    pub fn from_rect(rect: &math::Rect) -> Result<Self, TessellationError> {
+        ClipPolygon::new(&[
+            math::point(rect.min_x(), rect.min_y()),
+            math::point(rect.max_x(), rect.min_y()),
+            math::point(rect.max_x(), rect.max_y()),
+            math::point(rect.min_x(), rect.max_y()),
+        ])
+    }
+
+    // The same polygon with the points transformed.
+    pub(crate) fn transformed(&self, transform: &math::Transform2D) -> Self {
+        let mut clip = *self;
+        for point in &mut clip.points[..self.num_points as usize] {
+            *point = transform.transform_point(*point);
+        }
+
+        clip
+    }
+
+    /// The vertices of the polygon.
+    #[inline]
+    pub fn points(&self) -> &[math::Point] {
+        &self.points[..self.num_points as usize]
+    }
+}
+
+/// Parameters of the mesh quality mode of the fill tessellator.
 ///
-/// See: https://svgwg.org/specs/strokes/#StrokeLinejoinProperty
+/// See `FillOptions::mesh_quality`.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
-pub enum LineJoin {
-    /// A sharp corner is to be used to join path segments.
-    Miter,
-    /// Same as a miter join, but if the miter limit is exceeded,
-    /// the miter is clipped at a miter length equal to the miter limit value
-    /// multiplied by the stroke width.
-    MiterClip,
-    /// A round corner is to be used to join path segments.
-    Round,
-    /// A bevelled corner is to be used to join path segments.
-    /// The bevel shape is a triangle that fills the area between the two stroked
-    /// segments.
-    Bevel,
+pub struct MeshQuality {
+    /// Maximum area of the triangles.
+    ///
+    /// Larger triangles are split by inserting a vertex in the middle of their longest edge.
+    ///
+    /// Default value: `None`.
+    pub max_area: Option<f32>,
+
+    /// Minimum angle of the triangles, in radians.
+    ///
+    /// Thinner triangles are split by inserting a vertex in the middle of their longest edge.
+    /// This is a best effort: triangles can't always be improved this way, in particular
+    /// near the sharp corners of the shape.
+    ///
+    /// Default value: `None`.
+    pub min_angle: Option<f32>,
+
+    /// Maximum length of the edges of the triangles.
+    ///
+    /// Longer edges are split in their middle, which adds vertices in the interior of
+    /// large shapes, for example to deform them in a vertex shader. Unlike `max_area` and
+    /// `min_angle`, this is not affected by `min_edge_length`.
+    ///
+    /// Edges shared by more than two triangles, which happens where the shape touches
+    /// itself, are never split, so they can remain longer than this. The limit also isn't
+    /// met when the refinement stops because of `max_vertices`.
+    ///
+    /// Default value: `None`.
+    pub max_edge_length: Option<f32>,
+
+    /// Edges shorter than twice this length are not split to improve the area or the
+    /// angles of the triangles.
+    ///
+    /// Default value: `MeshQuality::DEFAULT_MIN_EDGE_LENGTH`.
+    pub min_edge_length: f32,
+
+    /// Maximum number of vertices of the mesh.
+    ///
+    /// The refinement stops adding vertices when the mesh reaches this number, which
+    /// bounds the cost of small limits on large shapes.
+    ///
+    /// Default value: `MeshQuality::DEFAULT_MAX_VERTICES`.
+    pub max_vertices: u32,
+
+    // To be able to add fields without making it a breaking change, add an empty private field
+    // which makes it impossible to create a MeshQuality without the calling constructor.
+    _private: (),
+}
+
+impl MeshQuality {
+    /// Default minimum edge length.
+    pub const DEFAULT_MIN_EDGE_LENGTH: f32 = 0.1;
+
+    /// Default maximum number of vertices.
+    pub const DEFAULT_MAX_VERTICES: u32 = 1 << 16;
+
+    /// Constrained Delaunay triangulation, without refinement.
+    pub const DELAUNAY: Self = MeshQuality {
+        max_area: None,
+        min_angle: None,
+        max_edge_length: None,
+        min_edge_length: Self::DEFAULT_MIN_EDGE_LENGTH,
+        max_vertices: Self::DEFAULT_MAX_VERTICES,
+        _private: (),
+    };
+
+    #[inline]
+    pub fn with_max_area(mut self, max_area: f32) -> Self {
+        self.max_area = Some(max_area);
+        self
+    }
+
+    #[inline]
+    pub fn with_min_angle(mut self, min_angle: f32) -> Self {
+        self.min_angle = Some(min_angle);
+        self
+    }
+
+    #[inline]
+    pub fn with_max_edge_length(mut self, max_edge_length: f32) -> Self {
+        self.max_edge_length = Some(max_edge_length);
+        self
+    }
+
+    #[inline]
+    pub fn with_min_edge_length(mut self, min_edge_length: f32) -> Self {
+        self.min_edge_length = min_edge_length;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_vertices(mut self, max_vertices: u32) -> Self {
+        self.max_vertices = max_vertices;
+        self
+    }
+
+    /// Whether the parameters can be used.
+    ///
+    /// The limits must be positive and finite, and the minimum angle must be at most 60
+    /// degrees, which no triangle can exceed. The tessellator returns
+    /// `TessellationError::UnsupportedParamater` otherwise.
+    pub fn is_valid(&self) -> bool {
+        let positive = |value: f32| value > 0.0 && value.is_finite();
+        self.max_area.map_or(true, positive)
+            && self.min_angle.map_or(true, |angle| positive(angle) && angle <= std::f32::consts::FRAC_PI_3)
+            && self.max_edge_length.map_or(true, positive)
+            && self.min_edge_length >= 0.0 && self.min_edge_length.is_finite()
+    }
+}
+
+impl Default for MeshQuality {
+    fn default() -> Self { Self::DELAUNAY }
 }
 
 /// Parameters for the tessellator.
@@ -420,6 +861,43 @@ pub struct StrokeOptions {
     /// Default value: `true`.
     pub apply_line_width: bool,
 
+    /// Index of the custom path attribute that drives the line width.
+    ///
+    /// When set, the line width at each endpoint is `line_width` multiplied by this
+    /// attribute, and is interpolated along the edges. If `apply_line_width` is false,
+    /// the vertex normals are scaled accordingly instead.
+    /// Only used by `StrokeTessellator::tessellate_path_with_attributes`, which returns
+    /// `TessellationError::UnsupportedParamater` if the path doesn't have this attribute.
+    /// Default value: `None`.
+    pub variable_line_width: Option<usize>,
+
+    /// Distance into the dash pattern at which the dashes of each sub-path start.
+    ///
+    /// Only used when a dash pattern is set with `with_dashes`.
+    /// Default value: `0.0`.
+    pub dash_offset: f32,
+
+    /// Width of the anti-aliasing fringe.
+    ///
+    /// When set, a band of triangles of this width is added along the outline of the
+    /// stroke, with a `coverage` going from 1.0 on the stroke to 0.0 on its outer edge
+    /// (see `VertexAttributes::coverage`).
+    /// If `apply_line_width` is false, the fringe vertices are extruded through their
+    /// normal instead of their position.
+    /// Default value: `None`.
+    pub anti_aliasing: Option<f32>,
+
+    /// A convex polygon to clip the stroke to.
+    ///
+    /// When set, only the parts of the triangles that are inside of the polygon are
+    /// produced, new vertices being interpolated on the clip edges.
+    /// Default value: `None`.
+    pub clip: Option<ClipPolygon>,
+
+    // The dash pattern, see `with_dashes`.
+    dash_array: [f32; StrokeOptions::MAX_DASHES],
+    num_dashes: u8,
+
     // To be able to add fields without making it a breaking change, add an empty private field
     // which makes it impossible to create a StrokeOptions without calling the constructor.
     _private: (),
@@ -438,6 +916,8 @@ impl StrokeOptions {
     pub const DEFAULT_LINE_JOIN: LineJoin = LineJoin::Miter;
     pub const DEFAULT_LINE_WIDTH: f32 = 1.0;
     pub const DEFAULT_TOLERANCE: f32 = 0.1;
+    /// Maximum number of values in a dash pattern.
+    pub const MAX_DASHES: usize = 16;
 
     pub const DEFAULT: Self = StrokeOptions {
         start_cap: Self::DEFAULT_LINE_CAP,
@@ -447,6 +927,12 @@ impl StrokeOptions {
         miter_limit: Self::DEFAULT_MITER_LIMIT,
         tolerance: Self::DEFAULT_TOLERANCE,
         apply_line_width: true,
+        variable_line_width: None,
+        dash_offset: 0.0,
+        anti_aliasing: None,
+        clip: None,
+        dash_array: [0.0; Self::MAX_DASHES],
+        num_dashes: 0,
         _private: (),
     };
 
@@ -504,6 +990,55 @@ impl StrokeOptions {
         self.apply_line_width = false;
         self
     }
+
+    /// Use a custom path attribute to vary the line width along the path.
+    ///
+    /// See `variable_line_width`.
+    #[inline]
+    pub fn with_variable_line_width(mut self, attribute_index: usize) -> Self {
+        self.variable_line_width = Some(attribute_index);
+        self
+    }
+
+    #[inline]
+    pub fn with_anti_aliasing(mut self, fringe_width: f32) -> Self {
+        self.anti_aliasing = Some(fringe_width);
+        self
+    }
+
+    #[inline]
+    pub fn with_clip(mut self, clip: ClipPolygon) -> Self {
+        self.clip = Some(clip);
+        self
+    }
+
+    /// Stroke the path with dashes.
+    ///
+    /// The dash pattern is a repeated sequence of alternating dash and gap lengths,
+    /// starting with a dash. As in the SVG specification, a pattern with an odd number
+    /// of values is repeated twice, and the pattern restarts at the beginning of each
+    /// sub-path, `offset` units into the pattern. Each dash gets the start and end caps.
+    ///
+    /// An empty pattern disables dashing. Returns `TessellationError::UnsupportedParamater`
+    /// if the pattern contains more than `MAX_DASHES` values, if one of them is negative or
+    /// not finite, or if the offset is not finite.
+    pub fn with_dashes(mut self, dashes: &[f32], offset: f32) -> Result<Self, TessellationError> {
+        if dashes.len() > Self::MAX_DASHES
+            || !dashes.iter().all(|&dash| dash.is_finite() && dash >= 0.0)
+            || !offset.is_finite() {
+            return Err(TessellationError::UnsupportedParamater);
+        }
+        self.dash_array[..dashes.len()].copy_from_slice(dashes);
+        self.num_dashes = dashes.len() as u8;
+        self.dash_offset = offset;
+        Ok(self)
+    }
+
+    /// The dash pattern set with `with_dashes`, or an empty slice if the path is not dashed.
+    #[inline]
+    pub fn dashes(&self) -> &[f32] {
+        &self.dash_array[..self.num_dashes as usize]
+    }
 }
 
 /// Parameters for the fill tessellator.
@@ -520,7 +1055,6 @@ pub struct FillOptions {
     /// Set the fill rule.
     ///
     /// See the [SVG specification](https://www.w3.org/TR/SVG/painting.html#FillRuleProperty).
-    /// Currently, only the `EvenOdd` rule is implemented.
     ///
     /// Default value: `EvenOdd`.
     pub fill_rule: FillRule,
@@ -547,6 +1081,76 @@ pub struct FillOptions {
     /// What to do if the tessellator detects an error.
     pub on_error: OnError,
 
+    /// Width of the anti-aliasing fringe.
+    ///
+    /// When set, a band of triangles of this width is added along the outline of the
+    /// shape, with a `coverage` going from 1.0 on the shape to 0.0 on its outer edge
+    /// (see `VertexAttributes::coverage`).
+    ///
+    /// Default value: `None`.
+    pub anti_aliasing: Option<f32>,
+
+    /// Whether to preserve the quadratic bézier curves instead of flattening them.
+    ///
+    /// When set, each quadratic bézier curve of the path is represented by a single curve
+    /// triangle made of its endpoints and its control point, and the rest of the shape is
+    /// tessellated from the control polygons (the endpoints and control points) of the
+    /// curves. Cubic bézier curves are approximated with quadratic ones first. The curves
+    /// can then be rendered at any scale by evaluating the `curve` coordinates of the
+    /// vertices in a shader (see `VertexAttributes::curve`).
+    ///
+    /// The curves whose triangles overlap other curve triangles or edges of the path are
+    /// subdivided, and the parts that still overlap after a few subdivisions are flattened
+    /// (this happens where an edge crosses a curve). The anti-aliasing fringe
+    /// is not generated in this mode. Only used by `FillTessellator::tessellate_path`.
+    ///
+    /// Default value: `false`.
+    pub curve_triangles: bool,
+
+    /// A convex polygon to clip the shape to.
+    ///
+    /// When set, the edges of the polygon are added to the path and only the regions that
+    /// are inside of both are tessellated. With `anti_aliasing`, `curve_triangles`, or
+    /// when tessellating with custom attributes or sources, the triangles are clipped
+    /// after the tessellation instead, new vertices being interpolated on the clip edges.
+    /// Only used by `FillTessellator`.
+    ///
+    /// Default value: `None`.
+    pub clip: Option<ClipPolygon>,
+
+    /// Improves the shape of the triangles.
+    ///
+    /// When set, the edges of the triangulation are flipped until it is the constrained
+    /// Delaunay triangulation of the shape, which maximizes the smallest angle of the
+    /// triangles. The triangles can then be refined according to the `MeshQuality`
+    /// parameters. Vertices at the same position are merged. Not used in
+    /// `curve_triangles` mode.
+    ///
+    /// Default value: `None`.
+    pub mesh_quality: Option<MeshQuality>,
+
+    /// Whether to use exact predicates.
+    ///
+    /// When set, the segment intersections are computed with exact integer arithmetic on
+    /// the fixed point coordinates, which avoids errors with near-coincident intersections.
+    /// This is slower, so it is best used for inputs that are known to be problematic.
+    ///
+    /// Default value: `false`.
+    pub robust_predicates: bool,
+
+    /// Whether to normalize the coordinates of the path before tessellating it.
+    ///
+    /// The fill tessellator works with 32 bits fixed point numbers, which limits the
+    /// coordinates to roughly `[-32768, 32768]` with a precision of `1/65536`. When set,
+    /// the bounding box of the path is mapped to the range where the fixed point numbers
+    /// are the most precise, and the generated vertices are mapped back to the path's
+    /// coordinate space. This allows tessellating paths with large coordinates (GIS or
+    /// CAD data for example) or very small ones. Only used by
+    /// `FillTessellator::tessellate_path` and `FillTessellator::tessellate_path_with_attributes`.
+    ///
+    /// Default value: `false`.
+    pub normalize_coordinates: bool,
+
     // To be able to add fields without making it a breaking change, add an empty private field
     // which makes it impossible to create a FillOptions without the calling constructor.
     _private: (),
@@ -568,6 +1172,12 @@ impl FillOptions {
         compute_normals: true,
         assume_no_intersections: false,
         on_error: OnError::DEFAULT,
+        anti_aliasing: None,
+        curve_triangles: false,
+        clip: None,
+        mesh_quality: None,
+        robust_predicates: false,
+        normalize_coordinates: false,
         _private: (),
     };
 
@@ -609,6 +1219,59 @@ impl FillOptions {
         self.on_error = policy;
         self
     }
+
+    #[inline]
+    pub fn with_anti_aliasing(mut self, fringe_width: f32) -> Self {
+        self.anti_aliasing = Some(fringe_width);
+        self
+    }
+
+    #[inline]
+    pub fn with_curve_triangles(mut self) -> Self {
+        self.curve_triangles = true;
+        self
+    }
+
+    #[inline]
+    pub fn with_clip(mut self, clip: ClipPolygon) -> Self {
+        self.clip = Some(clip);
+        self
+    }
+
+    #[inline]
+    pub fn with_delaunay(self) -> Self {
+        self.with_mesh_quality(MeshQuality::DELAUNAY)
+    }
+
+    #[inline]
+    pub fn with_mesh_quality(mut self, quality: MeshQuality) -> Self {
+        self.mesh_quality = Some(quality);
+        self
+    }
+
+    #[inline]
+    pub fn with_robust_predicates(mut self) -> Self {
+        self.robust_predicates = true;
+        self
+    }
+
+    #[inline]
+    pub fn with_normalized_coordinates(mut self) -> Self {
+        self.normalize_coordinates = true;
+        self
+    }
+
+    /// Subdivides the triangles so that none of their edges is longer than `max_edge_length`.
+    ///
+    /// This enables the mesh quality mode, see `MeshQuality::max_edge_length`. The edges
+    /// shared by more than two triangles are not split, and the number of vertices is
+    /// limited by `MeshQuality::max_vertices`, so some edges can remain longer.
+    /// `max_edge_length` must be positive and finite.
+    #[inline]
+    pub fn with_max_edge_length(self, max_edge_length: f32) -> Self {
+        let quality = self.mesh_quality.unwrap_or(MeshQuality::DELAUNAY);
+        self.with_mesh_quality(quality.with_max_edge_length(max_edge_length))
+    }
 }
 
 impl Default for FillOptions {
@@ -26,6 +26,14 @@ pub(crate) fn segment_intersection(
     e1: &Edge, // The new edge.
     e2: &Edge, // An already inserted edge.
 ) -> Option<TessPoint> {
+    segment_intersection_impl(e1, e2, FixedPoint32::from_f64)
+}
+
+fn segment_intersection_impl(
+    e1: &Edge,
+    e2: &Edge,
+    to_fixed: fn(f64) -> FixedPoint32,
+) -> Option<TessPoint> {
 
     // This early-out test gives a noticeable performance improvement.
     if !x_aabb_test(e1.upper.x, e1.lower.x, e2.upper.x, e2.lower.x) {
@@ -36,9 +44,7 @@ pub(crate) fn segment_intersection(
         return None;
     }
 
-    fn tess_point(x: f64, y: f64) -> TessPoint {
-        TessPoint::new(FixedPoint32::from_f64(x), FixedPoint32::from_f64(y))
-    }
+    let tess_point = |x, y| TessPoint::new(to_fixed(x), to_fixed(y));
 
     let a1 = F64Point::new(e1.upper.x.to_f64(), e1.upper.y.to_f64());
     let b1 = F64Point::new(e1.lower.x.to_f64(), e1.lower.y.to_f64());
@@ -93,6 +99,149 @@ pub(crate) fn segment_intersection(
     None
 }
 
+/// How the intersections are snapped to the fixed point grid in robust mode.
+///
+/// Snapping moves the intersections slightly, which can put the sweep line in an
+/// inconsistent state. When this happens, the tessellation is attempted again with
+/// another snapping mode, in the order of `SNAPPING_MODES`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Snapping {
+    /// Truncates the coordinates like `segment_intersection`.
+    Truncate,
+    /// Rounds the coordinates to the nearest representable value.
+    Nearest,
+    /// Rounds the coordinates towards the negative direction.
+    Floor,
+    /// Rounds the coordinates towards the positive direction.
+    Ceil,
+}
+
+pub(crate) const SNAPPING_MODES: [Snapping; 4] = [
+    Snapping::Truncate,
+    Snapping::Nearest,
+    Snapping::Floor,
+    Snapping::Ceil,
+];
+
+/// Same as `segment_intersection`, with adaptive precision.
+///
+/// The fixed point coordinates are integers, so the orientation tests that decide whether
+/// the segments intersect can be done exactly with 128 bits integers. Like Shewchuk's
+/// adaptive predicates, the floating point computation is used when its error bound
+/// guarantees that the result of the tests is correct, and the exact computation otherwise.
+///
+/// The floating point computation can only truncate or round the intersection to the
+/// nearest point, so the other snapping modes always use the exact computation.
+pub(crate) fn segment_intersection_robust(e1: &Edge, e2: &Edge, snapping: Snapping) -> Option<TessPoint> {
+    if !x_aabb_test(e1.upper.x, e1.lower.x, e2.upper.x, e2.lower.x) {
+        return None;
+    }
+
+    let to_fixed: fn(f64) -> FixedPoint32 = match snapping {
+        Snapping::Truncate => FixedPoint32::from_f64,
+        Snapping::Nearest => FixedPoint32::from_f64_rounded,
+        Snapping::Floor | Snapping::Ceil => {
+            return segment_intersection_exact(e1, e2, snapping);
+        }
+    };
+
+    let a1 = F64Point::new(e1.upper.x.to_f64(), e1.upper.y.to_f64());
+    let b1 = F64Point::new(e1.lower.x.to_f64(), e1.lower.y.to_f64());
+    let a2 = F64Point::new(e2.upper.x.to_f64(), e2.upper.y.to_f64());
+    let b2 = F64Point::new(e2.lower.x.to_f64(), e2.lower.y.to_f64());
+
+    // The differences of fixed point coordinates are exact in f64, so the only errors come
+    // from the products and the subtraction of the cross products.
+    let cross = |a: euclid::default::Vector2D<f64>, b: euclid::default::Vector2D<f64>| {
+        (a.cross(b), (a.x * b.y).abs() + (a.y * b.x).abs())
+    };
+    let err = |magnitude: f64| magnitude * 2.0 * f64::EPSILON;
+
+    let (denom, denom_mag) = cross(b1 - a1, b2 - a2);
+    let (t, t_mag) = cross(a2 - a1, b2 - a2);
+    let (u, u_mag) = cross(a2 - a1, b1 - a1);
+
+    let certain = denom.abs() > err(denom_mag)
+        && t.abs() > err(t_mag)
+        && u.abs() > err(u_mag)
+        && (denom.abs() - t.abs()).abs() > err(denom_mag + t_mag)
+        && (denom.abs() - u.abs()).abs() > err(denom_mag + u_mag);
+
+    if certain {
+        segment_intersection_impl(e1, e2, to_fixed)
+    } else {
+        segment_intersection_exact(e1, e2, snapping)
+    }
+}
+
+// Same as `segment_intersection`, using exact integer arithmetic to decide whether the
+// segments intersect. Only the position of the intersection is snapped to the grid,
+// truncation being replaced with rounding to the nearest point.
+fn segment_intersection_exact(
+    e1: &Edge, // The new edge.
+    e2: &Edge, // An already inserted edge.
+    snapping: Snapping,
+) -> Option<TessPoint> {
+    if !x_aabb_test(e1.upper.x, e1.lower.x, e2.upper.x, e2.lower.x) {
+        return None;
+    }
+
+    if e1.upper == e2.lower || e1.upper == e2.upper || e1.lower == e2.upper || e1.lower == e2.lower {
+        return None;
+    }
+
+    let raw = |p: TessPoint| (i128::from(p.x.raw()), i128::from(p.y.raw()));
+    let cross = |a: (i128, i128), b: (i128, i128)| a.0 * b.1 - a.1 * b.0;
+    let sub = |a: (i128, i128), b: (i128, i128)| (a.0 - b.0, a.1 - b.1);
+
+    let (a1, b1, a2, b2) = (raw(e1.upper), raw(e1.lower), raw(e2.upper), raw(e2.lower));
+    let v1 = sub(b1, a1);
+    let v2 = sub(b2, a2);
+
+    let v1_cross_v2 = cross(v1, v2);
+    if v1_cross_v2 == 0 {
+        return None;
+    }
+
+    let sign = v1_cross_v2.signum();
+    let denom = v1_cross_v2.abs();
+    let t = cross(sub(a2, a1), v2) * sign;
+    let u = cross(sub(a2, a1), v1) * sign;
+    if t < 0 || t > denom || u <= 0 || u > denom {
+        return None;
+    }
+
+    // Snap intersections to the edge if it is very close, like `segment_intersection`.
+    if (denom - t) * 1_000_000 < denom {
+        return Some(e1.lower);
+    }
+    if (denom - u) * 1_000_000 < denom {
+        return Some(e2.lower);
+    }
+
+    // Rounds n / d to an integer, with d > 0.
+    let div_round = |n: i128, d: i128| match snapping {
+        Snapping::Truncate | Snapping::Nearest => (2 * n + d).div_euclid(2 * d),
+        Snapping::Floor => n.div_euclid(d),
+        Snapping::Ceil => -(-n).div_euclid(d),
+    };
+    let res = TessPoint::new(
+        FixedPoint32::from_raw((a1.0 + div_round(v1.0 * t, denom)) as i32),
+        FixedPoint32::from_raw((a1.1 + div_round(v1.1 * t, denom)) as i32),
+    );
+
+    if res == e1.lower || res == e2.lower {
+        return Some(res);
+    }
+
+    if res != e1.upper && res != e2.upper
+        && res.y <= e1.lower.y && res.y <= e2.lower.y {
+        return Some(res);
+    }
+
+    None
+}
+
 /// Compute a normal vector at a point P such that ```x ---e1----> P ---e2---> x```
 ///
 /// The resulting vector is not normalized. The length is such that extruding the shape
@@ -26,14 +26,23 @@ use std::cmp::{PartialOrd, Ordering};
 use sid::{Id, IdVec};
 
 use crate::FillVertex as Vertex;
-use crate::{FillOptions, FillRule, Side, OnError, TessellationError, TessellationResult, InternalError};
+use crate::{FillOptions, FillRule, Side, OnError, TessellationError, TessellationResult, InternalError, InternalErrorCode};
+use crate::ClipPolygon;
+use crate::{CurveCoordinates, VertexAttributes, VertexSource};
 use crate::geom::math::*;
-use crate::geom::{QuadraticBezierSegment, CubicBezierSegment};
+use crate::geom::{QuadraticBezierSegment, CubicBezierSegment, LineSegment};
+use crate::geom::cubic_to_quadratic::cubic_to_quadratics;
 use crate::geom::euclid::{self, Trig};
 use crate::math_utils::*;
 use crate::geometry_builder::{GeometryBuilder, GeometryBuilderError, Count, VertexId};
-use crate::path::PathEvent;
+use crate::fringe::with_fringe;
+use crate::clip::{with_clip, clip_convex_polygon};
+use crate::quality::with_mesh_quality;
+use crate::path::{PathEvent, PathSlice};
 use crate::path::builder::{Build, FlatPathBuilder};
+use crate::path::iterator::PathIterator;
+use lyon_algorithms::aabb::fast_bounding_rect;
+use lyon_algorithms::hit_test::winding_numbers;
 
 #[cfg(feature="debugger")]
 use crate::debugger::*;
@@ -102,29 +111,56 @@ impl Edge {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+// Index of the input path event that an edge comes from, used to look up the custom
+// attributes and the source of the vertices (see EdgeAttributes).
+type EdgeSource = u32;
+const NO_SOURCE: EdgeSource = u32::MAX;
+
+// The winding of the edges of the clip polygon when it is applied during the sweep (see
+// FillEvents::add_clip). It is large enough to be told apart from the winding of the path
+// as long as fewer than 2048 sub-paths overlap.
+const CLIP_WINDING: i16 = 1 << 12;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 struct OrientedEdge {
     upper: TessPoint,
     lower: TessPoint,
     winding: i16,
+    src: EdgeSource,
 }
 
 impl OrientedEdge {
-    fn new(mut a: TessPoint, mut b: TessPoint) -> Self {
+    fn new(mut a: TessPoint, mut b: TessPoint, src: EdgeSource) -> Self {
         let mut winding = 1;
         if is_after(a, b) {
             swap(&mut a, &mut b);
             winding = -1;
         }
-        OrientedEdge { upper: a, lower: b, winding }
+        OrientedEdge { upper: a, lower: b, winding, src }
     }
 
-    fn with_winding(mut a: TessPoint, mut b: TessPoint, winding: i16) -> Self {
+    fn with_winding(mut a: TessPoint, mut b: TessPoint, winding: i16, src: EdgeSource) -> Self {
         debug_assert!(winding != 0);
         if is_after(a, b) {
             swap(&mut a, &mut b);
         }
-        OrientedEdge { upper: a, lower: b, winding }
+        OrientedEdge { upper: a, lower: b, winding, src }
+    }
+
+    // The part of an edge from `from` to `to`. Snapping intersections to the fixed point
+    // grid can occasionally make it go backward compared to the original edge, in which
+    // case its winding is reversed as well.
+    fn split_part(from: TessPoint, to: TessPoint, winding: i16, src: EdgeSource) -> Self {
+        let winding = if is_after(from, to) { -winding } else { winding };
+        OrientedEdge::with_winding(from, to, winding, src)
+    }
+
+    // Applies the winding of another edge, taking a change of orientation into account,
+    // and its source.
+    fn with_winding_of(mut self, other: &OrientedEdge) -> Self {
+        self.winding *= other.winding;
+        self.src = other.src;
+        self
     }
 
     fn to_active_edge(&self, upper_id: VertexId) -> ActiveEdge {
@@ -135,6 +171,7 @@ impl OrientedEdge {
             },
             upper_id,
             winding: self.winding,
+            src: self.src,
             merge: false,
         }
     }
@@ -150,6 +187,7 @@ struct PendingEdge {
     lower: TessPoint,
     angle: f32,
     winding: i16,
+    src: EdgeSource,
 }
 
 impl PendingEdge {
@@ -158,6 +196,7 @@ impl PendingEdge {
             upper,
             lower: self.lower,
             winding: self.winding,
+            src: self.src,
         }
     }
 
@@ -169,10 +208,10 @@ impl PendingEdge {
             },
             upper_id,
             winding: self.winding,
+            src: self.src,
             merge: false,
         }
     }
-
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -285,12 +324,30 @@ pub struct FillTessellator {
     // Events and intersections that haven't been processed yet.
     events: FillEvents,
     intersections: Vec<OrientedEdge>,
+    // With fill rules other than even-odd, the edges that intersect with the sweep line
+    // but don't separate the inside from the outside of the shape. They don't bound any
+    // span but their winding is needed to classify the edges below them.
+    interior_edges: Vec<OrientedEdge>,
 
     monotone_tessellators: IdVec<SpanId, MonotoneTessellator>,
     tess_pool: Vec<MonotoneTessellator>,
 
     error: Option<TessellationError>,
 
+    // How the intersections are snapped to the fixed point grid when using robust
+    // predicates (see FillOptions::robust_predicates).
+    snapping: Snapping,
+
+    // Whether the events contain the edges of the clip polygon.
+    clipping: bool,
+
+    // When tessellating with custom attributes or sources, the data of the edges of the
+    // path, the edges that touch the current vertex and the data interpolated from them.
+    edge_attributes: EdgeAttributes,
+    vertex_edges: Vec<EdgeSource>,
+    vertex_attributes: Vec<f32>,
+    vertex_source: Option<VertexSource>,
+
     #[cfg(feature="debugger")]
     debugger: Option<Box<dyn Debugger2D>>,
 }
@@ -300,12 +357,19 @@ impl FillTessellator {
     pub fn new() -> Self {
         FillTessellator {
             events: FillEvents::new(),
+            interior_edges: Vec::new(),
             active_edges: ActiveEdges::with_capacity(16),
             pending_edges: Vec::with_capacity(8),
             monotone_tessellators: IdVec::with_capacity(16),
             intersections: Vec::with_capacity(8),
             current_position: TessPoint::new(FixedPoint32::min_val(), FixedPoint32::min_val()),
             error: None,
+            snapping: Snapping::Truncate,
+            clipping: false,
+            edge_attributes: EdgeAttributes::new(),
+            vertex_edges: Vec::new(),
+            vertex_attributes: Vec::new(),
+            vertex_source: None,
             options: FillOptions::DEFAULT,
             log: false,
             tess_pool: Vec::with_capacity(8),
@@ -325,15 +389,292 @@ impl FillTessellator {
     where
         Iter: IntoIterator<Item = PathEvent>,
     {
+        with_clip(post_clip(options), None, output, |output| {
+            self.tessellate_path_impl(it, options, output)
+        })
+    }
+
+    fn tessellate_path_impl<Iter>(
+        &mut self,
+        it: Iter,
+        options: &FillOptions,
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult
+    where
+        Iter: IntoIterator<Item = PathEvent>,
+    {
+        if options.normalize_coordinates {
+            return self.tessellate_normalized(it.into_iter(), options, output);
+        }
+
+        if options.curve_triangles {
+            return self.tessellate_curves(it.into_iter(), options, output);
+        }
+
         let mut events = replace(&mut self.events, FillEvents::new());
         events.clear();
         events.set_path(options.tolerance, it.into_iter());
-        let result = self.tessellate_events(&events, options, output);
+        if let Some(clip) = sweep_clip(options) {
+            events.add_clip(clip);
+        }
+        let result = self.tessellate_events_with_fringe(&events, options, output);
+        self.events = events;
+
+        result
+    }
+
+    // Maps the bounding box of the path to the range where the fixed point coordinates are
+    // the most precise, and the generated vertices back to the path's coordinate space.
+    fn tessellate_normalized<Iter>(
+        &mut self,
+        it: Iter,
+        options: &FillOptions,
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult
+    where
+        Iter: Iterator<Item = PathEvent>,
+    {
+        let path: Vec<PathEvent> = it.collect();
+        let mut options = *options;
+        options.normalize_coordinates = false;
+
+        let rect = fast_bounding_rect(path.iter().cloned());
+        let extent = rect.size.width.max(rect.size.height);
+        if !extent.is_finite() || extent <= 0.0 {
+            return self.tessellate_path_impl(path, &options, output);
+        }
+
+        // Scaling by a power of two doesn't lose precision.
+        let scale = (NORMALIZED_EXTENT / extent).log2().floor().exp2();
+        let center = rect.center();
+        let transform = Transform2D::create_translation(-center.x, -center.y).post_scale(scale, scale);
+
+        // The distances are scaled as well.
+        options.tolerance *= scale;
+        options.clip = options.clip.map(|clip| clip.transformed(&transform));
+        if let Some(ref mut fringe_width) = options.anti_aliasing {
+            *fringe_width *= scale;
+        }
+        if let Some(ref mut quality) = options.mesh_quality {
+            quality.max_area = quality.max_area.map(|area| area * scale * scale);
+            quality.max_edge_length = quality.max_edge_length.map(|length| length * scale);
+            quality.min_edge_length *= scale;
+        }
+
+        let mut output = Denormalize {
+            output,
+            scale: 1.0 / scale,
+            offset: center.to_vector(),
+        };
+        self.edge_attributes.transform(&transform);
+
+        self.tessellate_path_impl(path.into_iter().transformed(&transform), &options, &mut output)
+    }
+
+    // Tessellates the interior of the shape from the control polygons of the curves,
+    // and adds a curve triangle for each quadratic bézier curve.
+    fn tessellate_curves<Iter>(
+        &mut self,
+        it: Iter,
+        options: &FillOptions,
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult
+    where
+        Iter: Iterator<Item = PathEvent>,
+    {
+        let tolerance = options.tolerance;
+
+        // The path with cubic bézier curves approximated with quadratic ones.
+        let mut path = Vec::new();
+        let mut num_curves = 0;
+        for evt in it {
+            match evt {
+                PathEvent::Quadratic(segment) => {
+                    path.push(PathEvent::Quadratic(segment));
+                    num_curves += 1;
+                }
+                PathEvent::Cubic(segment) => {
+                    cubic_to_quadratics(&segment, tolerance, &mut |segment| {
+                        path.push(PathEvent::Quadratic(*segment));
+                        num_curves += 1;
+                    });
+                }
+                evt => {
+                    path.push(evt);
+                }
+            }
+        }
+
+        // Curve triangles can't overlap each other or the other edges of the path without
+        // drawing outside of the shape. The overlapping curves are subdivided, which shrinks
+        // their triangles towards the curves, and flattened if they still overlap.
+        for i in 0..=MAX_CURVE_SUBDIVISIONS {
+            let overlapping = overlapping_curves(&path, tolerance);
+            if !overlapping.contains(&true) {
+                break;
+            }
+            let subdivide = i < MAX_CURVE_SUBDIVISIONS;
+            let mut refined = Vec::with_capacity(path.len());
+            for (evt, overlapping) in path.into_iter().zip(overlapping) {
+                match evt {
+                    PathEvent::Quadratic(segment) if overlapping && subdivide => {
+                        let (a, b) = segment.split(0.5);
+                        refined.push(PathEvent::Quadratic(a));
+                        refined.push(PathEvent::Quadratic(b));
+                    }
+                    PathEvent::Quadratic(segment) if overlapping => {
+                        let mut from = segment.from;
+                        segment.for_each_flattened(tolerance, &mut |to| {
+                            refined.push(PathEvent::Line(LineSegment { from, to }));
+                            from = to;
+                        });
+                    }
+                    evt => {
+                        refined.push(evt);
+                    }
+                }
+            }
+            path = refined;
+        }
+
+        // Flattened edges, used to find which side of each curve is inside the shape.
+        let mut edges = Vec::new();
+        let mut curves = Vec::with_capacity(num_curves);
+        let mut first = point(0.0, 0.0);
+        let mut current = first;
+        for evt in &path {
+            match *evt {
+                PathEvent::MoveTo(to) => {
+                    // Sub-paths are implicitly closed.
+                    edges.push(LineSegment { from: current, to: first });
+                    first = to;
+                    current = to;
+                }
+                PathEvent::Line(segment) | PathEvent::Close(segment) => {
+                    edges.push(segment);
+                    current = segment.to;
+                }
+                PathEvent::Quadratic(segment) => {
+                    let mut from = segment.from;
+                    segment.for_each_flattened(tolerance, &mut |to| {
+                        edges.push(LineSegment { from, to });
+                        from = to;
+                    });
+                    curves.push(segment);
+                    current = segment.to;
+                }
+                PathEvent::Cubic(..) => unreachable!(),
+            }
+        }
+        edges.push(LineSegment { from: current, to: first });
+
+        let sides = curve_sides(&curves, &edges, options.fill_rule, tolerance);
+
+        let mut curve_triangles = Vec::with_capacity(curves.len());
+        let mut interior = Vec::with_capacity(path.len());
+        let mut sides = sides.into_iter();
+        for evt in path {
+            let segment = match evt {
+                PathEvent::Quadratic(segment) => segment,
+                evt => {
+                    interior.push(evt);
+                    continue;
+                }
+            };
+
+            let line = |from, to| PathEvent::Line(LineSegment { from, to });
+            match sides.next().unwrap() {
+                Some(true) => {
+                    // The control point is inside of the shape.
+                    interior.push(line(segment.from, segment.ctrl));
+                    interior.push(line(segment.ctrl, segment.to));
+                    curve_triangles.push((segment, -1.0));
+                }
+                Some(false) => {
+                    interior.push(line(segment.from, segment.to));
+                    curve_triangles.push((segment, 1.0));
+                }
+                None => {
+                    interior.push(line(segment.from, segment.to));
+                }
+            }
+        }
+
+        let mut events = replace(&mut self.events, FillEvents::new());
+        events.clear();
+        events.set_path(tolerance, interior.into_iter());
+        let mut interior_options = *options;
+        interior_options.curve_triangles = false;
+        interior_options.anti_aliasing = None;
+        // The curve triangles are clipped after the tessellation.
+        interior_options.clip = None;
+        let result = self.tessellate_events_impl(&events, &interior_options, &curve_triangles, output);
         self.events = events;
 
         result
     }
 
+    /// Compute the tessellation of a path, taking its custom attributes into account.
+    ///
+    /// The attributes of each vertex are interpolated along the edge of the path it
+    /// lies on (along the length of curves) and passed to
+    /// `GeometryBuilder::add_vertex_with_attributes`. Vertices created at the intersection
+    /// of several edges take the average of their attributes.
+    ///
+    /// The curves are always flattened, `FillOptions::curve_triangles` is ignored.
+    pub fn tessellate_path_with_attributes(
+        &mut self,
+        path: PathSlice,
+        options: &FillOptions,
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult {
+        if path.num_attributes() == 0 {
+            return self.tessellate_path(path.iter(), options, output);
+        }
+
+        self.tessellate_path_with_edge_attributes(path, options, false, output)
+    }
+
+    /// Compute the tessellation of a path, reporting the location in the path that each
+    /// vertex was generated from.
+    ///
+    /// This is useful for picking or for coloring each sub-path differently. The source of
+    /// each vertex (see `VertexSource`) is that of the edge of the path it lies on, and is
+    /// passed to `GeometryBuilder::add_vertex_with_attributes` along with the custom attributes
+    /// of the path, interpolated as in `tessellate_path_with_attributes`. Vertices created
+    /// at the intersection of several edges take the source of the first of them in the
+    /// path.
+    pub fn tessellate_path_with_sources(
+        &mut self,
+        path: PathSlice,
+        options: &FillOptions,
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult {
+        self.tessellate_path_with_edge_attributes(path, options, true, output)
+    }
+
+    fn tessellate_path_with_edge_attributes(
+        &mut self,
+        path: PathSlice,
+        options: &FillOptions,
+        sources: bool,
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult {
+        let mut options = *options;
+        options.curve_triangles = false;
+
+        // The attributes are interpolated along the edges of the path, so the vertices
+        // on the clip edges are interpolated after the tessellation instead.
+        let clip = options.clip.take();
+        with_clip(clip.as_ref(), None, output, |output| {
+            let events = self.edge_attributes.set_path(path, options.tolerance, sources);
+            let result = self.tessellate_path_impl(events, &options, output);
+            self.edge_attributes.clear();
+
+            result
+        })
+    }
+
     /// Compute the tessellation from pre-sorted events.
     pub fn tessellate_events(
         &mut self,
@@ -341,21 +682,92 @@ impl FillTessellator {
         options: &FillOptions,
         output: &mut dyn GeometryBuilder<Vertex>,
     ) -> TessellationResult {
-        if options.fill_rule != FillRule::EvenOdd {
-            println!("warning: Fill rule {:?} is not supported yet.", options.fill_rule);
-            match options.on_error {
-                OnError::Stop => { return Err(TessellationError::UnsupportedParamater); }
-                OnError::Panic => { panic!("Unsupported fill rule"); }
-                OnError::Recover => {}
+        if let Some(clip) = sweep_clip(options) {
+            let mut clipped = replace(&mut self.events, FillEvents::new());
+            clipped.clone_from(events);
+            clipped.add_clip(clip);
+            let result = self.tessellate_events_with_fringe(&clipped, options, output);
+            self.events = clipped;
+
+            return result;
+        }
+
+        with_clip(post_clip(options), None, output, |output| {
+            self.tessellate_events_with_fringe(events, options, output)
+        })
+    }
+
+    fn tessellate_events_with_fringe(
+        &mut self,
+        events: &FillEvents,
+        options: &FillOptions,
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult {
+        with_fringe(options.anti_aliasing, None, output, |output| {
+            with_mesh_quality(options.mesh_quality.as_ref(), output, |output| {
+                self.tessellate_events_impl(events, options, &[], output)
+            })
+        })
+    }
+
+    fn tessellate_events_impl(
+        &mut self,
+        events: &FillEvents,
+        options: &FillOptions,
+        curve_triangles: &[(QuadraticBezierSegment<f32>, f32)],
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult {
+        if !options.robust_predicates {
+            return self.tessellate_events_attempt(events, options, curve_triangles, output);
+        }
+
+        // With exact predicates, the remaining inconsistencies come from snapping the
+        // intersections to the fixed point grid, so if the sweep runs into one, try again
+        // with the intersections snapped differently. Only the last attempt uses the
+        // error handling of the options.
+        let mut attempt_options = *options;
+        attempt_options.on_error = OnError::Stop;
+        let mut res = Ok(Count { vertices: 0, indices: 0 });
+        for (i, &snapping) in SNAPPING_MODES.iter().enumerate() {
+            let last = i == SNAPPING_MODES.len() - 1;
+            self.snapping = snapping;
+            res = self.tessellate_events_attempt(
+                events,
+                if last { options } else { &attempt_options },
+                curve_triangles,
+                output,
+            );
+            match res {
+                Err(TessellationError::Internal(..)) => {}
+                _ => { break; }
             }
         }
+        self.snapping = Snapping::Truncate;
 
+        res
+    }
+
+    fn tessellate_events_attempt(
+        &mut self,
+        events: &FillEvents,
+        options: &FillOptions,
+        curve_triangles: &[(QuadraticBezierSegment<f32>, f32)],
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult {
         self.options = *options;
+        self.clipping = sweep_clip(options).is_some();
 
         self.begin_tessellation(output);
 
         self.tessellator_loop(events, output);
 
+        for &(ref curve, sign) in curve_triangles {
+            if let Err(e) = add_curve_triangle(curve, sign, output) {
+                self.builder_error(e);
+                break;
+            }
+        }
+
         let mut error = None;
         swap(&mut error, &mut self.error);
         if let Some(err) = error {
@@ -386,6 +798,8 @@ impl FillTessellator {
         self.active_edges.clear();
         self.monotone_tessellators.clear();
         self.pending_edges.clear();
+        self.intersections.clear();
+        self.interior_edges.clear();
     }
 
     fn begin_tessellation(&mut self, output: &mut dyn GeometryBuilder<Vertex>) {
@@ -444,6 +858,7 @@ impl FillTessellator {
                         lower: edge.lower,
                         angle,
                         winding: edge.winding,
+                        src: edge.src,
                     });
                     tess_log!(self, " edge at {:?} -> {:?} (angle={:?})", edge.upper, edge.lower, angle);
 
@@ -481,6 +896,7 @@ impl FillTessellator {
                                 lower: inter.lower,
                                 angle: edge_angle(inter.lower - self.current_position),
                                 winding: inter.winding,
+                                src: inter.src,
                             }
                         );
                     }
@@ -592,7 +1008,24 @@ impl FillTessellator {
             (next - position).normalize(),
         );
 
-        output.add_vertex(Vertex { position, normal })
+        self.add_vertex(Vertex { position, normal }, output)
+    }
+
+    fn add_vertex(
+        &self,
+        vertex: Vertex,
+        output: &mut dyn GeometryBuilder<Vertex>
+    ) -> Result<VertexId, GeometryBuilderError> {
+        if self.edge_attributes.is_empty() {
+            return output.add_vertex(vertex);
+        }
+
+        let attributes = VertexAttributes {
+            custom: &self.vertex_attributes,
+            source: self.vertex_source,
+            ..VertexAttributes::NONE
+        };
+        output.add_vertex_with_attributes(vertex, attributes)
     }
 
     fn process_vertex(
@@ -608,6 +1041,8 @@ impl FillTessellator {
         // The logic here really need to be simplified, it is the trickiest part of the
         // tessellator.
 
+        self.vertex_edges.clear();
+
         let (
             // Whether the point is inside, outside or on an edge.
             point_type,
@@ -615,10 +1050,21 @@ impl FillTessellator {
             first_edge_above,
             // Number of active edges that end at the current point.
             mut num_edges_above,
-            // The number used by the fill rule to determine what is in or out.
-            _winding_number,
+            // The winding number of the region on the left of the current point.
+            mut winding_number,
         ) = self.find_interesting_active_edges();
 
+        // The winding numbers are needed to tell the clip polygon apart from the path.
+        let even_odd = self.options.fill_rule == FillRule::EvenOdd && !self.clipping;
+        if !even_odd {
+            winding_number += self.find_interesting_interior_edges();
+        }
+
+        if !self.edge_attributes.is_empty() {
+            // The edges that end at the current position were collected above.
+            self.vertex_edges.extend(self.pending_edges.iter().map(|edge| edge.src));
+        }
+
         // We'll bump above_idx as we process active edges that interact with
         // the current point.
         let mut above_idx = first_edge_above;
@@ -628,19 +1074,39 @@ impl FillTessellator {
         // Go through all pending edges, sort them and handle pairs of overlapping edges.
         // Doing this here avoids some potentially tricky cases with intersections
         // later.
-        prepare_pending_edges(&mut self.pending_edges, &mut self.intersections);
+        prepare_pending_edges(&mut self.pending_edges, &mut self.intersections, even_odd);
+
+        if !even_odd {
+            self.classify_pending_edges(winding_number);
+
+            if num_edges_above == 0 && self.pending_edges.is_empty() {
+                // Only interior edges touch the current point.
+                return Ok(());
+            }
+        }
 
         self.log_sl(first_edge_above);
         tess_log!(self, "{:?}", point_type);
         tess_log!(self, "above:{}", num_edges_above);
 
+        if !self.edge_attributes.is_empty() {
+            self.vertex_edges.sort_unstable();
+            self.vertex_edges.dedup();
+            self.vertex_source = self.edge_attributes.interpolate(
+                to_f32_point(self.current_position),
+                &self.vertex_edges,
+                &mut self.vertex_attributes,
+            );
+        }
+
         let mut vertex_id = if !self.options.compute_normals {
             let vector_position = to_f32_point(self.current_position);
-            output.add_vertex(
+            self.add_vertex(
                 Vertex {
                     position: vector_position,
                     normal: vector(0.0, 0.0),
-                }
+                },
+                output,
             )?
         } else {
             // placeholder
@@ -768,7 +1234,11 @@ impl FillTessellator {
         // Since we took care of left and right events already we should not have
         // an odd number of pending edges to work with by now.
         if num_pending_edges % 2 != 0 {
-            if self.error(InternalError::E01) {
+            let edge = self.pending_edges.get(pending_edge_id).map(|e| Edge {
+                upper: self.current_position,
+                lower: e.lower,
+            });
+            if self.error(InternalErrorCode::E01, "odd number of edges below a vertex", edge) {
                 return Ok(());
             }
             // TODO - We are in an invalid state, and trying to continue tessellating
@@ -798,12 +1268,18 @@ impl FillTessellator {
                     vertex_id = self.add_vertex_with_normal(&left_vertex, &right_vertex, output)?;
                 }
 
+                let merge = self.active_edges[above_idx].merge;
                 self.split_event(above_idx, left_idx, right_idx, vertex_id, output);
 
                 num_pending_edges -= 2;
+                // The edges in between form start events.
+                pending_edge_id = left_idx + 1;
                 // split_event inserts two active edges at the current offset
-                // and we need skip them.
-                above_idx = above_idx + 2;
+                // and we need skip them, unless it replaced the merge edges
+                // around the current offset.
+                if !merge {
+                    above_idx = above_idx + 2;
+                }
             }
 
             while num_pending_edges >= 2 {
@@ -830,12 +1306,12 @@ impl FillTessellator {
             }
         }
 
-        self.debug_check_sl();
+        self.check_sl();
 
         self.pending_edges.clear();
 
         if num_edges_above != 0 || num_pending_edges != 0 {
-            self.error(InternalError::E02);
+            self.error(InternalErrorCode::E02, "edges left after processing a vertex", None);
         }
 
         Ok(())
@@ -858,8 +1334,6 @@ impl FillTessellator {
                 continue;
             }
 
-            winding_number += active_edge.winding;
-
             let edge_idx = ActiveEdgeId::new(i);
             let side = if even(edge_idx) { Side::Left } else { Side::Right };
 
@@ -886,6 +1360,7 @@ impl FillTessellator {
                 // too early.
                 debug_assert!(!edge_after_point);
                 num_edges_above += 1;
+                self.vertex_edges.push(active_edge.src);
                 if point_type.is_none() {
                     point_type = Some(PointType::OnEdge(side));
                     first_edge_above = edge_idx;
@@ -902,6 +1377,7 @@ impl FillTessellator {
                     lower: active_edge.points.lower,
                     angle: edge_angle(active_edge.points.lower - self.current_position),
                     winding: active_edge.winding,
+                    src: active_edge.src,
                 });
                 active_edge.points.lower = self.current_position;
             }
@@ -928,6 +1404,10 @@ impl FillTessellator {
                 // we know that there won't be any more edges touching this point.
                 break;
             }
+
+            if !at_endpoint && !on_edge {
+                winding_number += active_edge.winding;
+            }
         }
 
         (
@@ -938,6 +1418,87 @@ impl FillTessellator {
         )
     }
 
+    // Same as find_interesting_active_edges for the interior edges: the edges that end at
+    // the current position are removed, the ones passing through it are split, and the
+    // winding number of the interior edges on the left of the current position is returned.
+    fn find_interesting_interior_edges(&mut self) -> i16 {
+        let mut winding_number = 0;
+        let mut i = 0;
+        while i < self.interior_edges.len() {
+            let edge = self.interior_edges[i];
+            let mut on_edge = false;
+            let mut edge_after_point = false;
+
+            if is_after(edge.lower, self.current_position) {
+                compare_edge_against_position(
+                    &edge.edge(),
+                    self.current_position,
+                    &mut on_edge,
+                    &mut edge_after_point,
+                );
+            } else {
+                self.vertex_edges.push(edge.src);
+                self.interior_edges.swap_remove(i);
+                continue;
+            }
+
+            if on_edge {
+                self.pending_edges.push(PendingEdge {
+                    lower: edge.lower,
+                    angle: edge_angle(edge.lower - self.current_position),
+                    winding: edge.winding,
+                    src: edge.src,
+                });
+                self.interior_edges.swap_remove(i);
+                continue;
+            }
+
+            if !edge_after_point {
+                winding_number += edge.winding;
+            }
+
+            i += 1;
+        }
+
+        winding_number
+    }
+
+    // Walk the pending edges from left to right and move the ones that don't separate
+    // the inside from the outside of the shape to the interior edges, so that the rest
+    // of the sweep only sees the edges of the even-odd spans.
+    fn classify_pending_edges(&mut self, winding_left: i16) {
+        let mut winding = winding_left;
+        let mut i = 0;
+        while i < self.pending_edges.len() {
+            let edge = self.pending_edges[i].clone();
+            let winding_right = winding + edge.winding;
+            if self.is_in(winding) != self.is_in(winding_right) {
+                i += 1;
+            } else {
+                self.pending_edges.remove(i);
+                self.insert_interior_edge(edge);
+            }
+            winding = winding_right;
+        }
+    }
+
+    // Whether the region with this winding number is inside of the shape. When clipping,
+    // the winding number is the sum of the winding of the clip polygon and the path.
+    fn is_in(&self, winding: i16) -> bool {
+        let fill_rule = self.options.fill_rule;
+        if !self.clipping {
+            return fill_rule.is_in(winding);
+        }
+
+        let clip = (winding + CLIP_WINDING / 2).div_euclid(CLIP_WINDING);
+        clip != 0 && fill_rule.is_in(winding - clip * CLIP_WINDING)
+    }
+
+    fn insert_interior_edge(&mut self, mut edge: PendingEdge) {
+        self.find_intersections(&mut edge);
+        self.interior_edges.push(edge.to_oriented_edge(self.current_position));
+    }
+
     // Look for eventual merge vertices on this span above the current vertex, and connect
     // them to the current vertex.
     // This should be called when processing a vertex that is on the left side of a span.
@@ -1139,6 +1700,12 @@ impl FillTessellator {
     }
 
     fn handle_intersections(&mut self, new_edge_idx: usize) {
+        let mut new_edge = self.pending_edges[new_edge_idx].clone();
+        self.find_intersections(&mut new_edge);
+        self.pending_edges[new_edge_idx].lower = new_edge.lower;
+    }
+
+    fn find_intersections(&mut self, pending_edge: &mut PendingEdge) {
         // Test and for intersections against the edges in the sweep line.
         // If an intersecton is found, the edge is split and retains only the part
         // above the intersection point. The lower part is kept with the intersection
@@ -1152,15 +1719,25 @@ impl FillTessellator {
             return;
         }
 
-        let mut new_edge = self.pending_edges[new_edge_idx].to_oriented_edge(self.current_position);
+        let mut new_edge = pending_edge.to_oriented_edge(self.current_position);
 
         let original_edge = new_edge.edge();
         let mut intersection = None;
 
-        for (edge_idx, edge) in self.active_edges.iter_mut().enumerate() {
+        let snapping = self.snapping;
+        let robust = self.options.robust_predicates;
+        let intersect = |a: &Edge, b: &Edge| {
+            if robust {
+                segment_intersection_robust(a, b, snapping)
+            } else {
+                segment_intersection(a, b)
+            }
+        };
+
+        for (edge_idx, edge) in self.active_edges.iter().enumerate() {
             // Test for an intersection against the span's left edge.
             if !edge.merge {
-                if let Some(position) = segment_intersection(&new_edge.edge(), &edge.points) {
+                if let Some(position) = intersect(&new_edge.edge(), &edge.points) {
                     tess_log!(self, " -- found an intersection at {:?}
                                     |    {:?}->{:?} x {:?}->{:?}",
                         position,
@@ -1168,7 +1745,7 @@ impl FillTessellator {
                         edge.points.upper, edge.points.lower,
                     );
 
-                    intersection = Some((position, ActiveEdgeId::new(edge_idx)));
+                    intersection = Some((position, IntersectedEdge::Active(ActiveEdgeId::new(edge_idx))));
                     // From now on only consider potential intersections above the one we found,
                     // by removing the lower part from the segment we test against.
                     new_edge.lower = position;
@@ -1176,11 +1753,18 @@ impl FillTessellator {
             }
         }
 
+        for (edge_idx, edge) in self.interior_edges.iter().enumerate() {
+            if let Some(position) = intersect(&new_edge.edge(), &edge.edge()) {
+                intersection = Some((position, IntersectedEdge::Interior(edge_idx)));
+                new_edge.lower = position;
+            }
+        }
+
         if intersection.is_none() {
             return;
         }
 
-        let (mut intersection, edge_idx) = intersection.unwrap();
+        let (mut intersection, intersected_edge) = intersection.unwrap();
 
         // Because precision issues, it can happen that the intersection appear to be
         // "above" the current vertex (in fact it is at the same y but on its left which
@@ -1192,26 +1776,34 @@ impl FillTessellator {
             new_edge.lower = intersection;
         }
 
-        self.pending_edges[new_edge_idx].lower = new_edge.lower;
+        pending_edge.lower = new_edge.lower;
 
-        let active_edge_lower;
-        let active_edge_winding;
-        {
-            let active_edge = &mut self.active_edges[edge_idx];
-            active_edge_lower = active_edge.points.lower;
-            active_edge_winding = active_edge.winding;
-            active_edge.points.lower = intersection;
-        }
+        let (other_edge_lower, other_edge_winding, other_edge_src) = match intersected_edge {
+            IntersectedEdge::Active(edge_idx) => {
+                let active_edge = &mut self.active_edges[edge_idx];
+                let lower = active_edge.points.lower;
+                active_edge.points.lower = intersection;
+                (lower, active_edge.winding, active_edge.src)
+            }
+            IntersectedEdge::Interior(edge_idx) => {
+                let interior_edge = &mut self.interior_edges[edge_idx];
+                let lower = interior_edge.lower;
+                interior_edge.lower = intersection;
+                (lower, interior_edge.winding, interior_edge.src)
+            }
+        };
 
-        self.intersections.push(OrientedEdge::with_winding(
+        self.intersections.push(OrientedEdge::split_part(
             intersection,
             original_edge.lower,
-            new_edge.winding
+            new_edge.winding,
+            new_edge.src,
         ));
-        self.intersections.push(OrientedEdge::with_winding(
+        self.intersections.push(OrientedEdge::split_part(
             intersection,
-            active_edge_lower,
-            active_edge_winding
+            other_edge_lower,
+            other_edge_winding,
+            other_edge_src,
         ));
 
         #[cfg(feature="debugger")] {
@@ -1257,10 +1849,19 @@ impl FillTessellator {
 
     #[cold]
     #[inline(never)]
-    fn error(&mut self, err: InternalError) -> bool {
-        tess_log!(self, " !! FillTessellator Error {:?}", err);
+    fn error(&mut self, code: InternalErrorCode, description: &'static str, edge: Option<Edge>) -> bool {
+        let err = InternalError {
+            code,
+            description,
+            position: to_f32_point(self.current_position),
+            edge: edge.map(|e| LineSegment {
+                from: to_f32_point(e.upper),
+                to: to_f32_point(e.lower),
+            }),
+        };
+        tess_log!(self, " !! FillTessellator Error {}", err);
         if self.panic_on_errors() {
-            panic!();
+            panic!("{}", err);
         }
         if self.error.is_none() {
             self.error = Some(TessellationError::Internal(err));
@@ -1284,12 +1885,14 @@ impl FillTessellator {
         }
     }
 
-    #[cfg(not(debug_assertions))]
-    fn debug_check_sl(&mut self) {}
+    // Checks the sweep line for inconsistencies. This only runs in debug builds, and with
+    // robust predicates where an error means that the tessellation is attempted again.
+    fn check_sl(&mut self) {
+        if !cfg!(debug_assertions) && !self.options.robust_predicates {
+            return;
+        }
 
-    #[cfg(debug_assertions)]
-    fn debug_check_sl(&mut self) {
-        let mut ok = true;
+        let mut bad_edge = None;
         for edge in &self.active_edges {
             if edge.merge {
                 continue;
@@ -1300,7 +1903,7 @@ impl FillTessellator {
                     self.current_position,
                     edge.points.lower
                 );
-                ok = false;
+                bad_edge = Some(edge.points);
                 break;
             }
             if is_after(edge.points.upper, edge.points.lower) {
@@ -1309,13 +1912,13 @@ impl FillTessellator {
                     edge.points.upper,
                     edge.points.lower
                 );
-                ok = false;
+                bad_edge = Some(edge.points);
                 break;
             }
         }
 
-        if !ok {
-            self.error(InternalError::E03);
+        if bad_edge.is_some() {
+            self.error(InternalErrorCode::E03, "active edge in an invalid state", bad_edge);
         }
 
         self.log_sl_winding();
@@ -1336,6 +1939,9 @@ impl FillTessellator {
         for b in &self.pending_edges {
             println!("   -- below: {:?}", b);
         }
+        for e in &self.interior_edges {
+            println!("   -- interior: {:?}", e);
+        }
     }
 
     fn log_sl_winding(&self) {
@@ -1348,7 +1954,7 @@ impl FillTessellator {
                 1 => "+",
                 -1 => "-",
                 0 => "*",
-                _ => panic!(),
+                _ => "#",
             });
         }
         println!("|");
@@ -1389,6 +1995,56 @@ impl Default for FillTessellator {
     }
 }
 
+// The size of the range that the bounding box of the path is mapped to when normalizing the
+// coordinates, which leaves a margin before the limits of the fixed point numbers.
+const NORMALIZED_EXTENT: f32 = 16384.0;
+
+// Maps the vertices from the normalized coordinate space back to the path's.
+struct Denormalize<'l> {
+    output: &'l mut dyn GeometryBuilder<Vertex>,
+    scale: f32,
+    offset: Vector,
+}
+
+impl<'l> Denormalize<'l> {
+    fn map(&self, mut vertex: Vertex) -> Vertex {
+        vertex.position = vertex.position * self.scale + self.offset;
+        vertex
+    }
+}
+
+impl<'l> GeometryBuilder<Vertex> for Denormalize<'l> {
+    fn begin_geometry(&mut self) {
+        self.output.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        self.output.end_geometry()
+    }
+
+    fn add_vertex(&mut self, vertex: Vertex) -> Result<VertexId, GeometryBuilderError> {
+        let vertex = self.map(vertex);
+        self.output.add_vertex(vertex)
+    }
+
+    fn add_vertex_with_attributes(
+        &mut self,
+        vertex: Vertex,
+        attributes: VertexAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let vertex = self.map(vertex);
+        self.output.add_vertex_with_attributes(vertex, attributes)
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.output.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.output.abort_geometry();
+    }
+}
+
 #[inline]
 fn even(edge: ActiveEdgeId) -> bool { edge.handle % 2 == 0 }
 
@@ -1462,6 +2118,7 @@ fn compare_edge_against_position(
 fn prepare_pending_edges(
     pending_edges: &mut Vec<PendingEdge>,
     intersections: &mut Vec<OrientedEdge>,
+    even_odd: bool,
 ) {
     pending_edges.sort_by(|a, b| a.angle.partial_cmp(&b.angle).unwrap_or(Ordering::Equal));
 
@@ -1474,20 +2131,43 @@ fn prepare_pending_edges(
             let threshold = 0.0035;
             let edge_a = &pending_edges[i];
             let edge_b = &pending_edges[i+1];
-            // TODO: Skipping edges loses track of the correct winding number,
-            // hence the check that the winding isn't affected but it is far
-            // from ideal.
-            let doesnt_affect_winding = true; //edge_a.winding + edge_b.winding == 0;
-            if (edge_a.angle - edge_b.angle).abs() < threshold && doesnt_affect_winding {
+            if (edge_a.angle - edge_b.angle).abs() >= threshold {
+                i += 1;
+                continue;
+            }
+
+            let (nearest, furthest) = if is_after(edge_a.lower, edge_b.lower) {
+                (i + 1, i)
+            } else {
+                (i, i + 1)
+            };
+            if edge_a.lower != edge_b.lower {
+                let furthest = &pending_edges[furthest];
+                intersections.push(OrientedEdge::with_winding(
+                    edge_a.lower,
+                    edge_b.lower,
+                    furthest.winding,
+                    furthest.src,
+                ));
+            }
+
+            if even_odd {
+                // With the even-odd fill rule, a pair of overlapping edges never separates
+                // the inside from the outside of the shape.
                 to_remove.push(i);
-                if edge_a.lower != edge_b.lower {
-                    let furthest = if is_after(edge_a.lower, edge_b.lower) { i } else { i + 1 };
-                    let winding = pending_edges[furthest].winding;
-                    intersections.push(OrientedEdge::with_winding(edge_a.lower, edge_b.lower, winding));
-                }
                 i += 2;
+                continue;
+            }
+
+            // Otherwise the overlapping part is kept as a single edge with the winding
+            // of both edges, which may overlap with the next one as well.
+            let winding = edge_a.winding + edge_b.winding;
+            let nearest = pending_edges[nearest].clone();
+            pending_edges.remove(i + 1);
+            if winding == 0 {
+                pending_edges.remove(i);
             } else {
-                i += 1;
+                pending_edges[i] = PendingEdge { winding, ..nearest };
             }
         }
 
@@ -1498,11 +2178,19 @@ fn prepare_pending_edges(
     }
 }
 
+// The edge of the sweep line that find_intersections found the closest intersection with.
+#[derive(Copy, Clone, Debug)]
+enum IntersectedEdge {
+    Active(ActiveEdgeId),
+    Interior(usize),
+}
+
 #[derive(Copy, Clone, Debug)]
 struct ActiveEdge {
     points: Edge,
     upper_id: VertexId,
     winding: i16,
+    src: EdgeSource,
     merge: bool,
 }
 
@@ -1545,11 +2233,64 @@ fn edge_angle(v: TessVector) -> f32 {
 }
 
 /// A sequence of edges sorted from top to bottom, to be used as the tessellator's input.
+///
+/// Building the events is a significant part of the cost of the fill tessellation. They
+/// can be built once with `FillEvents::from_path`, kept (and serialized with the
+/// `serialization` feature), transformed, and tessellated with
+/// `FillTessellator::tessellate_events`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serialization", serde(from = "SerializedFillEvents", into = "SerializedFillEvents"))]
 pub struct FillEvents {
     edges: Vec<OrientedEdge>,
     vertices: Vec<TessPoint>,
 }
 
+// The fixed point representation of the events.
+#[cfg(feature = "serialization")]
+#[derive(Serialize, Deserialize)]
+struct SerializedFillEvents {
+    // Upper x and y, lower x and y, winding.
+    edges: Vec<(i32, i32, i32, i32, i16)>,
+    vertices: Vec<(i32, i32)>,
+}
+
+#[cfg(feature = "serialization")]
+impl From<FillEvents> for SerializedFillEvents {
+    fn from(events: FillEvents) -> Self {
+        SerializedFillEvents {
+            edges: events.edges.iter().map(|e| {
+                (e.upper.x.raw(), e.upper.y.raw(), e.lower.x.raw(), e.lower.y.raw(), e.winding)
+            }).collect(),
+            vertices: events.vertices.iter().map(|v| (v.x.raw(), v.y.raw())).collect(),
+        }
+    }
+}
+
+// The serialized events come from outside, so they are not trusted to be sorted: the edges
+// are oriented and sorted again and the vertices are computed from the edges.
+#[cfg(feature = "serialization")]
+impl From<SerializedFillEvents> for FillEvents {
+    fn from(events: SerializedFillEvents) -> Self {
+        let p = |x, y| TessPoint::new(FixedPoint32::from_raw(x), FixedPoint32::from_raw(y));
+        let mut edges: Vec<OrientedEdge> = events.edges.iter()
+            .map(|&(ux, uy, lx, ly, winding)| (p(ux, uy), p(lx, ly), winding))
+            .filter(|&(upper, lower, winding)| upper != lower && winding != 0)
+            .map(|(upper, lower, winding)| {
+                let mut edge = OrientedEdge::new(upper, lower, NO_SOURCE);
+                edge.winding *= winding;
+                edge
+            })
+            .collect();
+        edges.sort_by(|a, b| compare_positions(a.upper, b.upper));
+
+        let mut vertices = Vec::new();
+        end_points(&edges, &mut vertices);
+
+        FillEvents { edges, vertices }
+    }
+}
+
 impl FillEvents {
     pub fn from_path<Iter: Iterator<Item = PathEvent>>(tolerance: f32, it: Iter) -> Self {
         let mut events = FillEvents::new();
@@ -1570,6 +2311,10 @@ impl FillEvents {
         self.vertices.clear();
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty() && self.vertices.is_empty()
+    }
+
     pub fn set_path<Iter: Iterator<Item = PathEvent>>(&mut self, tolerance: f32, it: Iter) {
         self.clear();
         let mut tmp = FillEvents::new();
@@ -1579,7 +2324,9 @@ impl FillEvents {
         builder.recycle(tmp);
         builder.tolerance = tolerance;
 
-        for evt in it {
+        for (idx, evt) in it.enumerate() {
+            // The edges are tagged with the index of the event they come from.
+            builder.src = idx as EdgeSource;
             match evt {
                 PathEvent::MoveTo(to) => {
                     builder.move_to(to);
@@ -1601,6 +2348,120 @@ impl FillEvents {
 
         swap(self, &mut builder.build());
     }
+
+    /// Applies a transform to the events.
+    ///
+    /// Transforms that keep the order of the points from top to bottom (translations and
+    /// positive scales along the axes) only move the points. Other transforms also sort
+    /// the edges again, which is still cheaper than building the events from the path.
+    pub fn transform(&mut self, transform: &Transform2D) {
+        let map = |p: TessPoint| to_internal(transform.transform_point(to_f32_point(p)));
+        let keeps_order = transform.m12 == 0.0 && transform.m21 == 0.0
+            && transform.m11 > 0.0 && transform.m22 > 0.0;
+
+        let mut degenerate = false;
+        for edge in &mut self.edges {
+            *edge = OrientedEdge::new(map(edge.upper), map(edge.lower), edge.src).with_winding_of(edge);
+            degenerate |= edge.upper == edge.lower;
+        }
+        for vertex in &mut self.vertices {
+            *vertex = map(*vertex);
+        }
+
+        // Points that are very close can end up at the same position, which breaks the
+        // order of the events just like other transforms.
+        if keeps_order && !degenerate {
+            return;
+        }
+
+        self.edges.retain(|edge| edge.upper != edge.lower);
+        self.edges.sort_by(|a, b| compare_positions(a.upper, b.upper));
+        self.vertices.clear();
+        end_points(&self.edges, &mut self.vertices);
+    }
+
+    /// Returns a transformed copy of the events.
+    ///
+    /// See `FillEvents::transform`.
+    pub fn transformed(&self, transform: &Transform2D) -> Self {
+        let mut events = self.clone();
+        events.transform(transform);
+        events
+    }
+
+    // Adds the edges of the clip polygon with the CLIP_WINDING winding, so that the sweep
+    // only fills the regions that are inside of both the path and the clip polygon.
+    fn add_clip(&mut self, clip: &ClipPolygon) {
+        if self.edges.is_empty() {
+            return;
+        }
+
+        // Only the part of the clip polygon that overlaps the path matters, which also
+        // keeps its coordinates in the range of the fixed point numbers.
+        let mut min = to_f32_point(self.edges[0].upper);
+        let mut max = min;
+        for edge in &self.edges {
+            for p in &[to_f32_point(edge.upper), to_f32_point(edge.lower)] {
+                min = min.min(*p);
+                max = max.max(*p);
+            }
+        }
+        let (min, max) = (min - vector(1.0, 1.0), max + vector(1.0, 1.0));
+        let mut polygon = vec![min, point(max.x, min.y), max, point(min.x, max.y)];
+        clip_convex_polygon(&mut polygon, clip);
+
+        if polygon.is_empty() {
+            // Nothing is inside of the clip polygon.
+            self.clear();
+            return;
+        }
+
+        for i in 0..polygon.len() {
+            let a = to_internal(polygon[i]);
+            let b = to_internal(polygon[(i + 1) % polygon.len()]);
+            if a != b {
+                let mut edge = OrientedEdge::new(a, b, NO_SOURCE);
+                edge.winding *= CLIP_WINDING;
+                self.edges.push(edge);
+            }
+        }
+
+        self.edges.sort_by(|a, b| compare_positions(a.upper, b.upper));
+        self.vertices.clear();
+        end_points(&self.edges, &mut self.vertices);
+    }
+}
+
+// The clip polygon is applied during the sweep, except with the anti-aliasing fringe and
+// the curve triangles which are clipped after the tessellation.
+fn sweep_clip(options: &FillOptions) -> Option<&ClipPolygon> {
+    if options.anti_aliasing.is_some() || options.curve_triangles {
+        return None;
+    }
+
+    options.clip.as_ref()
+}
+
+// The clip polygon when it is applied after the tessellation.
+fn post_clip(options: &FillOptions) -> Option<&ClipPolygon> {
+    if sweep_clip(options).is_some() {
+        return None;
+    }
+
+    options.clip.as_ref()
+}
+
+// The tessellator needs to visit the end points that don't have edges below them.
+fn end_points(edges: &[OrientedEdge], output: &mut Vec<TessPoint>) {
+    let mut upper_points: Vec<TessPoint> = edges.iter().map(|e| e.upper).collect();
+    upper_points.sort_by(|a, b| compare_positions(*a, *b));
+    for edge in edges {
+        if upper_points.binary_search_by(|p| compare_positions(*p, edge.lower)).is_err() {
+            output.push(edge.lower);
+        }
+    }
+    output.sort_by(|a, b| compare_positions(*a, *b));
+    output.dedup();
 }
 
 pub(crate) struct EventsBuilder {
@@ -1613,6 +2474,8 @@ pub(crate) struct EventsBuilder {
     current: TessPoint,
     nth: u32,
     tolerance: f32,
+    // The source of the edges being added.
+    src: EdgeSource,
 }
 
 impl EventsBuilder {
@@ -1627,6 +2490,7 @@ impl EventsBuilder {
             current: TessPoint::new(fixed(0.0), fixed(0.0)),
             nth: 0,
             tolerance: 0.1,
+            src: NO_SOURCE,
         }
     }
 
@@ -1637,7 +2501,7 @@ impl EventsBuilder {
 
     fn add_edge(&mut self, a: TessPoint, b: TessPoint) {
         if a != b {
-            self.edges.push(OrientedEdge::new(a, b));
+            self.edges.push(OrientedEdge::new(a, b, self.src));
         }
     }
 
@@ -1683,7 +2547,12 @@ impl EventsBuilder {
                 self.vertex(prev, from, to);
             }
 
-            self.add_edge(from, to);
+            // Preserve the original orientation of the edge for the winding number.
+            if needs_swap {
+                self.add_edge(to, from);
+            } else {
+                self.add_edge(from, to);
+            }
 
             prev = from;
             from = to;
@@ -1733,7 +2602,12 @@ impl EventsBuilder {
                 self.vertex(prev, from, to);
             }
 
-            self.add_edge(from, to);
+            // Preserve the original orientation of the edge for the winding number.
+            if needs_swap {
+                self.add_edge(to, from);
+            } else {
+                self.add_edge(from, to);
+            }
 
             prev = from;
             from = to;
@@ -1858,6 +2732,413 @@ impl FlatPathBuilder for EventsBuilder {
     }
 }
 
+// Maximum number of times the curves whose triangles overlap are subdivided before
+// being flattened.
+const MAX_CURVE_SUBDIVISIONS: u32 = 4;
+
+fn is_flat(curve: &QuadraticBezierSegment<f32>) -> bool {
+    let chord = curve.to - curve.from;
+    (curve.ctrl - curve.from).cross(chord).abs() <= 1e-5 * chord.square_length()
+}
+
+// Returns for each event of the path whether it is a curve whose triangle overlaps another
+// curve triangle or an edge of the path.
+fn overlapping_curves(path: &[PathEvent], tolerance: f32) -> Vec<bool> {
+    // The triangles of the curves and the edges of the path (as degenerate triangles),
+    // along with the index of the curve events.
+    let mut shapes = Vec::with_capacity(path.len());
+    let mut first = point(0.0, 0.0);
+    let mut current = first;
+    for (idx, evt) in path.iter().enumerate() {
+        match *evt {
+            PathEvent::MoveTo(to) => {
+                shapes.push(([current, first, first], None));
+                first = to;
+                current = to;
+            }
+            PathEvent::Line(segment) | PathEvent::Close(segment) => {
+                shapes.push(([segment.from, segment.to, segment.to], None));
+                current = segment.to;
+            }
+            PathEvent::Quadratic(segment) => {
+                if is_flat(&segment) {
+                    shapes.push(([segment.from, segment.to, segment.to], None));
+                } else {
+                    shapes.push(([segment.from, segment.ctrl, segment.to], Some(idx)));
+                }
+                current = segment.to;
+            }
+            PathEvent::Cubic(..) => unreachable!(),
+        }
+    }
+    shapes.push(([current, first, first], None));
+
+    // Sweep along the x axis to only test the shapes with overlapping bounding boxes.
+    let bounds = |points: &[Point; 3]| {
+        let min = points[0].x.min(points[1].x).min(points[2].x);
+        let max = points[0].x.max(points[1].x).max(points[2].x);
+        (min, max)
+    };
+    shapes.sort_by(|a, b| bounds(&a.0).0.partial_cmp(&bounds(&b.0).0).unwrap_or(Ordering::Equal));
+
+    // Touching shapes are not considered overlapping.
+    let epsilon = tolerance * 0.01;
+    let mut overlapping = vec![false; path.len()];
+    for (i, &(ref a, curve_a)) in shapes.iter().enumerate() {
+        let max_x = bounds(a).1;
+        for &(ref b, curve_b) in &shapes[i + 1..] {
+            if bounds(b).0 > max_x {
+                break;
+            }
+            if curve_a.is_none() && curve_b.is_none() {
+                continue;
+            }
+            if triangles_overlap(a, b, epsilon) {
+                for curve in curve_a.iter().chain(curve_b.iter()) {
+                    overlapping[*curve] = true;
+                }
+            }
+        }
+    }
+
+    overlapping
+}
+
+// Separating axis test between two (possibly degenerate) triangles.
+fn triangles_overlap(a: &[Point; 3], b: &[Point; 3], epsilon: f32) -> bool {
+    let separated_by_edges_of = |t: &[Point; 3]| {
+        (0..3).any(|i| {
+            let edge = t[(i + 1) % 3] - t[i];
+            if edge.square_length() == 0.0 {
+                return false;
+            }
+            let axis = vector(-edge.y, edge.x).normalize();
+            let project = |t: &[Point; 3]| {
+                let p = [axis.dot(t[0].to_vector()), axis.dot(t[1].to_vector()), axis.dot(t[2].to_vector())];
+                (p[0].min(p[1]).min(p[2]), p[0].max(p[1]).max(p[2]))
+            };
+            let (min_a, max_a) = project(a);
+            let (min_b, max_b) = project(b);
+            max_a <= min_b + epsilon || max_b <= min_a + epsilon
+        })
+    };
+
+    !separated_by_edges_of(a) && !separated_by_edges_of(b)
+}
+
+// Returns for each curve whether its control point is on the inside of the shape, or None
+// if the curve is flat.
+fn curve_sides(
+    curves: &[QuadraticBezierSegment<f32>],
+    edges: &[LineSegment<f32>],
+    fill_rule: FillRule,
+    tolerance: f32,
+) -> Vec<Option<bool>> {
+    // Look at a point next to the middle of each curve, on the side of the control point.
+    // The flattened curve is on the other side so it doesn't get in the way.
+    let mut positions = Vec::with_capacity(curves.len());
+    let mut indices = Vec::with_capacity(curves.len());
+    for (idx, curve) in curves.iter().enumerate() {
+        if is_flat(curve) {
+            continue;
+        }
+        let position = curve.sample(0.5);
+        let tangent = curve.derivative(0.5);
+        let mut normal = vector(-tangent.y, tangent.x).normalize();
+        if normal.dot(curve.ctrl - position) < 0.0 {
+            normal = -normal;
+        }
+        positions.push((position + normal * tolerance * 0.1, None));
+        indices.push(idx);
+    }
+
+    let edges: Vec<(LineSegment<f32>, i32)> = edges.iter().map(|edge| {
+        (*edge, if edge.to.y > edge.from.y { 1 } else { -1 })
+    }).collect();
+
+    let mut sides = vec![None; curves.len()];
+    for (idx, winding) in indices.into_iter().zip(winding_numbers(&edges, &positions)) {
+        sides[idx] = Some(fill_rule.is_in(winding as i16));
+    }
+
+    sides
+}
+
+fn add_curve_triangle(
+    curve: &QuadraticBezierSegment<f32>,
+    sign: f32,
+    output: &mut dyn GeometryBuilder<Vertex>,
+) -> Result<(), GeometryBuilderError> {
+    let mut add_vertex = |position, u, v| {
+        output.add_vertex_with_attributes(
+            Vertex { position, normal: vector(0.0, 0.0) },
+            VertexAttributes {
+                curve: CurveCoordinates { u, v, sign },
+                ..VertexAttributes::NONE
+            },
+        )
+    };
+    let a = add_vertex(curve.from, 0.0, 0.0)?;
+    let b = add_vertex(curve.ctrl, 0.5, 0.0)?;
+    let c = add_vertex(curve.to, 1.0, 1.0)?;
+    output.add_triangle(a, b, c);
+
+    Ok(())
+}
+
+// An edge of the flattened input path along with its custom attributes and source.
+struct EdgeData {
+    from: Point,
+    to: Point,
+    // Offset in EdgeAttributes::values of the attributes at `from`, followed by the
+    // ones at `to`.
+    attributes: usize,
+    source: VertexSource,
+}
+
+// The data that the custom attributes and the source of the vertices are interpolated
+// from.
+//
+// The path is flattened beforehand so that each of its events is a single edge. The
+// edges of the sweep line keep the index of the event they come from through splits and
+// intersections, and each vertex interpolates the data of the edges that touch it.
+struct EdgeAttributes {
+    // Indexed by event, None for the MoveTo events.
+    edges: Vec<Option<EdgeData>>,
+    values: Vec<f32>,
+    num_attributes: usize,
+    sources: bool,
+}
+
+impl EdgeAttributes {
+    fn new() -> Self {
+        EdgeAttributes {
+            edges: Vec::new(),
+            values: Vec::new(),
+            num_attributes: 0,
+            sources: false,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.edges.clear();
+        self.values.clear();
+    }
+
+    // Flattens the path and records the data of its edges, returning the events to
+    // tessellate.
+    //
+    // The curves are flattened the same way as EventsBuilder does, and the sub-paths
+    // are explicitly closed so that the builder doesn't add edges of its own.
+    fn set_path(&mut self, path: PathSlice, tolerance: f32, sources: bool) -> Vec<PathEvent> {
+        self.clear();
+        let n = path.num_attributes();
+        self.num_attributes = n;
+        self.sources = sources;
+
+        let mut events = Vec::new();
+        let mut first = point(0.0, 0.0);
+        let mut first_attributes = vec![0.0; n];
+        let mut from = first;
+        let mut from_attributes = vec![0.0; n];
+        let mut attributes = vec![0.0; n];
+        let mut source = VertexSource { sub_path: 0, sub_path_id: None, segment: 0 };
+        let mut num_sub_paths = 0;
+        let mut open = false;
+        let mut points = Vec::new();
+
+        for (evt, to_attributes) in path.iter_with_attributes() {
+            let segment_length = match evt {
+                PathEvent::MoveTo(to) => {
+                    if open {
+                        self.close(first, &first_attributes, from, &from_attributes, source, &mut events);
+                    }
+                    events.push(PathEvent::MoveTo(to));
+                    self.edges.push(None);
+                    first = to;
+                    first_attributes.copy_from_slice(to_attributes);
+                    from = to;
+                    from_attributes.copy_from_slice(to_attributes);
+                    source = VertexSource {
+                        sub_path: num_sub_paths,
+                        sub_path_id: path.sub_paths().get(num_sub_paths as usize).and_then(|sp| sp.id),
+                        segment: 0,
+                    };
+                    num_sub_paths += 1;
+                    open = false;
+                    continue;
+                }
+                PathEvent::Line(segment) => {
+                    self.add_edge(segment.from, segment.to, &from_attributes, to_attributes, source);
+                    events.push(PathEvent::Line(segment));
+                    from = segment.to;
+                    from_attributes.copy_from_slice(to_attributes);
+                    source.segment += 1;
+                    open = true;
+                    continue;
+                }
+                PathEvent::Close(segment) => {
+                    self.add_edge(segment.from, segment.to, &from_attributes, to_attributes, source);
+                    events.push(PathEvent::Close(segment));
+                    from = segment.to;
+                    from_attributes.copy_from_slice(to_attributes);
+                    source.segment += 1;
+                    open = false;
+                    continue;
+                }
+                PathEvent::Quadratic(mut segment) => {
+                    // Flatten downwards like EventsBuilder::quadratic_segment.
+                    let needs_swap = is_after(to_internal(segment.from), to_internal(segment.to));
+                    if needs_swap {
+                        swap(&mut segment.from, &mut segment.to);
+                    }
+                    points.clear();
+                    segment.for_each_flattened(tolerance, &mut |to| points.push(to));
+                    if needs_swap {
+                        reverse_flattened(segment.from, &mut points);
+                    }
+                    segment.approximate_length(tolerance)
+                }
+                PathEvent::Cubic(mut segment) => {
+                    // Flatten downwards like EventsBuilder::cubic_segment.
+                    let needs_swap = is_after(to_internal(segment.from), to_internal(segment.to));
+                    if needs_swap {
+                        swap(&mut segment.from, &mut segment.to);
+                        swap(&mut segment.ctrl1, &mut segment.ctrl2);
+                    }
+                    points.clear();
+                    segment.for_each_flattened(tolerance, &mut |to| points.push(to));
+                    if needs_swap {
+                        reverse_flattened(segment.from, &mut points);
+                    }
+                    segment.approximate_length(tolerance)
+                }
+            };
+
+            // The attributes are interpolated along the length of the curve.
+            let curve_attributes = from_attributes.clone();
+            let mut traveled = 0.0;
+            for &to in &points {
+                traveled += (to - from).length();
+                let t = if segment_length > 0.0 { f32::min(traveled / segment_length, 1.0) } else { 1.0 };
+                lerp_attributes(&curve_attributes, to_attributes, t, &mut attributes);
+                self.add_edge(from, to, &from_attributes, &attributes, source);
+                events.push(PathEvent::Line(LineSegment { from, to }));
+                from = to;
+                from_attributes.copy_from_slice(&attributes);
+            }
+            from_attributes.copy_from_slice(to_attributes);
+            source.segment += 1;
+            open = true;
+        }
+
+        if open {
+            self.close(first, &first_attributes, from, &from_attributes, source, &mut events);
+        }
+
+        events
+    }
+
+    // Adds the implicit closing edge of a sub-path.
+    fn close(
+        &mut self,
+        first: Point,
+        first_attributes: &[f32],
+        from: Point,
+        from_attributes: &[f32],
+        source: VertexSource,
+        events: &mut Vec<PathEvent>,
+    ) {
+        self.add_edge(from, first, from_attributes, first_attributes, source);
+        events.push(PathEvent::Close(LineSegment { from, to: first }));
+    }
+
+    fn add_edge(
+        &mut self,
+        from: Point,
+        to: Point,
+        from_attributes: &[f32],
+        to_attributes: &[f32],
+        source: VertexSource,
+    ) {
+        let attributes = self.values.len();
+        self.values.extend_from_slice(from_attributes);
+        self.values.extend_from_slice(to_attributes);
+        self.edges.push(Some(EdgeData { from, to, attributes, source }));
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        for edge in self.edges.iter_mut().flatten() {
+            edge.from = transform.transform_point(edge.from);
+            edge.to = transform.transform_point(edge.to);
+        }
+    }
+
+    // Averages the attributes of the edges at a position, and returns the source of the
+    // first one in the path if the sources are requested.
+    fn interpolate(
+        &self,
+        position: Point,
+        edges: &[EdgeSource],
+        output: &mut Vec<f32>,
+    ) -> Option<VertexSource> {
+        let n = self.num_attributes;
+        output.clear();
+        output.resize(n, 0.0);
+
+        let mut count = 0;
+        let mut source = None;
+        // Sorted, so the first edge in the path comes first.
+        for &src in edges {
+            let edge = match self.edges.get(src as usize) {
+                Some(Some(edge)) => edge,
+                _ => { continue; }
+            };
+            let v = edge.to - edge.from;
+            let square_length = v.square_length();
+            let t = if square_length > 0.0 {
+                ((position - edge.from).dot(v) / square_length).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let attributes = &self.values[edge.attributes..edge.attributes + 2 * n];
+            for (i, out) in output.iter_mut().enumerate() {
+                *out += attributes[i] + (attributes[n + i] - attributes[i]) * t;
+            }
+            if source.is_none() {
+                source = Some(edge.source);
+            }
+            count += 1;
+        }
+
+        if count > 1 {
+            for out in output.iter_mut() {
+                *out /= count as f32;
+            }
+        }
+
+        if self.sources { source } else { None }
+    }
+}
+
+// Reverses the points of a curve flattened from its end to `end`, so that they go
+// from its start to its end.
+fn reverse_flattened(end: Point, points: &mut Vec<Point>) {
+    points.pop();
+    points.reverse();
+    points.push(end);
+}
+
+fn lerp_attributes(from: &[f32], to: &[f32], t: f32, output: &mut [f32]) {
+    for ((out, a), b) in output.iter_mut().zip(from).zip(to) {
+        *out = a + (b - a) * t;
+    }
+}
+
 /// Helper class that generates a triangulation from a sequence of vertices describing a monotone
 /// polygon (used internally by the `FillTessellator`).
 struct MonotoneTessellator {
@@ -2022,3 +3303,49 @@ fn test_monotone_tess() {
     }
     println!(" ------------ ");
 }
+
+#[cfg(feature = "serialization")]
+#[test]
+fn test_deserialize_unsorted_events() {
+    use crate::path::Path;
+    use crate::geometry_builder::{simple_builder, VertexBuffers};
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 2.0));
+    builder.line_to(point(5.0, 10.0));
+    builder.line_to(point(3.0, 4.0));
+    builder.close();
+    let path = builder.build();
+
+    let events = FillEvents::from_path(0.05, path.iter());
+    let mut serialized = SerializedFillEvents::from(events.clone());
+    // Reverse the order of the edges and flip some of them upside down.
+    serialized.edges.reverse();
+    for edge in serialized.edges.iter_mut().step_by(2) {
+        *edge = (edge.2, edge.3, edge.0, edge.1, -edge.4);
+    }
+    serialized.vertices.clear();
+
+    let deserialized = FillEvents::from(serialized);
+    for pair in deserialized.edges.windows(2) {
+        assert!(!is_after(pair[0].upper, pair[1].upper));
+    }
+    for edge in &deserialized.edges {
+        assert!(is_after(edge.lower, edge.upper));
+    }
+    assert_eq!(deserialized.vertices, events.vertices);
+
+    let tessellate = |events: &FillEvents| {
+        let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+        FillTessellator::new().tessellate_events(
+            events,
+            &FillOptions::default(),
+            &mut simple_builder(&mut buffers),
+        ).unwrap();
+        buffers
+    };
+    let (expected, result) = (tessellate(&events), tessellate(&deserialized));
+    assert_eq!(result.vertices, expected.vertices);
+    assert_eq!(result.indices, expected.indices);
+}
@@ -3,14 +3,22 @@ use crate::geom::math::*;
 use crate::geom::{QuadraticBezierSegment, CubicBezierSegment, LineSegment, Arc};
 use crate::geom::utils::{normalized_tangent, directed_angle};
 use crate::geom::euclid::Trig;
-use crate::geometry_builder::{VertexId, GeometryBuilder, GeometryBuilderError};
+use crate::geometry_builder::{VertexId, GeometryBuilder, GeometryBuilderError, add_recorded_vertex};
+use crate::geometry_builder::{StripGeometryBuilder, LineGeometryBuilder};
+use crate::fringe::with_fringe;
+use crate::clip::with_clip;
+use crate::strip::with_strips;
 use crate::basic_shapes::circle_flattening_step;
 use crate::path::builder::{Build, FlatPathBuilder, PathBuilder};
-use crate::path::PathEvent;
+use crate::path::{PathEvent, PathSlice, FlattenedEvent};
+use crate::path::iterator::PathIterator;
 use crate::StrokeVertex as Vertex;
-use crate::{Side, Order, LineCap, LineJoin, StrokeOptions, TessellationError, TessellationResult};
+use crate::{HairlineVertex, HairlineExtrusion};
+use crate::{Side, Order, LineCap, LineJoin, StrokeOptions, TessellationError, TessellationResult, VertexAttributes, VertexSource};
+use lyon_algorithms::walk::{Dasher, DashEvent};
 
 use std::f32::consts::PI;
+use std::mem::replace;
 const EPSILON: f32 = 1e-4;
 
 /// A Context object that can tessellate stroke operations for complex paths.
@@ -24,7 +32,9 @@ const EPSILON: f32 = 1e-4;
 /// be the desired behavior. This needs to be kept in mind when rendering transparent
 /// SVG strokes since the spec mandates that each point along a semi-transparent path
 /// is shaded once no matter how many times the path overlaps with itself at this
-/// location.
+/// location. In this case the outline of the stroke can be generated with a
+/// [`StrokeOutlineBuilder`](struct.StrokeOutlineBuilder.html) and filled with the
+/// `NonZero` fill rule instead.
 ///
 /// `StrokeTessellator` exposes a similar interface to its
 /// [fill equivalent](struct.FillTessellator.html).
@@ -99,12 +109,276 @@ impl StrokeTessellator {
     where
         Input: IntoIterator<Item = PathEvent>,
     {
+        let half_width = fringe_half_width(options);
+        with_clip(options.clip.as_ref(), half_width, builder, |builder| {
+            with_fringe(options.anti_aliasing, half_width, builder, |builder| {
+                builder.begin_geometry();
+                {
+                    let mut stroker = StrokeBuilder::new(options, builder);
+
+                    for evt in input {
+                        stroker.path_event(evt);
+                        if let Some(error) = stroker.error {
+                            stroker.output.abort_geometry();
+                            return Err(error)
+                        }
+                    }
+
+                    stroker.build()?;
+                }
+                Ok(builder.end_geometry())
+            })
+        })
+    }
+
+    /// Compute the tessellation of a path, taking its custom attributes into account.
+    ///
+    /// The attributes are interpolated along the path (including along curves and
+    /// within dashes) and passed to `GeometryBuilder::add_vertex_with_attributes`.
+    ///
+    /// If `options.variable_line_width` is set, the line width at each endpoint is
+    /// `options.line_width` multiplied by the corresponding attribute.
+    pub fn tessellate_path_with_attributes(
+        &mut self,
+        path: PathSlice,
+        options: &StrokeOptions,
+        builder: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult {
+        let half_width = fringe_half_width(options);
+        with_clip(options.clip.as_ref(), half_width, builder, |builder| {
+            with_fringe(options.anti_aliasing, half_width, builder, |builder| {
+                Self::tessellate_attributes(path, options, false, builder)
+            })
+        })
+    }
+
+    /// Compute the tessellation of a path, reporting the location in the path that each
+    /// vertex was generated from.
+    ///
+    /// The source of each vertex (see `VertexSource`) is passed to
+    /// `GeometryBuilder::add_vertex_with_attributes` along with the custom attributes of the
+    /// path, interpolated as in `tessellate_path_with_attributes`. The vertices of a join
+    /// report the edge that starts at the join, the start cap reports the first edge of the
+    /// sub-path and the end cap its last edge.
+    pub fn tessellate_path_with_sources(
+        &mut self,
+        path: PathSlice,
+        options: &StrokeOptions,
+        builder: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult {
+        let half_width = fringe_half_width(options);
+        with_clip(options.clip.as_ref(), half_width, builder, |builder| {
+            with_fringe(options.anti_aliasing, half_width, builder, |builder| {
+                Self::tessellate_attributes(path, options, true, builder)
+            })
+        })
+    }
+
+    /// Compute the tessellation from a path iterator, as triangle strips.
+    ///
+    /// This produces the same triangles as `tessellate_path`, with the same winding order,
+    /// the consecutive triangles that share an edge being sent to the output as a single
+    /// strip. The strips can contain degenerate triangles, which are needed to keep the
+    /// winding order of the next triangle.
+    pub fn tessellate_path_strips<Input>(
+        &mut self,
+        input: Input,
+        options: &StrokeOptions,
+        output: &mut dyn StripGeometryBuilder<Vertex>,
+    ) -> TessellationResult
+    where
+        Input: IntoIterator<Item = PathEvent>,
+    {
+        with_strips(output, |output| self.tessellate_path(input, options, output))
+    }
+
+    /// Compute a tessellation of a path made of line segments instead of triangles.
+    ///
+    /// The path is flattened with `options.tolerance` and each segment is sent to
+    /// `LineGeometryBuilder::add_line`, to be rendered with a line primitive such as a line
+    /// list. For strokes of a constant width on the screen, see `tessellate_path_hairline`. The vertices have a nil normal. The line width,
+    /// caps, joins and dashes, the anti-aliasing fringe and the clip polygon are ignored.
+    pub fn tessellate_path_lines<Input>(
+        &mut self,
+        input: Input,
+        options: &StrokeOptions,
+        output: &mut dyn LineGeometryBuilder<Vertex>,
+    ) -> TessellationResult
+    where
+        Input: IntoIterator<Item = PathEvent>,
+    {
+        output.begin_geometry();
+        if let Err(e) = Self::tessellate_lines(input, options, output) {
+            output.abort_geometry();
+            return Err(e.into());
+        }
+
+        Ok(output.end_geometry())
+    }
+
+    fn tessellate_lines<Input>(
+        input: Input,
+        options: &StrokeOptions,
+        output: &mut dyn LineGeometryBuilder<Vertex>,
+    ) -> Result<(), GeometryBuilderError>
+    where
+        Input: IntoIterator<Item = PathEvent>,
+    {
+        let vertex = |position: Point, advancement: f32| Vertex {
+            position,
+            normal: vector(0.0, 0.0),
+            advancement,
+            side: Side::Left,
+        };
+
+        let mut previous = None;
+        let mut advancement = 0.0;
+        for evt in input.into_iter().flattened(options.tolerance) {
+            match evt {
+                FlattenedEvent::MoveTo(to) => {
+                    advancement = 0.0;
+                    previous = Some(output.add_vertex(vertex(to, advancement))?);
+                }
+                FlattenedEvent::Line(segment) | FlattenedEvent::Close(segment) => {
+                    if segment.from == segment.to {
+                        continue;
+                    }
+                    advancement += segment.length();
+                    let id = output.add_vertex(vertex(segment.to, advancement))?;
+                    if let Some(previous) = previous {
+                        output.add_line(previous, id);
+                    }
+                    previous = Some(id);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compute a hairline tessellation of a path, for strokes that have a constant width
+    /// on the screen.
+    ///
+    /// The path is flattened with `options.tolerance` and the vertices are produced on the
+    /// path, with the tangents of the path around them so that a vertex shader can extrude
+    /// them after transforming them (see `HairlineVertex::extrude`). The segments don't
+    /// share their vertices, and the joins and the caps are produced according to
+    /// `options.line_join`, `options.start_cap` and `options.end_cap`. Since the joins are
+    /// extruded after the transform, the miter limit is provided to `extrude`. The line
+    /// width, dashes, the anti-aliasing fringe and the clip polygon are ignored.
+    pub fn tessellate_path_hairline<Input>(
+        &mut self,
+        input: Input,
+        options: &StrokeOptions,
+        output: &mut dyn GeometryBuilder<HairlineVertex>,
+    ) -> TessellationResult
+    where
+        Input: IntoIterator<Item = PathEvent>,
+    {
+        output.begin_geometry();
+
+        let mut points = Vec::new();
+        let mut result = Ok(());
+        for evt in input.into_iter().flattened(options.tolerance) {
+            match evt {
+                FlattenedEvent::MoveTo(to) => {
+                    result = tessellate_hairline_sub_path(&points, false, options, output);
+                    points.clear();
+                    points.push(to);
+                }
+                FlattenedEvent::Line(segment) => {
+                    if points.last() != Some(&segment.to) {
+                        points.push(segment.to);
+                    }
+                }
+                FlattenedEvent::Close(..) => {
+                    if points.len() > 1 && points.first() == points.last() {
+                        points.pop();
+                    }
+                    result = tessellate_hairline_sub_path(&points, true, options, output);
+                    points.clear();
+                }
+            }
+            if result.is_err() {
+                break;
+            }
+        }
+        if result.is_ok() {
+            result = tessellate_hairline_sub_path(&points, false, options, output);
+        }
+
+        if let Err(e) = result {
+            output.abort_geometry();
+            return Err(e.into());
+        }
+
+        Ok(output.end_geometry())
+    }
+
+    fn tessellate_attributes(
+        path: PathSlice,
+        options: &StrokeOptions,
+        sources: bool,
+        builder: &mut dyn GeometryBuilder<Vertex>,
+    ) -> TessellationResult {
+        if let Some(index) = options.variable_line_width {
+            if index >= path.num_attributes() {
+                return Err(TessellationError::UnsupportedParamater);
+            }
+        }
+
         builder.begin_geometry();
         {
-            let mut stroker = StrokeBuilder::new(options, builder);
+            let mut stroker = StrokeBuilder::with_attributes(options, path.num_attributes(), builder);
+            let mut source = VertexSource { sub_path: 0, sub_path_id: None, segment: 0 };
+            let mut num_sub_paths = 0;
+
+            for (evt, attributes) in path.iter_with_attributes() {
+                let width = match options.variable_line_width {
+                    Some(index) => options.line_width * attributes[index],
+                    None => options.line_width,
+                };
+                stroker.attributes.set(slot::TO, attributes);
+
+                // The vertices of each edge are generated when the edge is added, except
+                // for the caps, which are generated when the next sub-path begins.
+                let is_move_to = matches!(evt, PathEvent::MoveTo(..));
+                if is_move_to {
+                    source = VertexSource {
+                        sub_path: num_sub_paths,
+                        sub_path_id: path.sub_paths().get(num_sub_paths as usize).and_then(|sp| sp.id),
+                        segment: 0,
+                    };
+                    num_sub_paths += 1;
+                } else if sources {
+                    stroker.source = Some(source);
+                }
+
+                match evt {
+                    PathEvent::MoveTo(to) => {
+                        stroker.begin(to, width);
+                    }
+                    PathEvent::Line(segment) => {
+                        stroker.stroke_to(segment.to, width, true);
+                    }
+                    PathEvent::Quadratic(segment) => {
+                        stroker.quadratic_bezier_to_with_width(segment.ctrl, segment.to, width);
+                    }
+                    PathEvent::Cubic(segment) => {
+                        stroker.cubic_bezier_to_with_width(segment.ctrl1, segment.ctrl2, segment.to, width);
+                    }
+                    PathEvent::Close(..) => {
+                        stroker.close();
+                    }
+                }
+
+                if !is_move_to {
+                    source.segment += 1;
+                } else if sources {
+                    // Now that the caps of the previous sub-path are generated.
+                    stroker.source = Some(source);
+                }
 
-            for evt in input {
-                stroker.path_event(evt);
                 if let Some(error) = stroker.error {
                     stroker.output.abort_geometry();
                     return Err(error)
@@ -117,15 +391,37 @@ impl StrokeTessellator {
     }
 }
 
+// When the line width is not applied, the fringe and the clipping are expressed in the
+// vertex normals.
+fn fringe_half_width(options: &StrokeOptions) -> Option<f32> {
+    if options.apply_line_width {
+        None
+    } else {
+        Some(options.line_width * 0.5)
+    }
+}
+
 macro_rules! add_vertex {
     ($builder: expr, $vertex: expr) => {{
         let mut v = $vertex;
 
         if $builder.options.apply_line_width {
-            v.position += v.normal * $builder.options.line_width / 2.0;
+            v.position += v.normal * $builder.line_width / 2.0;
+        } else if $builder.line_width != $builder.options.line_width && $builder.options.line_width != 0.0 {
+            v.normal *= $builder.line_width / $builder.options.line_width;
         }
 
-        match $builder.output.add_vertex(v) {
+        let result = add_recorded_vertex(
+            &mut *$builder.output,
+            v,
+            VertexAttributes {
+                custom: $builder.attributes.get(slot::LINE),
+                source: $builder.source,
+                ..VertexAttributes::NONE
+            },
+        );
+
+        match result {
             Ok(v) => v,
             Err(e) => {
                 $builder.builder_error(e);
@@ -150,8 +446,26 @@ pub struct StrokeBuilder<'l> {
     nth: u32,
     length: f32,
     sub_path_start_length: f32,
+    // Line width at the vertices being generated.
+    line_width: f32,
+    // Line widths at `first`, `second` and `current`.
+    first_width: f32,
+    second_width: f32,
+    current_width: f32,
+    // Line widths at the current position and at the start of the sub-path in the
+    // input, which differ from the ones above when the stroke is dashed.
+    input_width: f32,
+    input_first_width: f32,
+    // Custom attributes, tracked alongside the line widths.
+    attributes: Attributes,
+    // Location in the input of the vertices being generated, if reported.
+    source: Option<VertexSource>,
+    // Location in the input of the first edge of the sub-path, for the start cap and the
+    // join that closes the sub-path.
+    first_source: Option<VertexSource>,
     options: StrokeOptions,
     previous_command_was_move: bool,
+    dasher: Option<Dasher>,
     error: Option<TessellationError>,
     output: &'l mut dyn GeometryBuilder<Vertex>,
 }
@@ -160,6 +474,11 @@ impl<'l> Build for StrokeBuilder<'l> {
     type PathType = Result<(), GeometryBuilderError>;
 
     fn build(mut self) -> Result<(), GeometryBuilderError> {
+        let position = self.current_position();
+        let width = self.input_width;
+        self.attributes.copy(slot::INPUT, slot::FROM);
+        self.attributes.copy(slot::INPUT, slot::TO);
+        self.dash(true, (position, width), (position, width), |dasher, output| dasher.end(output));
         self.finish();
         Ok(())
     }
@@ -173,28 +492,36 @@ impl<'l> Build for StrokeBuilder<'l> {
         self.nth = 0;
         self.length = 0.0;
         self.sub_path_start_length = 0.0;
+        self.reset_widths();
         self.previous_command_was_move = false;
+        self.dasher = dasher(&self.options);
         Ok(())
     }
 }
 
 impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
     fn move_to(&mut self, to: Point) {
-        self.finish();
-
-        self.first = to;
-        self.current = to;
-        self.nth = 0;
-        self.sub_path_start_length = self.length;
-        self.previous_command_was_move = true;
+        let width = self.options.line_width;
+        self.begin(to, width);
     }
 
     fn line_to(&mut self, to: Point) {
-        self.previous_command_was_move = false;
-        self.edge_to(to, true);
+        let width = self.options.line_width;
+        self.stroke_to(to, width, true);
     }
 
     fn close(&mut self) {
+        let first_width = self.input_first_width;
+        if let Some(first) = self.dasher.as_ref().map(|dasher| dasher.first_position()) {
+            let from = (self.current_position(), self.input_width);
+            self.attributes.copy(slot::INPUT, slot::FROM);
+            self.attributes.copy(slot::INPUT_FIRST, slot::TO);
+            self.dash(true, from, (first, first_width), |dasher, output| dasher.close(output));
+            self.input_width = first_width;
+            self.attributes.copy(slot::INPUT_FIRST, slot::INPUT);
+            return;
+        }
+
         // If we close almost at the first edge, then we have to
         // skip connecting the last and first edges otherwise the
         // normal will be plagued with floating point precision
@@ -202,13 +529,19 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
         let threshold = 0.001;
         if (self.first - self.current).square_length() > threshold {
             let first = self.first;
-            self.edge_to(first, true);
+            self.attributes.copy(slot::INPUT_FIRST, slot::EDGE);
+            self.edge_to(first, first_width, true);
         }
 
         if self.nth > 1 {
+            let source = replace(&mut self.source, self.first_source);
             let second = self.second;
-            self.edge_to(second, true);
+            let second_width = self.second_width;
+            self.attributes.copy(slot::SECOND, slot::EDGE);
+            self.edge_to(second, second_width, true);
 
+            self.line_width = self.first_width;
+            self.attributes.copy(slot::FIRST, slot::LINE);
             let first_left_id = add_vertex!(
                 self,
                 Vertex {
@@ -230,48 +563,35 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
 
             self.output.add_triangle(first_right_id, first_left_id, self.second_right_id);
             self.output.add_triangle(first_left_id, self.second_left_id, self.second_right_id);
+            self.source = source;
         }
         self.nth = 0;
         self.current = self.first;
+        self.current_width = self.first_width;
+        self.input_width = first_width;
+        self.attributes.copy(slot::FIRST, slot::CURRENT);
+        self.attributes.copy(slot::INPUT_FIRST, slot::INPUT);
         self.sub_path_start_length = self.length;
         self.previous_command_was_move = false;
     }
 
-    fn current_position(&self) -> Point { self.current }
+    fn current_position(&self) -> Point {
+        match self.dasher {
+            Some(ref dasher) => dasher.current_position(),
+            None => self.current,
+        }
+    }
 }
 
 impl<'l> PathBuilder for StrokeBuilder<'l> {
     fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
-        self.previous_command_was_move = false;
-        let mut first = true;
-        QuadraticBezierSegment {
-            from: self.current,
-            ctrl,
-            to,
-        }.for_each_flattened(
-            self.options.tolerance,
-            &mut |point| {
-                self.edge_to(point, first);
-                first = false;
-            }
-        );
+        let width = self.options.line_width;
+        self.quadratic_bezier_to_with_width(ctrl, to, width);
     }
 
     fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
-        self.previous_command_was_move = false;
-        let mut first = true;
-        CubicBezierSegment {
-            from: self.current,
-            ctrl1,
-            ctrl2,
-            to,
-        }.for_each_flattened(
-            self.options.tolerance,
-            &mut |point| {
-                self.edge_to(point, first);
-                first = false;
-            }
-        );
+        let width = self.options.line_width;
+        self.cubic_bezier_to_with_width(ctrl1, ctrl2, to, width);
     }
 
     fn arc(
@@ -281,20 +601,21 @@ impl<'l> PathBuilder for StrokeBuilder<'l> {
         sweep_angle: Angle,
         x_rotation: Angle
     ) {
-        let start_angle = (self.current - center).angle_from_x_axis() - x_rotation;
-        let mut first = true;
-        Arc {
+        let start_angle = (self.current_position() - center).angle_from_x_axis() - x_rotation;
+        let arc = Arc {
             center,
             radii,
             start_angle,
             sweep_angle,
             x_rotation,
-        }.for_each_flattened(
-            self.options.tolerance,
-            &mut |point| {
-                self.edge_to(point, first);
-                first = false;
-            }
+        };
+        let width = self.options.line_width;
+        let tolerance = self.options.tolerance;
+        self.curve_to(
+            arc.to(),
+            width,
+            || arc.approximate_length(tolerance),
+            |cb| arc.for_each_flattened(tolerance, &mut |point| cb(point)),
         );
     }
 }
@@ -303,6 +624,14 @@ impl<'l> StrokeBuilder<'l> {
     pub fn new(
         options: &StrokeOptions,
         builder: &'l mut dyn GeometryBuilder<Vertex>,
+    ) -> Self {
+        StrokeBuilder::with_attributes(options, 0, builder)
+    }
+
+    fn with_attributes(
+        options: &StrokeOptions,
+        num_attributes: usize,
+        builder: &'l mut dyn GeometryBuilder<Vertex>,
     ) -> Self {
         let zero = Point::new(0.0, 0.0);
         StrokeBuilder {
@@ -319,14 +648,207 @@ impl<'l> StrokeBuilder<'l> {
             nth: 0,
             length: 0.0,
             sub_path_start_length: 0.0,
+            line_width: options.line_width,
+            first_width: options.line_width,
+            second_width: options.line_width,
+            current_width: options.line_width,
+            input_width: options.line_width,
+            input_first_width: options.line_width,
+            attributes: Attributes::new(num_attributes),
+            source: None,
+            first_source: None,
             options: *options,
             previous_command_was_move: false,
+            dasher: dasher(options),
             error: None,
             output: builder,
         }
     }
 
-    pub fn set_options(&mut self, options: &StrokeOptions) { self.options = *options; }
+    pub fn set_options(&mut self, options: &StrokeOptions) {
+        self.options = *options;
+        self.dasher = dasher(options);
+        self.reset_widths();
+    }
+
+    fn reset_widths(&mut self) {
+        let width = self.options.line_width;
+        self.line_width = width;
+        self.first_width = width;
+        self.second_width = width;
+        self.current_width = width;
+        self.input_width = width;
+        self.input_first_width = width;
+        self.attributes.clear();
+    }
+
+    /// Starts a sub-path with the provided line width.
+    fn begin(&mut self, to: Point, width: f32) {
+        self.input_width = width;
+        self.input_first_width = width;
+        self.attributes.copy(slot::TO, slot::INPUT);
+        self.attributes.copy(slot::TO, slot::INPUT_FIRST);
+        self.attributes.copy(slot::TO, slot::FROM);
+
+        if self.dash(true, (to, width), (to, width), |dasher, output| dasher.move_to(to, output)) {
+            return;
+        }
+
+        self.finish();
+
+        self.first = to;
+        self.current = to;
+        self.first_width = width;
+        self.current_width = width;
+        self.attributes.copy(slot::TO, slot::FIRST);
+        self.attributes.copy(slot::TO, slot::CURRENT);
+        self.nth = 0;
+        self.sub_path_start_length = self.length;
+        self.previous_command_was_move = true;
+    }
+
+    fn quadratic_bezier_to_with_width(&mut self, ctrl: Point, to: Point, width: f32) {
+        let curve = QuadraticBezierSegment {
+            from: self.current_position(),
+            ctrl,
+            to,
+        };
+        let tolerance = self.options.tolerance;
+        self.curve_to(
+            to,
+            width,
+            || curve.approximate_length(tolerance),
+            |cb| curve.for_each_flattened(tolerance, &mut |point| cb(point)),
+        );
+    }
+
+    fn cubic_bezier_to_with_width(&mut self, ctrl1: Point, ctrl2: Point, to: Point, width: f32) {
+        let curve = CubicBezierSegment {
+            from: self.current_position(),
+            ctrl1,
+            ctrl2,
+            to,
+        };
+        let tolerance = self.options.tolerance;
+        self.curve_to(
+            to,
+            width,
+            || curve.approximate_length(tolerance),
+            |cb| curve.for_each_flattened(tolerance, &mut |point| cb(point)),
+        );
+    }
+
+    /// Flattens a curve, interpolating the line width and the attributes along the
+    /// curve's length.
+    fn curve_to(
+        &mut self,
+        to: Point,
+        to_width: f32,
+        length: impl FnOnce() -> f32,
+        flatten: impl FnOnce(&mut dyn FnMut(Point)),
+    ) {
+        let from_width = self.input_width;
+        self.attributes.copy(slot::INPUT, slot::CURVE_FROM);
+        self.attributes.copy(slot::TO, slot::CURVE_TO);
+        let interpolate = from_width != to_width || !self.attributes.is_empty();
+        let length = if interpolate { length() } else { 0.0 };
+
+        let mut prev = self.current_position();
+        let mut traveled = 0.0;
+        let mut first = true;
+        flatten(&mut |point| {
+            traveled += (point - prev).length();
+            prev = point;
+            let t = if point == to || length <= 0.0 { 1.0 } else { f32::min(traveled / length, 1.0) };
+            self.attributes.lerp(slot::CURVE_FROM, slot::CURVE_TO, t, slot::TO);
+            self.stroke_to(point, from_width + (to_width - from_width) * t, first);
+            first = false;
+        });
+    }
+
+    /// Adds an edge, splitting it into dashes if needed.
+    ///
+    /// The attributes at `to` are read from the `TO` slot.
+    fn stroke_to(&mut self, to: Point, width: f32, with_join: bool) {
+        let from = (self.current_position(), self.input_width);
+        self.input_width = width;
+        self.attributes.copy(slot::INPUT, slot::FROM);
+        self.attributes.copy(slot::TO, slot::INPUT);
+
+        if !self.dash(with_join, from, (to, width), |dasher, output| dasher.line_to(to, output)) {
+            self.previous_command_was_move = false;
+            self.attributes.copy(slot::TO, slot::EDGE);
+            self.edge_to(to, width, with_join);
+        }
+    }
+
+    /// Feeds the dasher if the stroke is dashed, and returns false otherwise.
+    ///
+    /// The line width of the dashes is interpolated between the provided positions
+    /// and widths, and the attributes between the `FROM` and `TO` slots.
+    fn dash(
+        &mut self,
+        with_join: bool,
+        from: (Point, f32),
+        to: (Point, f32),
+        f: impl FnOnce(&mut Dasher, &mut dyn FnMut(DashEvent)),
+    ) -> bool {
+        let mut dasher = match self.dasher.take() {
+            Some(dasher) => dasher,
+            None => { return false; }
+        };
+
+        let length = (to.0 - from.0).length();
+        let interpolate = |position: Point| {
+            if length == 0.0 {
+                return (to.1, 1.0);
+            }
+            let t = f32::min((position - from.0).length() / length, 1.0);
+            (from.1 + (to.1 - from.1) * t, t)
+        };
+
+        f(&mut dasher, &mut |evt| self.dash_event(evt, with_join, &interpolate));
+        self.dasher = Some(dasher);
+
+        true
+    }
+
+    /// Each dash is stroked as a separate sub-path, while the advancement keeps
+    /// counting the length of the whole path.
+    ///
+    /// `interpolate` returns the line width at a position along with the corresponding
+    /// interpolation factor.
+    fn dash_event(&mut self, evt: DashEvent, with_join: bool, interpolate: &dyn Fn(Point) -> (f32, f32)) {
+        match evt {
+            DashEvent::Begin { at, advancement } => {
+                self.finish();
+                let (width, t) = interpolate(at);
+                self.first = at;
+                self.current = at;
+                self.first_width = width;
+                self.current_width = width;
+                self.attributes.lerp(slot::FROM, slot::TO, t, slot::FIRST);
+                self.attributes.copy(slot::FIRST, slot::CURRENT);
+                self.nth = 0;
+                self.length = advancement;
+                self.sub_path_start_length = advancement;
+                self.previous_command_was_move = true;
+            }
+            DashEvent::LineTo(to) => {
+                if to != self.current {
+                    self.previous_command_was_move = false;
+                }
+                let (width, t) = interpolate(to);
+                self.attributes.lerp(slot::FROM, slot::TO, t, slot::EDGE);
+                self.edge_to(to, width, with_join);
+            }
+            DashEvent::End => {
+                self.finish();
+                self.nth = 0;
+                self.previous_command_was_move = false;
+            }
+        }
+    }
 
     #[cold]
     fn builder_error(&mut self, e: GeometryBuilderError) {
@@ -341,7 +863,7 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: self.current,
                 normal: vector(1.0, 1.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Right,
             }
         );
@@ -350,7 +872,7 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: self.current,
                 normal: vector(1.0, -1.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Left,
             }
         );
@@ -359,7 +881,7 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: self.current,
                 normal: vector(-1.0, -1.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Left,
             }
         );
@@ -368,7 +890,7 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: self.current,
                 normal: vector(-1.0, 1.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Right,
             }
         );
@@ -383,7 +905,7 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: center,
                 normal: vector(-1.0, 0.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Left,
             }
         );
@@ -392,7 +914,7 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: center,
                 normal: vector(1.0, 0.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Right,
             }
         );
@@ -401,6 +923,8 @@ impl<'l> StrokeBuilder<'l> {
     }
 
     fn finish(&mut self) {
+        self.line_width = self.current_width;
+        self.attributes.copy(slot::CURRENT, slot::LINE);
         if self.nth == 0 && self.previous_command_was_move {
             match self.options.start_cap {
                 LineCap::Square => {
@@ -426,7 +950,9 @@ impl<'l> StrokeBuilder<'l> {
                 self.current += d.normalize();
             }
             let p = self.current + d;
-            self.edge_to(p, true);
+            let width = self.current_width;
+            self.attributes.copy(slot::CURRENT, slot::EDGE);
+            self.edge_to(p, width, true);
             // Restore the real current position.
             self.current = current;
 
@@ -438,6 +964,7 @@ impl<'l> StrokeBuilder<'l> {
         }
         // first edge
         if self.nth > 1 {
+            let source = replace(&mut self.source, self.first_source);
             let mut first = self.first;
             let d = first - self.second;
 
@@ -448,6 +975,9 @@ impl<'l> StrokeBuilder<'l> {
             let n2 = normalized_tangent(d);
             let n1 = -n2;
 
+            self.line_width = self.first_width;
+            self.attributes.copy(slot::FIRST, slot::LINE);
+
             let first_left_id = add_vertex!(
                 self,
                 Vertex {
@@ -473,10 +1003,12 @@ impl<'l> StrokeBuilder<'l> {
 
             self.output.add_triangle(first_right_id, first_left_id, self.second_right_id);
             self.output.add_triangle(first_left_id, self.second_left_id, self.second_right_id);
+            self.source = source;
         }
     }
 
-    fn edge_to(&mut self, to: Point, with_join: bool) {
+    /// The attributes at `to` are read from the `EDGE` slot.
+    fn edge_to(&mut self, to: Point, width: f32, with_join: bool) {
         if to == self.current {
             return;
         }
@@ -486,10 +1018,17 @@ impl<'l> StrokeBuilder<'l> {
             // vertices (and thus the current join) yet.
             self.previous = self.first;
             self.current = to;
+            self.current_width = width;
+            self.attributes.copy(slot::EDGE, slot::CURRENT);
+            self.first_source = self.source;
             self.nth += 1;
             return;
         }
 
+        // The join is generated at the current position.
+        self.line_width = self.current_width;
+        self.attributes.copy(slot::CURRENT, slot::LINE);
+
         let previous_edge = self.current - self.previous;
         let next_edge = to - self.current;
         let join_type = if with_join { self.options.line_join } else { LineJoin::Miter };
@@ -528,10 +1067,14 @@ impl<'l> StrokeBuilder<'l> {
 
         if self.nth == 1 {
             self.second = self.previous;
+            self.second_width = self.current_width;
+            self.attributes.copy(slot::CURRENT, slot::SECOND);
             self.second_left_id = start_left_id;
             self.second_right_id = start_right_id;
         }
 
+        self.current_width = width;
+        self.attributes.copy(slot::EDGE, slot::CURRENT);
         self.nth += 1;
     }
 
@@ -543,7 +1086,7 @@ impl<'l> StrokeBuilder<'l> {
         right: VertexId,
         is_start: bool,
     ) {
-        let radius = self.options.line_width.abs();
+        let radius = self.line_width.abs();
         if radius < 1e-4 {
             return;
         }
@@ -578,10 +1121,12 @@ impl<'l> StrokeBuilder<'l> {
         };
         self.output.add_triangle(v1, v2, v3);
 
-        let apply_width = if self.options.apply_line_width {
-            self.options.line_width * 0.5
+        let (apply_width, normal_scale) = if self.options.apply_line_width {
+            (self.line_width * 0.5, 1.0)
+        } else if self.options.line_width != 0.0 {
+            (0.0, self.line_width / self.options.line_width)
         } else {
-            0.0
+            (0.0, 1.0)
         };
 
         if let Err(e) = tess_round_cap(
@@ -593,7 +1138,13 @@ impl<'l> StrokeBuilder<'l> {
             advancement,
             Side::Left,
             apply_width,
+            normal_scale,
             !is_start,
+            VertexAttributes {
+                custom: self.attributes.get(slot::LINE),
+                source: self.source,
+                ..VertexAttributes::NONE
+            },
             self.output
         ) {
             self.builder_error(e);
@@ -607,7 +1158,13 @@ impl<'l> StrokeBuilder<'l> {
             advancement,
             Side::Right,
             apply_width,
+            normal_scale,
             !is_start,
+            VertexAttributes {
+                custom: self.attributes.get(slot::LINE),
+                source: self.source,
+                ..VertexAttributes::NONE
+            },
             self.output
         ) {
             self.builder_error(e);
@@ -625,8 +1182,8 @@ impl<'l> StrokeBuilder<'l> {
         // We must watch out for special cases where the previous or next edge is small relative
         // to the line width inducing an overlap of the stroke of both edges.
 
-        let d_next = -self.options.line_width / 2.0 * front_normal.dot(next_tangent) - next_length;
-        let d_prev = -self.options.line_width / 2.0 * front_normal.dot(-prev_tangent) - prev_length;
+        let d_next = -self.line_width / 2.0 * front_normal.dot(next_tangent) - next_length;
+        let d_prev = -self.line_width / 2.0 * front_normal.dot(-prev_tangent) - prev_length;
 
         let (d, t2, order) =
             if d_prev > d_next { (d_prev, next_tangent, Order::Before) }
@@ -874,7 +1431,7 @@ impl<'l> StrokeBuilder<'l> {
     ) -> (VertexId, VertexId) {
         let join_angle = get_join_angle(prev_tangent, next_tangent);
 
-        let max_radius_segment_angle = compute_max_radius_segment_angle(self.options.line_width / 2.0, self.options.tolerance);
+        let max_radius_segment_angle = compute_max_radius_segment_angle(self.line_width / 2.0, self.options.tolerance);
         let num_segments = (join_angle.abs() as f32 / max_radius_segment_angle).ceil() as u32;
         debug_assert!(num_segments > 0);
         // Calculate angle of each step
@@ -988,7 +1545,7 @@ impl<'l> StrokeBuilder<'l> {
     }
 
     fn get_clip_intersections(&self, prev_normal: Vector, next_normal: Vector, normal: Vector) -> (Vector, Vector) {
-        let miter_length = self.options.miter_limit * self.options.line_width;
+        let miter_length = self.options.miter_limit * self.line_width;
         let normal_limit = normal.normalize() * miter_length;
 
         let normal_limit_perp = LineSegment{
@@ -1010,6 +1567,186 @@ impl<'l> StrokeBuilder<'l> {
     }
 }
 
+// Maximum angle between two vertices of the arc of a hairline round join.
+const MAX_HAIRLINE_JOIN_ANGLE: f32 = std::f32::consts::PI / 8.0;
+
+// Number of triangles of a hairline round cap.
+const HAIRLINE_ROUND_CAP_TRIANGLES: u32 = 8;
+
+// Produces a pair of vertices at each end of the segments of a flattened sub-path, the
+// triangles between them, and the triangles of the joins and the caps.
+fn tessellate_hairline_sub_path(
+    points: &[Point],
+    closed: bool,
+    options: &StrokeOptions,
+    output: &mut dyn GeometryBuilder<HairlineVertex>,
+) -> Result<(), GeometryBuilderError> {
+    if points.len() < 2 {
+        return Ok(());
+    }
+
+    let n = points.len();
+    let num_segments = if closed { n } else { n - 1 };
+    let tangent = |i: usize| (points[(i + 1) % n] - points[i]).normalize();
+
+    let mut advancement = 0.0;
+    // The vertices at the start of the current segment.
+    let mut segment_start = None;
+    for i in 0..n {
+        if i > 0 {
+            advancement += (points[i] - points[i - 1]).length();
+        }
+        let vertex = HairlineVertex {
+            position: points[i],
+            previous_tangent: vector(0.0, 0.0),
+            next_tangent: vector(0.0, 0.0),
+            advancement,
+            side: Side::Left,
+            cap: 0.0,
+            extrusion: HairlineExtrusion::Side,
+        };
+
+        if i > 0 {
+            let t = tangent(i - 1);
+            let cap = if !closed && i == n - 1 { 1.0 } else { 0.0 };
+            let end = add_hairline_segment_end(vertex, t, cap, options.end_cap, output)?;
+            add_hairline_segment(segment_start.unwrap(), end, output);
+        }
+
+        if i < num_segments {
+            let t = tangent(i);
+            let cap = if !closed && i == 0 { -1.0 } else { 0.0 };
+            segment_start = Some(add_hairline_segment_end(vertex, t, cap, options.start_cap, output)?);
+            if i > 0 {
+                add_hairline_join(vertex, tangent(i - 1), t, options.line_join, output)?;
+            }
+        }
+    }
+
+    if closed {
+        // The first point is shared by the first and the last segments, and the vertices
+        // of the join keep the advancement of the start of the sub-path.
+        let vertex = HairlineVertex {
+            position: points[0],
+            previous_tangent: vector(0.0, 0.0),
+            next_tangent: vector(0.0, 0.0),
+            advancement: advancement + (points[0] - points[n - 1]).length(),
+            side: Side::Left,
+            cap: 0.0,
+            extrusion: HairlineExtrusion::Side,
+        };
+        let end = add_hairline_segment_end(vertex, tangent(n - 1), 0.0, LineCap::Butt, output)?;
+        add_hairline_segment(segment_start.unwrap(), end, output);
+        let vertex = HairlineVertex { advancement: 0.0, ..vertex };
+        add_hairline_join(vertex, tangent(n - 1), tangent(0), options.line_join, output)?;
+    }
+
+    Ok(())
+}
+
+// Adds the left and right vertices of the end of a segment with the provided direction,
+// and its cap if `cap` is not zero.
+fn add_hairline_segment_end(
+    vertex: HairlineVertex,
+    tangent: Vector,
+    cap: f32,
+    line_cap: LineCap,
+    output: &mut dyn GeometryBuilder<HairlineVertex>,
+) -> Result<(VertexId, VertexId), GeometryBuilderError> {
+    let mut vertex = HairlineVertex {
+        previous_tangent: tangent,
+        next_tangent: tangent,
+        cap: if line_cap == LineCap::Square { cap } else { 0.0 },
+        ..vertex
+    };
+    let left = output.add_vertex(vertex)?;
+    vertex.side = Side::Right;
+    let right = output.add_vertex(vertex)?;
+
+    if cap != 0.0 && line_cap == LineCap::Round {
+        let center = output.add_vertex(HairlineVertex {
+            cap,
+            extrusion: HairlineExtrusion::Center,
+            ..vertex
+        })?;
+        let mut previous = left;
+        for i in 1..HAIRLINE_ROUND_CAP_TRIANGLES {
+            let t = i as f32 / HAIRLINE_ROUND_CAP_TRIANGLES as f32;
+            let id = output.add_vertex(HairlineVertex {
+                cap,
+                extrusion: HairlineExtrusion::RoundCap(t),
+                ..vertex
+            })?;
+            output.add_triangle(center, previous, id);
+            previous = id;
+        }
+        output.add_triangle(center, previous, right);
+    }
+
+    Ok((left, right))
+}
+
+fn add_hairline_segment(
+    (from_left, from_right): (VertexId, VertexId),
+    (to_left, to_right): (VertexId, VertexId),
+    output: &mut dyn GeometryBuilder<HairlineVertex>,
+) {
+    output.add_triangle(from_left, from_right, to_left);
+    output.add_triangle(from_right, to_right, to_left);
+}
+
+// Adds the triangles that fill the gap between two segments on the outer side of a join.
+fn add_hairline_join(
+    vertex: HairlineVertex,
+    previous_tangent: Vector,
+    next_tangent: Vector,
+    line_join: LineJoin,
+    output: &mut dyn GeometryBuilder<HairlineVertex>,
+) -> Result<(), GeometryBuilderError> {
+    // The segments already cover the join when the path is straight. The other cases are
+    // handled because the transform can change the angle.
+    if previous_tangent == next_tangent {
+        return Ok(());
+    }
+
+    // The vertices of the outer side of the join, from the previous segment to the next one.
+    let (tip, round_steps): (&[HairlineExtrusion], u32) = match line_join {
+        LineJoin::Miter => (&[HairlineExtrusion::Miter], 1),
+        LineJoin::MiterClip => (&[HairlineExtrusion::MiterClip(0.0), HairlineExtrusion::MiterClip(1.0)], 1),
+        LineJoin::Round => {
+            let angle = previous_tangent.cross(next_tangent).atan2(previous_tangent.dot(next_tangent));
+            (&[], (angle.abs() / MAX_HAIRLINE_JOIN_ANGLE).ceil().max(1.0) as u32)
+        }
+        LineJoin::Bevel => (&[], 1),
+    };
+    let outer = Some(HairlineExtrusion::Join(0.0)).into_iter()
+        .chain(tip.iter().cloned())
+        .chain((1..round_steps).map(|i| HairlineExtrusion::Join(i as f32 / round_steps as f32)))
+        .chain(Some(HairlineExtrusion::Join(1.0)));
+
+    let vertex = HairlineVertex { previous_tangent, next_tangent, ..vertex };
+    let center = output.add_vertex(HairlineVertex { extrusion: HairlineExtrusion::Center, ..vertex })?;
+    let mut previous = None;
+    for extrusion in outer {
+        let id = output.add_vertex(HairlineVertex { extrusion, ..vertex })?;
+        if let Some(previous) = previous {
+            output.add_triangle(center, previous, id);
+        }
+        previous = Some(id);
+    }
+
+    Ok(())
+}
+
+fn dasher(options: &StrokeOptions) -> Option<Dasher> {
+    let dashes = options.dashes();
+    if dashes.iter().sum::<f32>() > 0.0 {
+        Some(Dasher::new(dashes, options.dash_offset))
+    } else {
+        None
+    }
+}
+
 // Computes the max angle of a radius segment for a given tolerance
 fn compute_max_radius_segment_angle(radius: f32, tolerance: f32) -> f32 {
     let t = radius - tolerance;
@@ -1039,7 +1776,9 @@ fn tess_round_cap(
     advancement: f32,
     side: Side,
     line_width: f32,
+    normal_scale: f32,
     invert_winding: bool,
+    attributes: VertexAttributes,
     output: &mut dyn GeometryBuilder<Vertex>
 ) -> Result<(), GeometryBuilderError> {
     if num_recursions == 0 {
@@ -1050,12 +1789,13 @@ fn tess_round_cap(
 
     let normal = vector(mid_angle.cos(), mid_angle.sin());
 
-    let vertex = output.add_vertex(Vertex {
+    let vertex = Vertex {
         position: center + normal * line_width,
-        normal,
+        normal: normal * normal_scale,
         advancement,
         side,
-    })?;
+    };
+    let vertex = add_recorded_vertex(output, vertex, attributes)?;
 
     let (v1, v2, v3) = if invert_winding {
         (vertex, vb, va)
@@ -1074,7 +1814,9 @@ fn tess_round_cap(
         advancement,
         side,
         line_width,
+        normal_scale,
         invert_winding,
+        attributes,
         output
     )?;
     tess_round_cap(
@@ -1087,15 +1829,97 @@ fn tess_round_cap(
         advancement,
         side,
         line_width,
+        normal_scale,
         invert_winding,
+        attributes,
         output
     )
 }
 
+// Custom attributes at the various positions tracked by the stroke builder, stored
+// in fixed slots of a single buffer. The buffer is empty when the path does not
+// have custom attributes.
+struct Attributes {
+    num_attributes: usize,
+    data: Vec<f32>,
+}
+
+mod slot {
+    // Attributes of the vertices being generated.
+    pub const LINE: usize = 0;
+    // Attributes at `first`, `second` and `current`.
+    pub const FIRST: usize = 1;
+    pub const SECOND: usize = 2;
+    pub const CURRENT: usize = 3;
+    // Attributes at the current position and at the start of the sub-path in the input.
+    pub const INPUT: usize = 4;
+    pub const INPUT_FIRST: usize = 5;
+    // Attributes at the endpoints of the input segment being dashed.
+    pub const FROM: usize = 6;
+    pub const TO: usize = 7;
+    // Attributes at the endpoints of the curve being flattened.
+    pub const CURVE_FROM: usize = 8;
+    pub const CURVE_TO: usize = 9;
+    // Attributes at the end of the edge being added.
+    pub const EDGE: usize = 10;
+
+    pub const COUNT: usize = 11;
+}
+
+impl Attributes {
+    fn new(num_attributes: usize) -> Self {
+        Attributes {
+            num_attributes,
+            data: vec![0.0; num_attributes * slot::COUNT],
+        }
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool { self.num_attributes == 0 }
+
+    #[inline]
+    fn get(&self, slot: usize) -> &[f32] {
+        let n = self.num_attributes;
+        &self.data[slot * n..(slot + 1) * n]
+    }
+
+    #[inline]
+    fn set(&mut self, slot: usize, attributes: &[f32]) {
+        let n = self.num_attributes;
+        self.data[slot * n..(slot + 1) * n].copy_from_slice(attributes);
+    }
+
+    #[inline]
+    fn copy(&mut self, src: usize, dst: usize) {
+        let n = self.num_attributes;
+        if n == 0 {
+            return;
+        }
+        self.data.copy_within(src * n..(src + 1) * n, dst * n);
+    }
+
+    fn lerp(&mut self, from: usize, to: usize, t: f32, dst: usize) {
+        let n = self.num_attributes;
+        for i in 0..n {
+            let a = self.data[from * n + i];
+            let b = self.data[to * n + i];
+            self.data[dst * n + i] = a + (b - a) * t;
+        }
+    }
+
+    fn clear(&mut self) {
+        for val in &mut self.data {
+            *val = 0.0;
+        }
+    }
+}
+
 #[cfg(test)]
-use crate::path::{Path, PathSlice};
+use crate::path::Path;
 #[cfg(test)]
 use crate::geometry_builder::{SimpleBuffersBuilder, simple_builder, VertexBuffers, Count};
+#[cfg(test)]
+use crate::geometry_builder::{BuffersBuilder, triangles_area, AttributedVertex, WithAttributes};
 
 #[cfg(test)]
 fn test_path(
@@ -1260,6 +2084,307 @@ fn test_empty_caps() {
     );
 }
 
+#[test]
+fn test_dashes() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let path = builder.build();
+
+    // Dashes at [0, 2], [3, 5], [6, 8] and [9, 10].
+    let options = StrokeOptions::default().with_dashes(&[2.0, 1.0], 0.0).unwrap();
+    test_path(path.as_slice(), &options, Some(8));
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path(
+        &path,
+        &options,
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    // The advancement is measured along the whole path, including the gaps.
+    for vertex in &buffers.vertices {
+        assert_eq!(vertex.advancement, vertex.position.x);
+    }
+    let mut advancements: Vec<f32> = buffers.vertices.iter().map(|v| v.advancement).collect();
+    advancements.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    advancements.dedup();
+    assert_eq!(advancements, vec![0.0, 2.0, 3.0, 5.0, 6.0, 8.0, 9.0, 10.0]);
+
+    // Starting one unit into the pattern, across a corner.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(4.0, 0.0));
+    builder.line_to(point(4.0, 4.0));
+    let path = builder.build();
+    test_path(
+        path.as_slice(),
+        &StrokeOptions::default().with_dashes(&[3.0, 1.0], 1.0).unwrap(),
+        // [0, 2], [3, 4] + [4, 2] with a miter join, [3, 4].
+        Some(2 + 4 + 2),
+    );
+
+    // Zero-length dashes are only visible with caps.
+    test_path(
+        path.as_slice(),
+        &StrokeOptions::default().with_dashes(&[0.0, 2.0], 0.0).unwrap(),
+        Some(0),
+    );
+    test_path(
+        path.as_slice(),
+        &StrokeOptions::default().with_dashes(&[0.0, 2.0], 0.0).unwrap().with_line_cap(LineCap::Square),
+        Some(2 * 5),
+    );
+    test_path(
+        path.as_slice(),
+        &StrokeOptions::default().with_dashes(&[0.0, 2.0], 0.0).unwrap().with_line_cap(LineCap::Round),
+        None,
+    );
+}
+
+#[test]
+fn test_invalid_dashes() {
+    let options = StrokeOptions::default();
+    assert!(options.with_dashes(&[1.0; StrokeOptions::MAX_DASHES], 0.0).is_ok());
+    assert!(options.with_dashes(&[1.0; StrokeOptions::MAX_DASHES + 1], 0.0).is_err());
+    assert!(options.with_dashes(&[1.0, -1.0], 0.0).is_err());
+    assert!(options.with_dashes(&[1.0, std::f32::NAN], 0.0).is_err());
+    assert!(options.with_dashes(&[1.0, 1.0], std::f32::INFINITY).is_err());
+    assert!(options.with_dashes(&[1.0, 1.0], -1.0).is_ok());
+}
+
+#[test]
+fn test_variable_line_width() {
+    fn tessellate(path: &Path, options: &StrokeOptions) -> VertexBuffers<Vertex, u16> {
+        let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+        StrokeTessellator::new().tessellate_path_with_attributes(
+            path.as_slice(),
+            options,
+            &mut simple_builder(&mut buffers),
+        ).unwrap();
+        buffers
+    }
+
+    fn area(buffers: &VertexBuffers<Vertex, u16>) -> f32 {
+        triangles_area(buffers, |v| v.position)
+    }
+
+    // The line width goes from 2 to 6.
+    let mut builder = Path::builder_with_attributes(1);
+    builder.move_to(point(0.0, 0.0), &[1.0]);
+    builder.line_to(point(10.0, 0.0), &[3.0]);
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0).with_variable_line_width(0);
+    let buffers = tessellate(&path, &options);
+    for vertex in &buffers.vertices {
+        let half_width = 1.0 + vertex.position.x / 5.0;
+        assert!((vertex.position.y.abs() - half_width).abs() < 0.001);
+    }
+    assert!((area(&buffers) - 40.0).abs() < 0.001);
+
+    // Without the option, the attribute is ignored.
+    let buffers = tessellate(&path, &StrokeOptions::default().with_line_width(2.0));
+    assert!((area(&buffers) - 20.0).abs() < 0.001);
+
+    // The width is interpolated within dashes.
+    let buffers = tessellate(&path, &options.with_dashes(&[5.0, 5.0], 0.0).unwrap());
+    assert!((area(&buffers) - 15.0).abs() < 0.001);
+
+    // And in the normals if the width is not applied.
+    let buffers = tessellate(&path, &options.dont_apply_line_width());
+    for vertex in &buffers.vertices {
+        let half_width = 1.0 + vertex.position.x / 5.0;
+        assert!((vertex.normal.length() - half_width).abs() < 0.001);
+    }
+
+    // Curves, joins and caps.
+    let mut builder = Path::builder_with_attributes(2);
+    builder.move_to(point(0.0, 0.0), &[0.0, 1.0]);
+    builder.quadratic_bezier_to(point(10.0, 0.0), point(10.0, 10.0), &[0.0, 2.0]);
+    builder.line_to(point(0.0, 10.0), &[0.0, 0.5]);
+    let path = builder.build();
+    for &join in &[LineJoin::Miter, LineJoin::MiterClip, LineJoin::Round, LineJoin::Bevel] {
+        for &cap in &[LineCap::Butt, LineCap::Square, LineCap::Round] {
+            let options = StrokeOptions::tolerance(0.01)
+                .with_variable_line_width(1)
+                .with_line_join(join)
+                .with_line_cap(cap);
+            let buffers = tessellate(&path, &options);
+            for vertex in &buffers.vertices {
+                assert!(!vertex.position.x.is_nan());
+                assert!(!vertex.position.y.is_nan());
+            }
+        }
+    }
+
+    // The attribute index must be in range.
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let result = StrokeTessellator::new().tessellate_path_with_attributes(
+        path.as_slice(),
+        &StrokeOptions::default().with_variable_line_width(2),
+        &mut simple_builder(&mut buffers),
+    );
+    assert_eq!(result, Err(TessellationError::UnsupportedParamater));
+}
+
+#[test]
+fn test_path_attributes() {
+    // The attribute is equal to the distance along the path, which is x + y.
+    let mut builder = Path::builder_with_attributes(1);
+    builder.move_to(point(0.0, 0.0), &[0.0]);
+    builder.line_to(point(10.0, 0.0), &[10.0]);
+    builder.quadratic_bezier_to(point(15.0, 0.0), point(20.0, 0.0), &[20.0]);
+    builder.line_to(point(20.0, 10.0), &[30.0]);
+    let path = builder.build();
+
+    // Don't apply the line width so that the vertices are positioned on the path.
+    let options = StrokeOptions::default().dont_apply_line_width();
+    for &join in &[LineJoin::Miter, LineJoin::Round, LineJoin::Bevel] {
+        for &dashes in &[&[][..], &[3.0, 2.0][..]] {
+            let mut buffers: VertexBuffers<AttributedVertex, u16> = VertexBuffers::new();
+            StrokeTessellator::new().tessellate_path_with_attributes(
+                path.as_slice(),
+                &options.with_line_join(join).with_line_cap(LineCap::Round).with_dashes(dashes, 0.0).unwrap(),
+                &mut BuffersBuilder::new(&mut buffers, WithAttributes),
+            ).unwrap();
+
+            assert!(!buffers.indices.is_empty());
+            for vertex in &buffers.vertices {
+                assert_eq!(vertex.attributes.len(), 1);
+                assert!((vertex.attributes[0] - vertex.position.x - vertex.position.y).abs() < 0.01);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_vertex_sources() {
+    let mut builder = Path::builder();
+    builder.set_sub_path_id(1);
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    builder.line_to(point(30.0, 10.0));
+    builder.close();
+    builder.move_to(point(50.0, 0.0));
+    let path = builder.build();
+
+    // Don't apply the line width so that the vertices are positioned on the path.
+    let options = StrokeOptions::default().dont_apply_line_width();
+    for &cap in &[LineCap::Butt, LineCap::Round] {
+        let mut buffers: VertexBuffers<AttributedVertex, u16> = VertexBuffers::new();
+        StrokeTessellator::new().tessellate_path_with_sources(
+            path.as_slice(),
+            &options.with_line_cap(cap),
+            &mut BuffersBuilder::new(&mut buffers, WithAttributes),
+        ).unwrap();
+
+        assert!(!buffers.indices.is_empty());
+        for vertex in &buffers.vertices {
+            let (position, source) = (vertex.position, vertex.source.unwrap());
+            let expected = match (position.x, position.y) {
+                // The start cap.
+                (x, y) if x == 0.0 && y == 0.0 => (0, 0),
+                (x, y) if x == 10.0 && y == 0.0 => (0, 1),
+                // The end cap.
+                (x, y) if x == 10.0 && y == 10.0 => (0, 1),
+                (x, y) if x == 30.0 && y == 0.0 => (1, 1),
+                (x, y) if x == 30.0 && y == 10.0 => (1, 2),
+                // The join between the closing edge and the first one.
+                (x, y) if x == 20.0 && y == 0.0 => (1, 0),
+                // The caps of the sub-path without edges.
+                (x, y) if x == 50.0 && y == 0.0 => (2, 0),
+                _ => panic!("unexpected vertex {:?}", position),
+            };
+            assert_eq!((source.sub_path, source.segment), expected);
+            let sub_path_id = if source.sub_path == 0 { Some(1) } else { None };
+            assert_eq!(source.sub_path_id, sub_path_id);
+        }
+    }
+}
+
+#[test]
+fn test_anti_aliasing_fringe() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0).with_anti_aliasing(1.0);
+    for &apply_line_width in &[true, false] {
+        let options = if apply_line_width { options } else { options.dont_apply_line_width() };
+        let mut buffers: VertexBuffers<AttributedVertex, u16> = VertexBuffers::new();
+        StrokeTessellator::new().tessellate_path(
+            &path,
+            &options,
+            &mut BuffersBuilder::new(&mut buffers, WithAttributes),
+        ).unwrap();
+
+        let position = |v: &AttributedVertex| {
+            if apply_line_width { v.position } else { v.position + v.normal }
+        };
+
+        let mut inner_area = 0.0;
+        let mut fringe_area = 0.0;
+        for triangle in buffers.indices.chunks(3) {
+            let a = &buffers.vertices[triangle[0] as usize];
+            let b = &buffers.vertices[triangle[1] as usize];
+            let c = &buffers.vertices[triangle[2] as usize];
+            let area = (position(b) - position(a)).cross(position(c) - position(a)).abs() * 0.5;
+            if a.coverage == 1.0 && b.coverage == 1.0 && c.coverage == 1.0 {
+                inner_area += area;
+            } else {
+                fringe_area += area;
+            }
+        }
+        assert!((inner_area - 20.0).abs() < 0.001);
+        assert!((fringe_area - 28.0).abs() < 0.001);
+
+        for vertex in &buffers.vertices {
+            let p = position(vertex);
+            let outer = p.y.abs() > 1.5;
+            assert_eq!(vertex.coverage, if outer { 0.0 } else { 1.0 });
+        }
+    }
+}
+
+#[test]
+fn test_clip() {
+    use crate::ClipPolygon;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let path = builder.build();
+
+    let clip = ClipPolygon::from_rect(&rect(2.0, -5.0, 3.0, 10.0)).unwrap();
+    let options = StrokeOptions::default().with_line_width(2.0).with_clip(clip);
+    for &apply_line_width in &[true, false] {
+        let options = if apply_line_width { options } else { options.dont_apply_line_width() };
+        let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+        StrokeTessellator::new().tessellate_path(
+            &path,
+            &options,
+            &mut simple_builder(&mut buffers),
+        ).unwrap();
+
+        let position = |v: &Vertex| {
+            if apply_line_width { v.position } else { v.position + v.normal }
+        };
+
+        assert!((triangles_area(&buffers, position) - 6.0).abs() < 0.001);
+
+        for vertex in &buffers.vertices {
+            let p = position(vertex);
+            assert!(p.x >= 1.999 && p.x <= 5.001);
+            assert!((vertex.advancement - p.x).abs() < 0.001);
+        }
+    }
+}
+
 #[test]
 fn test_too_many_vertices() {
     /// This test checks that the tessellator returns the proper error when
@@ -1304,3 +2429,217 @@ fn test_too_many_vertices() {
         Err(TessellationError::TooManyVertices),
     );
 }
+
+#[test]
+fn test_strips() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(20.0, 0.0), point(20.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.move_to(point(30.0, 0.0));
+    builder.line_to(point(40.0, 10.0));
+    let path = builder.build();
+    let options = StrokeOptions::tolerance(0.05).with_line_cap(LineCap::Round);
+
+    let mut expected: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path(&path, &options, &mut simple_builder(&mut expected)).unwrap();
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path_strips(&path, &options, &mut simple_builder(&mut buffers)).unwrap();
+
+    assert_eq!(buffers.vertices, expected.vertices);
+    assert!(buffers.indices.len() < expected.indices.len());
+
+    // Rotates the triangles so that they start with their smallest index, which preserves
+    // their winding.
+    let rotated = |a: u16, b: u16, c: u16| {
+        if a < b && a < c {
+            [a, b, c]
+        } else if b < c {
+            [b, c, a]
+        } else {
+            [c, a, b]
+        }
+    };
+    let mut triangles = Vec::new();
+    for strip in buffers.indices.split(|&idx| idx == u16::MAX) {
+        for i in 2..strip.len() {
+            let (a, b, c) = (strip[i - 2], strip[i - 1], strip[i]);
+            if a == b || b == c || a == c {
+                continue;
+            }
+            // Every other triangle of a strip has the opposite order.
+            triangles.push(if i % 2 == 0 { rotated(a, b, c) } else { rotated(b, a, c) });
+        }
+    }
+    let mut expected_triangles: Vec<_> = expected.indices.chunks(3).map(|t| rotated(t[0], t[1], t[2])).collect();
+    triangles.sort();
+    expected_triangles.sort();
+    assert_eq!(triangles, expected_triangles);
+    assert_eq!(buffers.indices.last(), Some(&u16::MAX));
+}
+
+#[test]
+fn test_lines() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(20.0, 0.0));
+    builder.quadratic_bezier_to(point(30.0, 0.0), point(30.0, 10.0));
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let count = StrokeTessellator::new().tessellate_path_lines(
+        &path,
+        &StrokeOptions::tolerance(0.01),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    assert_eq!(count.indices as usize, buffers.indices.len());
+    assert_eq!(&buffers.indices[..8], &[0, 1, 1, 2, 2, 3, 3, 4]);
+    assert_eq!(buffers.vertices[4].position, point(0.0, 0.0));
+    assert_eq!(buffers.vertices[4].advancement, 40.0);
+    assert_eq!(buffers.vertices[5].advancement, 0.0);
+    // The curve is flattened, its segments are connected.
+    assert!(buffers.indices.len() > 10);
+    for line in buffers.indices[8..].chunks(2) {
+        assert_eq!(line[1], line[0] + 1);
+    }
+    assert_eq!(buffers.vertices.last().unwrap().position, point(30.0, 10.0));
+}
+
+#[test]
+fn test_hairline() {
+    use crate::geom::euclid::Angle;
+    use crate::math::Transform2D;
+    use crate::{HairlineVertex, HairlineExtrusion};
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    // A sharp spike.
+    builder.line_to(point(10.2, 0.0));
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    builder.line_to(point(25.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let tessellate = |options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<HairlineVertex, u16> = VertexBuffers::new();
+        StrokeTessellator::new().tessellate_path_hairline(&path, options, &mut simple_builder(&mut buffers)).unwrap();
+        buffers
+    };
+
+    let buffers = tessellate(&StrokeOptions::default().with_line_cap(LineCap::Square));
+    let caps: Vec<f32> = buffers.vertices.iter().filter(|v| v.cap != 0.0).map(|v| v.cap).collect();
+    assert_eq!(caps, vec![-1.0, -1.0, 1.0, 1.0]);
+    // Two pairs of vertices per segment, and four vertices per miter join.
+    assert_eq!(buffers.vertices.len(), 6 * 4 + 5 * 4);
+    let max_advancement = buffers.vertices.iter().map(|v| v.advancement).fold(0.0, f32::max);
+    assert!((max_advancement - (10.0 + 2.0 * 125.0f32.sqrt())).abs() < 0.001);
+
+    let line_width = 1.0;
+    let miter_limit = 4.0;
+    let transforms = [
+        Transform2D::create_scale(3.0, 0.5).post_rotate(Angle::radians(0.5)),
+        // Reflections change the outer side of the joins.
+        Transform2D::create_scale(-2.0, 1.0),
+    ];
+    let joins = [LineJoin::Miter, LineJoin::MiterClip, LineJoin::Round, LineJoin::Bevel];
+    let caps = [LineCap::Butt, LineCap::Square, LineCap::Round];
+    for (&line_join, &line_cap) in joins.iter().zip(caps.iter().cycle()) {
+        let options = StrokeOptions::default().with_line_join(line_join).with_line_cap(line_cap);
+        let buffers = tessellate(&options);
+        for transform in &transforms {
+            let extrude = |v: &HairlineVertex| v.extrude(transform, line_width, miter_limit);
+            // Distance of a point to the line going through a vertex along a tangent.
+            let distance = |p: Point, v: &HairlineVertex, tangent: Vector| {
+                let tangent = transform.transform_vector(tangent).normalize();
+                tangent.cross(p - transform.transform_point(v.position)).abs()
+            };
+            let radius = |p: Point, v: &HairlineVertex| (p - transform.transform_point(v.position)).length();
+
+            for vertex in &buffers.vertices {
+                let p = extrude(vertex);
+                match vertex.extrusion {
+                    HairlineExtrusion::Side => {
+                        // The extruded vertices are at half the line width of the path on the screen.
+                        assert!((distance(p, vertex, vertex.next_tangent) - 0.5).abs() < 0.001);
+                    }
+                    HairlineExtrusion::Center => {
+                        assert!(radius(p, vertex) < 0.001);
+                    }
+                    HairlineExtrusion::Join(t) => {
+                        assert!((radius(p, vertex) - 0.5).abs() < 0.001);
+                        if t == 0.0 {
+                            assert!((distance(p, vertex, vertex.previous_tangent) - 0.5).abs() < 0.001);
+                        }
+                        if t == 1.0 {
+                            assert!((distance(p, vertex, vertex.next_tangent) - 0.5).abs() < 0.001);
+                        }
+                        // The joins are on the outer side of the turn.
+                        let turn = transform.transform_vector(vertex.next_tangent).normalize()
+                            - transform.transform_vector(vertex.previous_tangent).normalize();
+                        assert!((p - transform.transform_point(vertex.position)).dot(turn) <= 0.001);
+                    }
+                    HairlineExtrusion::Miter => {
+                        let r = radius(p, vertex);
+                        if r > 0.5 + 0.001 {
+                            assert!(r <= miter_limit * 0.5 + 0.001);
+                            assert!((distance(p, vertex, vertex.previous_tangent) - 0.5).abs() < 0.001);
+                            assert!((distance(p, vertex, vertex.next_tangent) - 0.5).abs() < 0.001);
+                        }
+                    }
+                    HairlineExtrusion::MiterClip(t) => {
+                        // On the outer edge of one of the segments.
+                        let tangent = if t < 0.5 { vertex.previous_tangent } else { vertex.next_tangent };
+                        assert!((distance(p, vertex, tangent) - 0.5).abs() < 0.001);
+                    }
+                    HairlineExtrusion::RoundCap(..) => {
+                        assert!((radius(p, vertex) - 0.5).abs() < 0.001);
+                    }
+                }
+            }
+
+            // The sharp spike exceeds the miter limit, which gives a bevel for miter joins,
+            // and a clipped miter for miter clip joins. Neither makes the line thinner.
+            let spike: Vec<&HairlineVertex> = buffers.vertices.iter()
+                .filter(|v| v.position == point(10.0, 10.0) && v.extrusion != HairlineExtrusion::Side)
+                .collect();
+            let find = |extrusion| spike.iter().find(|v| v.extrusion == extrusion).map(|v| extrude(v));
+            let (first, last) = (find(HairlineExtrusion::Join(0.0)).unwrap(), find(HairlineExtrusion::Join(1.0)).unwrap());
+            match line_join {
+                LineJoin::Miter => {
+                    let miter = find(HairlineExtrusion::Miter).unwrap();
+                    assert!((miter - first.lerp(last, 0.5)).length() < 0.001);
+                }
+                LineJoin::MiterClip => {
+                    let clipped = [find(HairlineExtrusion::MiterClip(0.0)).unwrap(), find(HairlineExtrusion::MiterClip(1.0)).unwrap()];
+                    let center = transform.transform_point(spike[0].position);
+                    let direction = (first.to_vector() + last.to_vector() - center.to_vector() * 2.0).normalize();
+                    for p in &clipped {
+                        assert!(((*p - center).dot(direction) - miter_limit * 0.5).abs() < 0.001);
+                    }
+                }
+                LineJoin::Round => {
+                    assert!(spike.len() > 4);
+                }
+                LineJoin::Bevel => {
+                    assert_eq!(spike.len(), 3);
+                }
+            }
+        }
+
+        // Round caps are fans of vertices around the ends of the open sub-path.
+        let round_caps = buffers.vertices.iter()
+            .filter(|v| matches!(v.extrusion, HairlineExtrusion::RoundCap(..)))
+            .count();
+        assert_eq!(round_caps > 0, line_cap == LineCap::Round);
+    }
+}
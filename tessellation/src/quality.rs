@@ -0,0 +1,302 @@
+// Mesh quality.
+//
+// The triangles produced by the fill tessellator are improved by flipping the edges that
+// don't satisfy the Delaunay criterion (Lawson's algorithm), which gives the constrained
+// Delaunay triangulation of the shape, its outline being the constraint. Optionally, the
+// triangles that are too large or too thin are split by inserting a vertex in the middle
+// of their longest edge, followed by more edge flips, until the mesh reaches the maximum
+// number of vertices.
+
+use crate::geom::math::*;
+use crate::geometry_builder::GeometryBuilder;
+use crate::clip::{ClipVertex, GeometryRecorder};
+use crate::{MeshQuality, TessellationError, TessellationResult};
+
+use std::collections::{HashMap, HashSet};
+
+// Maximum number of refinement passes, each pass splitting every bad triangle once.
+const MAX_REFINEMENT_PASSES: u32 = 64;
+
+/// Runs `tessellate`, improving the quality of its output if `quality` is not `None`.
+pub(crate) fn with_mesh_quality<V: ClipVertex>(
+    quality: Option<&MeshQuality>,
+    output: &mut dyn GeometryBuilder<V>,
+    tessellate: impl FnOnce(&mut dyn GeometryBuilder<V>) -> TessellationResult,
+) -> TessellationResult {
+    let quality = match quality {
+        Some(quality) => quality,
+        None => { return tessellate(output); }
+    };
+
+    if !quality.is_valid() {
+        return Err(TessellationError::UnsupportedParamater);
+    }
+
+    let mut recorder = GeometryRecorder::new();
+
+    tessellate(&mut recorder)?;
+
+    let mut mesh = Mesh::new(&mut recorder);
+    mesh.make_delaunay();
+    if quality.max_area.is_some() || quality.min_angle.is_some() || quality.max_edge_length.is_some() {
+        mesh.refine(quality);
+    }
+
+    output.begin_geometry();
+    for i in 0..mesh.triangles.len() {
+        let [a, b, c] = mesh.triangles[i];
+        let ids = (
+            mesh.recorder.output_id(a, output),
+            mesh.recorder.output_id(b, output),
+            mesh.recorder.output_id(c, output),
+        );
+        match ids {
+            (Ok(a), Ok(b), Ok(c)) => { output.add_triangle(a, b, c); }
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                output.abort_geometry();
+                return Err(e.into());
+            }
+        }
+    }
+
+    Ok(output.end_geometry())
+}
+
+struct Mesh<'l, V> {
+    recorder: &'l mut GeometryRecorder<V>,
+    positions: Vec<Point>,
+    // Counter-clockwise triangles (with a positive area).
+    triangles: Vec<[usize; 3]>,
+    // The triangle that contains each directed edge.
+    half_edges: HashMap<(usize, usize), usize>,
+    // Edges that can't be flipped or split because they are shared by more than two triangles.
+    constrained: HashSet<(usize, usize)>,
+}
+
+impl<'l, V: ClipVertex> Mesh<'l, V> {
+    fn new(recorder: &'l mut GeometryRecorder<V>) -> Self {
+        // The tessellator doesn't always share the vertices between neighbor triangles,
+        // so vertices are welded by position to find the topology.
+        let mut welded = HashMap::new();
+        let mut representatives = Vec::with_capacity(recorder.vertices.len());
+        let mut positions = Vec::with_capacity(recorder.vertices.len());
+        for (idx, vertex) in recorder.vertices.iter().enumerate() {
+            let position = vertex.vertex.fringe_position(None);
+            let key = (position.x.to_bits(), position.y.to_bits());
+            representatives.push(*welded.entry(key).or_insert(idx));
+            positions.push(position);
+        }
+
+        let mut mesh = Mesh {
+            triangles: Vec::with_capacity(recorder.triangles.len()),
+            recorder,
+            positions,
+            half_edges: HashMap::new(),
+            constrained: HashSet::new(),
+        };
+
+        for i in 0..mesh.recorder.triangles.len() {
+            let [a, b, c] = mesh.recorder.triangles[i];
+            let (a, b, c) = (representatives[a], representatives[b], representatives[c]);
+            let area = (mesh.positions[b] - mesh.positions[a]).cross(mesh.positions[c] - mesh.positions[a]);
+            // Degenerate triangles don't cover anything.
+            if area.abs() < 1e-6 {
+                continue;
+            }
+            let triangle = if area > 0.0 { [a, b, c] } else { [a, c, b] };
+            let idx = mesh.triangles.len();
+            mesh.triangles.push(triangle);
+            for i in 0..3 {
+                let edge = (triangle[i], triangle[(i + 1) % 3]);
+                if mesh.half_edges.insert(edge, idx).is_some() {
+                    mesh.constrained.insert(undirected(edge));
+                }
+            }
+        }
+
+        mesh
+    }
+
+    fn make_delaunay(&mut self) {
+        let mut stack = Vec::with_capacity(self.half_edges.len());
+        for &(a, b) in self.half_edges.keys() {
+            if a < b {
+                stack.push((a, b));
+            }
+        }
+        self.legalize(&mut stack);
+    }
+
+    // Flips the edges of the stack that are not locally Delaunay, until the stack is empty.
+    fn legalize(&mut self, stack: &mut Vec<(usize, usize)>) {
+        while let Some((a, b)) = stack.pop() {
+            if self.constrained.contains(&undirected((a, b))) {
+                continue;
+            }
+            let (t1, t2) = match (self.half_edges.get(&(a, b)), self.half_edges.get(&(b, a))) {
+                (Some(&t1), Some(&t2)) => (t1, t2),
+                _ => { continue; }
+            };
+            // t1 = (a, b, c) and t2 = (b, a, d).
+            let c = third_vertex(&self.triangles[t1], a, b);
+            let d = third_vertex(&self.triangles[t2], b, a);
+            if !self.should_flip(a, b, c, d) {
+                continue;
+            }
+
+            self.set_triangle(t1, [c, a, d]);
+            self.set_triangle(t2, [d, b, c]);
+            stack.push((a, d));
+            stack.push((d, b));
+            stack.push((b, c));
+            stack.push((c, a));
+        }
+    }
+
+    fn should_flip(&self, a: usize, b: usize, c: usize, d: usize) -> bool {
+        let (pa, pb, pc, pd) = (self.positions[a], self.positions[b], self.positions[c], self.positions[d]);
+
+        // The new diagonal must be inside of the quadrilateral.
+        let side_a = (pd - pc).cross(pa - pc);
+        let side_b = (pd - pc).cross(pb - pc);
+        if !(side_a < 0.0 && side_b > 0.0 || side_a > 0.0 && side_b < 0.0) {
+            return false;
+        }
+
+        in_circle(pa, pb, pc, pd)
+    }
+
+    // Replaces a triangle, keeping the half edges up to date.
+    fn set_triangle(&mut self, idx: usize, triangle: [usize; 3]) {
+        let old = self.triangles[idx];
+        for i in 0..3 {
+            let edge = (old[i], old[(i + 1) % 3]);
+            if self.half_edges.get(&edge) == Some(&idx) {
+                self.half_edges.remove(&edge);
+            }
+        }
+        self.add_triangle_at(idx, triangle);
+    }
+
+    fn add_triangle(&mut self, triangle: [usize; 3]) {
+        self.triangles.push(triangle);
+        self.add_triangle_at(self.triangles.len() - 1, triangle);
+    }
+
+    fn add_triangle_at(&mut self, idx: usize, triangle: [usize; 3]) {
+        self.triangles[idx] = triangle;
+        for i in 0..3 {
+            self.half_edges.insert((triangle[i], triangle[(i + 1) % 3]), idx);
+        }
+    }
+
+    fn refine(&mut self, quality: &MeshQuality) {
+        let min_split_length = quality.min_edge_length * 2.0;
+        for _ in 0..MAX_REFINEMENT_PASSES {
+            let mut split = false;
+            for idx in 0..self.triangles.len() {
+                if self.positions.len() >= quality.max_vertices as usize {
+                    return;
+                }
+
+                let [a, b, c] = self.triangles[idx];
+                let (pa, pb, pc) = (self.positions[a], self.positions[b], self.positions[c]);
+
+                let too_large = quality.max_area.map_or(false, |max_area| {
+                    (pb - pa).cross(pc - pa) * 0.5 > max_area
+                });
+                let too_thin = quality.min_angle.map_or(false, |min_angle| {
+                    min_triangle_angle(pa, pb, pc) < min_angle
+                });
+
+                // Split the longest edge.
+                let edges = [(a, b, (pb - pa).square_length()), (b, c, (pc - pb).square_length()), (c, a, (pa - pc).square_length())];
+                let mut longest = edges[0];
+                for &edge in &edges[1..] {
+                    if edge.2 > longest.2 {
+                        longest = edge;
+                    }
+                }
+                let (from, to, square_length) = longest;
+
+                let too_long = quality.max_edge_length.map_or(false, |max_length| {
+                    square_length > max_length * max_length
+                });
+                let too_bad = (too_large || too_thin) && square_length >= min_split_length * min_split_length;
+                if !too_long && !too_bad || self.constrained.contains(&undirected((from, to))) {
+                    continue;
+                }
+
+                self.split_edge(from, to);
+                split = true;
+            }
+
+            if !split {
+                break;
+            }
+        }
+    }
+
+    // Inserts a vertex in the middle of an edge, splitting the triangles on each side.
+    fn split_edge(&mut self, a: usize, b: usize) {
+        let m = self.recorder.interpolate(a, b, 0.5);
+        self.positions.push(self.recorder.vertices[m].vertex.fringe_position(None));
+
+        let mut stack = Vec::new();
+        if let Some(&t1) = self.half_edges.get(&(a, b)) {
+            let c = third_vertex(&self.triangles[t1], a, b);
+            self.set_triangle(t1, [a, m, c]);
+            self.add_triangle([m, b, c]);
+            stack.push((b, c));
+            stack.push((c, a));
+        }
+        if let Some(&t2) = self.half_edges.get(&(b, a)) {
+            let d = third_vertex(&self.triangles[t2], b, a);
+            self.set_triangle(t2, [b, m, d]);
+            self.add_triangle([m, a, d]);
+            stack.push((a, d));
+            stack.push((d, b));
+        }
+
+        self.legalize(&mut stack);
+    }
+}
+
+fn undirected((a, b): (usize, usize)) -> (usize, usize) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+// The vertex of a triangle that is after the edge (a, b).
+fn third_vertex(triangle: &[usize; 3], a: usize, b: usize) -> usize {
+    for i in 0..3 {
+        if triangle[i] == a && triangle[(i + 1) % 3] == b {
+            return triangle[(i + 2) % 3];
+        }
+    }
+    unreachable!();
+}
+
+// Whether d is strictly inside of the circumcircle of the counter-clockwise triangle (a, b, c).
+fn in_circle(a: Point, b: Point, c: Point, d: Point) -> bool {
+    let (adx, ady) = (a.x as f64 - d.x as f64, a.y as f64 - d.y as f64);
+    let (bdx, bdy) = (b.x as f64 - d.x as f64, b.y as f64 - d.y as f64);
+    let (cdx, cdy) = (c.x as f64 - d.x as f64, c.y as f64 - d.y as f64);
+    let a_lift = adx * adx + ady * ady;
+    let b_lift = bdx * bdx + bdy * bdy;
+    let c_lift = cdx * cdx + cdy * cdy;
+    let det = a_lift * (bdx * cdy - cdx * bdy)
+        + b_lift * (cdx * ady - adx * cdy)
+        + c_lift * (adx * bdy - bdx * ady);
+
+    // Don't flip cocircular points back and forth.
+    let scale = a_lift + b_lift + c_lift;
+    det > 1e-10 * scale * scale
+}
+
+fn min_triangle_angle(a: Point, b: Point, c: Point) -> f32 {
+    let angle = |p: Point, p1: Point, p2: Point| {
+        let (v1, v2) = (p1 - p, p2 - p);
+        v1.cross(v2).abs().atan2(v1.dot(v2))
+    };
+    angle(a, b, c).min(angle(b, c, a)).min(angle(c, a, b))
+}
@@ -0,0 +1,161 @@
+// Triangle strips.
+//
+// The vertices produced by the tessellators are forwarded to the output as they come,
+// while the triangles are recorded. Once the geometry is complete, consecutive triangles
+// that share an edge are chained into strips, which works well for the stroke tessellator
+// that produces the triangles of each segment one after the other. The winding of the
+// triangles is preserved.
+
+use crate::geometry_builder::{GeometryBuilder, GeometryBuilderError, StripGeometryBuilder, Count, VertexId};
+use crate::{TessellationResult, VertexAttributes};
+
+/// Runs `tessellate`, sending its output to `output` as triangle strips.
+pub(crate) fn with_strips<V>(
+    output: &mut dyn StripGeometryBuilder<V>,
+    tessellate: impl FnOnce(&mut dyn GeometryBuilder<V>) -> TessellationResult,
+) -> TessellationResult {
+    tessellate(&mut StripBuilder { output, triangles: Vec::new() })
+}
+
+struct StripBuilder<'l, V> {
+    output: &'l mut dyn StripGeometryBuilder<V>,
+    triangles: Vec<[VertexId; 3]>,
+}
+
+impl<'l, V> StripBuilder<'l, V> {
+    fn build_strips(&mut self) {
+        // The last two vertices of the current strip, and the number of triangles in it.
+        let mut strip: Option<(VertexId, VertexId, usize)> = None;
+        for i in 0..self.triangles.len() {
+            let triangle = self.triangles[i];
+
+            if let Some((a, b, len)) = strip {
+                if triangle.contains(&a) && triangle.contains(&b) {
+                    let c = *triangle.iter().find(|&&v| v != a && v != b).unwrap();
+                    // The triangles of a strip alternate between the order of their vertices
+                    // in the strip and the opposite order.
+                    let in_strip = if len % 2 == 0 { [a, b, c] } else { [b, a, c] };
+                    if same_winding(&in_strip, &triangle) {
+                        self.output.add_strip_vertex(c);
+                        strip = Some((b, c, len + 1));
+                    } else {
+                        // Repeating the last two vertices in the opposite order adds two
+                        // degenerate triangles, after which the order of (b, a, c) is kept.
+                        self.output.add_strip_vertex(b);
+                        self.output.add_strip_vertex(a);
+                        self.output.add_strip_vertex(c);
+                        strip = Some((a, c, len + 3));
+                    }
+                    continue;
+                }
+                self.output.end_strip();
+            }
+
+            // Start a new strip, finishing with the edge shared with the next triangle
+            // if any, so that the strip can continue. Rotating the triangle preserves its
+            // winding.
+            let mut start = triangle;
+            if let Some(next) = self.triangles.get(i + 1) {
+                for rotation in 0..3 {
+                    let candidate = rotate(&triangle, rotation);
+                    if next.contains(&candidate[1]) && next.contains(&candidate[2]) {
+                        start = candidate;
+                        break;
+                    }
+                }
+            }
+            for &v in &start {
+                self.output.add_strip_vertex(v);
+            }
+            strip = Some((start[1], start[2], 1));
+        }
+
+        if strip.is_some() {
+            self.output.end_strip();
+        }
+    }
+}
+
+fn rotate(triangle: &[VertexId; 3], rotation: usize) -> [VertexId; 3] {
+    [
+        triangle[rotation],
+        triangle[(rotation + 1) % 3],
+        triangle[(rotation + 2) % 3],
+    ]
+}
+
+// Whether two triangles have the same vertices in the same cyclic order.
+fn same_winding(a: &[VertexId; 3], b: &[VertexId; 3]) -> bool {
+    (0..3).any(|rotation| rotate(b, rotation) == *a)
+}
+
+impl<'l, V> GeometryBuilder<V> for StripBuilder<'l, V> {
+    fn begin_geometry(&mut self) {
+        self.triangles.clear();
+        self.output.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        self.build_strips();
+        self.output.end_geometry()
+    }
+
+    fn add_vertex(&mut self, vertex: V) -> Result<VertexId, GeometryBuilderError> {
+        self.output.add_vertex(vertex)
+    }
+
+    fn add_vertex_with_attributes(
+        &mut self,
+        vertex: V,
+        attributes: VertexAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.output.add_vertex_with_attributes(vertex, attributes)
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.triangles.push([a, b, c]);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.triangles.clear();
+        self.output.abort_geometry();
+    }
+}
+
+#[test]
+fn test_strip_winding() {
+    use crate::geometry_builder::{simple_builder, VertexBuffers};
+    use crate::math::{Point, point};
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    // The second triangle doesn't have the winding of the first one.
+    let triangles = [[0, 1, 2], [1, 2, 3], [3, 2, 4]];
+    with_strips(&mut simple_builder(&mut buffers), |output| {
+        output.begin_geometry();
+        for i in 0..5 {
+            output.add_vertex(point(i as f32, (i % 2) as f32))?;
+        }
+        for t in &triangles {
+            output.add_triangle(VertexId(t[0]), VertexId(t[1]), VertexId(t[2]));
+        }
+        Ok(output.end_geometry())
+    }).unwrap();
+
+    let mut decoded = Vec::new();
+    for strip in buffers.indices.split(|&idx| idx == u16::MAX) {
+        for i in 2..strip.len() {
+            let (a, b, c) = (strip[i - 2], strip[i - 1], strip[i]);
+            if a == b || b == c || a == c {
+                continue;
+            }
+            decoded.push(if i % 2 == 0 { [a, b, c] } else { [b, a, c] });
+        }
+    }
+
+    assert_eq!(decoded.len(), triangles.len());
+    for (triangle, expected) in decoded.iter().zip(triangles.iter()) {
+        let triangle = [VertexId(triangle[0] as u32), VertexId(triangle[1] as u32), VertexId(triangle[2] as u32)];
+        let expected = [VertexId(expected[0]), VertexId(expected[1]), VertexId(expected[2])];
+        assert!(same_winding(&triangle, &expected), "{:?} {:?}", triangle, expected);
+    }
+}
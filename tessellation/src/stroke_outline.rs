@@ -0,0 +1,412 @@
+use crate::geom::math::*;
+use crate::geom::{Arc, BezierSegment, LineSegment, QuadraticBezierSegment, CubicBezierSegment};
+use crate::geom::euclid::Angle as EuclidAngle;
+use crate::path::builder::{Build, FlatPathBuilder, PathBuilder};
+use crate::path::{Path, PathEvent, Builder};
+use crate::{LineCap, StrokeOptions};
+use lyon_algorithms::offset::OffsetCurve;
+
+use std::f32::consts::PI;
+use std::mem;
+
+/// A path builder that generates the outline of a stroke as a fillable path.
+///
+/// The stroke tessellator produces overlapping triangles where the path overlaps with
+/// itself, which shades semi-transparent pixels several times. The outline generated
+/// by this builder includes the caps and joins of the stroke and can instead be filled
+/// with the `NonZero` fill rule to obtain a geometry without overlapping triangles.
+///
+/// Curves are outlined with curves. The `apply_line_width` option and the dash pattern
+/// are ignored.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lyon_tessellation as tess;
+/// # use tess::path::Path;
+/// # use tess::path::builder::*;
+/// # use tess::geom::math::*;
+/// # use tess::geometry_builder::{VertexBuffers, simple_builder};
+/// # use tess::*;
+/// # fn main() {
+/// let mut path_builder = Path::builder();
+/// path_builder.move_to(point(0.0, 0.0));
+/// path_builder.line_to(point(10.0, 10.0));
+/// path_builder.line_to(point(10.0, 0.0));
+/// path_builder.line_to(point(0.0, 10.0));
+/// let path = path_builder.build();
+///
+/// // Generate the outline of the stroke.
+/// let outline = stroke_outline(&path, &StrokeOptions::default().with_line_width(2.0));
+///
+/// // Fill it with the non-zero fill rule.
+/// let mut buffers: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+/// FillTessellator::new().tessellate_path(
+///     &outline,
+///     &FillOptions::non_zero(),
+///     &mut simple_builder(&mut buffers),
+/// ).unwrap();
+/// # }
+/// ```
+pub struct StrokeOutlineBuilder {
+    first: Point,
+    current: Point,
+    segments: Vec<BezierSegment<f32>>,
+    options: StrokeOptions,
+    previous_command_was_move: bool,
+    output: Builder,
+}
+
+/// Generates the outline of the stroke of a path.
+///
+/// See [`StrokeOutlineBuilder`](struct.StrokeOutlineBuilder.html).
+pub fn stroke_outline<Input>(input: Input, options: &StrokeOptions) -> Path
+where
+    Input: IntoIterator<Item = PathEvent>,
+{
+    let mut builder = StrokeOutlineBuilder::new(options);
+    for evt in input {
+        builder.path_event(evt);
+    }
+
+    builder.build()
+}
+
+impl StrokeOutlineBuilder {
+    pub fn new(options: &StrokeOptions) -> Self {
+        let zero = Point::new(0.0, 0.0);
+        StrokeOutlineBuilder {
+            first: zero,
+            current: zero,
+            segments: Vec::new(),
+            options: *options,
+            previous_command_was_move: false,
+            output: Path::builder(),
+        }
+    }
+
+    pub fn set_options(&mut self, options: &StrokeOptions) { self.options = *options; }
+
+    fn finish(&mut self, closed: bool) {
+        let segments = mem::replace(&mut self.segments, Vec::new());
+        let half_width = self.options.line_width * 0.5;
+
+        let start = segments.iter().position(|segment| !segment.is_degenerate());
+        let end = segments.iter().rposition(|segment| !segment.is_degenerate());
+        let (start, end) = match (start, end) {
+            (Some(start), Some(end)) => (start, end),
+            _ => {
+                // Also covers the sub-paths that only have zero-length edges.
+                if self.previous_command_was_move || !segments.is_empty() {
+                    self.empty_cap(half_width);
+                }
+                return;
+            }
+        };
+
+        let join = self.options.line_join;
+        let miter_limit = self.options.miter_limit;
+        let tolerance = self.options.tolerance;
+
+        let reversed: Vec<BezierSegment<f32>> = segments.iter().rev().map(BezierSegment::flip).collect();
+        let right = OffsetCurve::new(&segments, closed, half_width, join, miter_limit, tolerance);
+        let left = OffsetCurve::new(&reversed, closed, half_width, join, miter_limit, tolerance);
+
+        if closed {
+            // Two loops, the inner one going in the opposite direction.
+            for side in &[right, left] {
+                self.output.move_to(side.first_point().unwrap());
+                side.append_to(&mut self.output);
+                self.output.close();
+            }
+            return;
+        }
+
+        let first_point = right.first_point().unwrap();
+        let end_point = segments[end].to();
+        let end_tangent = segments[end].end_tangent().normalize();
+        let start_point = segments[start].from();
+        let start_tangent = segments[start].start_tangent().normalize();
+
+        self.output.move_to(first_point);
+        right.append_to(&mut self.output);
+        self.cap(self.options.end_cap, end_point, end_tangent, left.first_point().unwrap());
+        left.append_to(&mut self.output);
+        self.cap(self.options.start_cap, start_point, -start_tangent, first_point);
+        self.output.close();
+    }
+
+    /// Adds a cap going from the current position to the provided point around the end
+    /// of a sub-path.
+    fn cap(&mut self, cap: LineCap, center: Point, direction: Vector, to: Point) {
+        let half_width = self.options.line_width * 0.5;
+        let from = self.output.current_position();
+        match cap {
+            LineCap::Butt => {
+                self.output.line_to(to);
+            }
+            LineCap::Square => {
+                let v = direction * half_width;
+                self.output.line_to(from + v);
+                self.output.line_to(to + v);
+                self.output.line_to(to);
+            }
+            LineCap::Round => {
+                let start = from - center;
+                let sweep = if start.cross(direction) > 0.0 { PI } else { -PI };
+                let arc = Arc {
+                    center,
+                    radii: vector(half_width, half_width),
+                    start_angle: EuclidAngle::radians(start.y.atan2(start.x)),
+                    sweep_angle: EuclidAngle::radians(sweep),
+                    x_rotation: EuclidAngle::radians(0.0),
+                };
+                let output = &mut self.output;
+                arc.for_each_quadratic_bezier(&mut |curve| {
+                    output.quadratic_bezier_to(curve.ctrl, curve.to);
+                });
+                self.output.line_to(to);
+            }
+        }
+    }
+
+    /// A sub-path without any edge only shows its caps.
+    fn empty_cap(&mut self, half_width: f32) {
+        let center = self.first;
+        match self.options.start_cap {
+            LineCap::Square => {
+                self.output.move_to(center + vector(-half_width, -half_width));
+                self.output.line_to(center + vector(-half_width, half_width));
+                self.output.line_to(center + vector(half_width, half_width));
+                self.output.line_to(center + vector(half_width, -half_width));
+                self.output.close();
+            }
+            LineCap::Round => {
+                let arc = Arc {
+                    center,
+                    radii: vector(half_width, half_width),
+                    start_angle: EuclidAngle::radians(0.0),
+                    sweep_angle: EuclidAngle::radians(-2.0 * PI),
+                    x_rotation: EuclidAngle::radians(0.0),
+                };
+                self.output.move_to(arc.from());
+                let output = &mut self.output;
+                arc.for_each_quadratic_bezier(&mut |curve| {
+                    output.quadratic_bezier_to(curve.ctrl, curve.to);
+                });
+                self.output.close();
+            }
+            LineCap::Butt => {}
+        }
+    }
+}
+
+impl Build for StrokeOutlineBuilder {
+    type PathType = Path;
+
+    fn build(mut self) -> Path {
+        self.finish(false);
+        self.output.build()
+    }
+
+    fn build_and_reset(&mut self) -> Path {
+        self.finish(false);
+        self.first = Point::new(0.0, 0.0);
+        self.current = Point::new(0.0, 0.0);
+        self.previous_command_was_move = false;
+        self.output.build_and_reset()
+    }
+}
+
+impl FlatPathBuilder for StrokeOutlineBuilder {
+    fn move_to(&mut self, to: Point) {
+        self.finish(false);
+
+        self.first = to;
+        self.current = to;
+        self.previous_command_was_move = true;
+    }
+
+    fn line_to(&mut self, to: Point) {
+        self.previous_command_was_move = false;
+        self.segments.push(BezierSegment::Linear(LineSegment { from: self.current, to }));
+        self.current = to;
+    }
+
+    fn close(&mut self) {
+        let first = self.first;
+        self.segments.push(BezierSegment::Linear(LineSegment { from: self.current, to: first }));
+        self.finish(true);
+        self.current = first;
+        self.previous_command_was_move = false;
+    }
+
+    fn current_position(&self) -> Point { self.current }
+}
+
+impl PathBuilder for StrokeOutlineBuilder {
+    fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
+        self.previous_command_was_move = false;
+        self.segments.push(BezierSegment::Quadratic(QuadraticBezierSegment {
+            from: self.current,
+            ctrl,
+            to,
+        }));
+        self.current = to;
+    }
+
+    fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+        self.previous_command_was_move = false;
+        self.segments.push(BezierSegment::Cubic(CubicBezierSegment {
+            from: self.current,
+            ctrl1,
+            ctrl2,
+            to,
+        }));
+        self.current = to;
+    }
+
+    fn arc(
+        &mut self,
+        center: Point,
+        radii: Vector,
+        sweep_angle: Angle,
+        x_rotation: Angle
+    ) {
+        self.previous_command_was_move = false;
+        let start_angle = (self.current - center).angle_from_x_axis() - x_rotation;
+        let segments = &mut self.segments;
+        Arc {
+            center,
+            radii,
+            start_angle,
+            sweep_angle,
+            x_rotation,
+        }.for_each_quadratic_bezier(&mut |curve| {
+            segments.push(BezierSegment::Quadratic(*curve));
+        });
+        self.current = segments.last().map(|segment| segment.to()).unwrap_or(self.current);
+    }
+}
+
+#[cfg(test)]
+fn filled_area(path: &Path) -> f32 {
+    use crate::geometry_builder::{VertexBuffers, simple_builder, triangles_area};
+    use crate::{FillTessellator, FillOptions, FillVertex};
+
+    let mut buffers: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        path.iter(),
+        &FillOptions::non_zero().with_tolerance(0.01),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    triangles_area(&buffers, |v| v.position)
+}
+
+#[cfg(test)]
+fn assert_approx_eq(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 0.05, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn outline_caps() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0).with_tolerance(0.01);
+    let area = |cap| filled_area(&stroke_outline(&path, &options.with_line_cap(cap)));
+    assert_approx_eq(area(LineCap::Butt), 20.0);
+    assert_approx_eq(area(LineCap::Square), 24.0);
+    assert_approx_eq(area(LineCap::Round), 20.0 + PI);
+
+    // A sub-path without edges.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    let path = builder.build();
+    let area = |cap| filled_area(&stroke_outline(&path, &options.with_line_cap(cap)));
+    assert_approx_eq(area(LineCap::Butt), 0.0);
+    assert_approx_eq(area(LineCap::Square), 4.0);
+    assert_approx_eq(area(LineCap::Round), PI);
+
+    // A sub-path with a zero-length edge.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(0.0, 0.0));
+    let path = builder.build();
+    let area = |cap| filled_area(&stroke_outline(&path, &options.with_line_cap(cap)));
+    assert_approx_eq(area(LineCap::Butt), 0.0);
+    assert_approx_eq(area(LineCap::Square), 4.0);
+    assert_approx_eq(area(LineCap::Round), PI);
+}
+
+#[test]
+fn outline_closed_path() {
+    use crate::LineJoin;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0).with_tolerance(0.01);
+    let area = |join| filled_area(&stroke_outline(&path, &options.with_line_join(join)));
+    assert_approx_eq(area(LineJoin::Miter), 144.0 - 64.0);
+    assert_approx_eq(area(LineJoin::Bevel), 142.0 - 64.0);
+    assert_approx_eq(area(LineJoin::Round), 140.0 + PI - 64.0);
+}
+
+#[test]
+fn outline_self_overlapping_path() {
+    use crate::geometry_builder::{VertexBuffers, simple_builder, triangles_area};
+    use crate::{StrokeTessellator, StrokeVertex};
+
+    // Two overlapping segments.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(5.0, 0.0));
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0).with_tolerance(0.01);
+
+    // The stroke tessellator covers the overlapping part twice.
+    let mut buffers: VertexBuffers<StrokeVertex, u16> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path(
+        &path,
+        &options,
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+    let stroke_area = triangles_area(&buffers, |v| v.position);
+    assert!(stroke_area > 29.0);
+
+    assert_approx_eq(filled_area(&stroke_outline(&path, &options)), 20.0);
+}
+
+#[test]
+fn outline_curves() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(10.0, 0.0), point(10.0, 10.0), point(20.0, 10.0));
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0).with_tolerance(0.01);
+    let outline = stroke_outline(&path, &options);
+
+    // Curves are preserved.
+    assert!(outline.iter().any(|evt| if let PathEvent::Cubic(..) = evt { true } else { false }));
+
+    // The area of the stroke is the length of the curve times the line width.
+    let length = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(10.0, 0.0),
+        ctrl2: point(10.0, 10.0),
+        to: point(20.0, 10.0),
+    }.approximate_length(0.001);
+    assert!((filled_area(&outline) - length * 2.0).abs() < 0.5);
+}